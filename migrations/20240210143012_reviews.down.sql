@@ -0,0 +1,10 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS restaurants_rating_average_idx;
+
+ALTER TABLE restaurants
+    DROP COLUMN IF EXISTS rating_average,
+    DROP COLUMN IF EXISTS rating_histogram,
+    DROP COLUMN IF EXISTS rating_sum,
+    DROP COLUMN IF EXISTS rating_count;
+
+DROP TABLE IF EXISTS reviews;
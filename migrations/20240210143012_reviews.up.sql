@@ -0,0 +1,29 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS reviews (
+        review_id SERIAL PRIMARY KEY,
+        restaurant_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        rating INTEGER NOT NULL,
+        content TEXT NOT NULL,
+        photo_uris TEXT[] NOT NULL DEFAULT '{}',
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        CHECK (rating BETWEEN 1 AND 5),
+        UNIQUE (restaurant_id, user_id),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE,
+        FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE
+    );
+
+ALTER TABLE restaurants
+    ADD COLUMN IF NOT EXISTS rating_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS rating_sum INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS rating_histogram INTEGER[] NOT NULL DEFAULT '{0,0,0,0,0}',
+    ADD COLUMN IF NOT EXISTS rating_average DOUBLE PRECISION GENERATED ALWAYS AS (
+        CASE
+            WHEN rating_count = 0 THEN 0
+            ELSE rating_sum::DOUBLE PRECISION / rating_count
+        END
+    ) STORED NOT NULL;
+
+CREATE INDEX IF NOT EXISTS restaurants_rating_average_idx ON restaurants (rating_average DESC, rating_count DESC);
@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS review_reports;
+
+ALTER TABLE reviews
+    DROP CONSTRAINT IF EXISTS reviews_status_check,
+    DROP COLUMN IF EXISTS moderated_at,
+    DROP COLUMN IF EXISTS owner_replied_at,
+    DROP COLUMN IF EXISTS owner_reply,
+    DROP COLUMN IF EXISTS status;
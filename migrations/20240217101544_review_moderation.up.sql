@@ -0,0 +1,21 @@
+-- Add up migration script here
+ALTER TABLE reviews
+    ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'Visible',
+    ADD COLUMN IF NOT EXISTS owner_reply TEXT,
+    ADD COLUMN IF NOT EXISTS owner_replied_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS moderated_at TIMESTAMPTZ,
+    ADD CONSTRAINT reviews_status_check CHECK (status IN ('Visible', 'Flagged', 'Hidden'));
+
+CREATE TABLE
+    IF NOT EXISTS review_reports (
+        review_report_id SERIAL PRIMARY KEY,
+        review_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL,
+        reason TEXT NOT NULL,
+        details TEXT NOT NULL DEFAULT '',
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        CHECK (reason IN ('Spam', 'Offensive', 'OffTopic', 'Fake', 'Other')),
+        UNIQUE (review_id, user_id),
+        FOREIGN KEY (review_id) REFERENCES reviews (review_id) ON DELETE CASCADE,
+        FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE
+    );
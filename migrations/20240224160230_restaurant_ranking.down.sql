@@ -0,0 +1,16 @@
+-- Add down migration script here
+DROP FUNCTION IF EXISTS haversine_km;
+
+DROP INDEX IF EXISTS restaurants_city_category_idx;
+
+DROP INDEX IF EXISTS restaurants_score_idx;
+
+ALTER TABLE restaurants
+    DROP COLUMN IF EXISTS created_at,
+    DROP COLUMN IF EXISTS score_updated_at,
+    DROP COLUMN IF EXISTS score,
+    DROP COLUMN IF EXISTS view_count,
+    DROP COLUMN IF EXISTS longitude,
+    DROP COLUMN IF EXISTS latitude,
+    DROP COLUMN IF EXISTS category,
+    DROP COLUMN IF EXISTS city;
@@ -0,0 +1,27 @@
+-- Add up migration script here
+ALTER TABLE restaurants
+    ADD COLUMN IF NOT EXISTS city TEXT,
+    ADD COLUMN IF NOT EXISTS category TEXT,
+    ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION,
+    ADD COLUMN IF NOT EXISTS view_count INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS score DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS score_updated_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS restaurants_score_idx ON restaurants (score DESC);
+
+CREATE INDEX IF NOT EXISTS restaurants_city_category_idx ON restaurants (LOWER(city), LOWER(category));
+
+CREATE OR REPLACE FUNCTION haversine_km(
+    lat1 DOUBLE PRECISION,
+    lng1 DOUBLE PRECISION,
+    lat2 DOUBLE PRECISION,
+    lng2 DOUBLE PRECISION
+) RETURNS DOUBLE PRECISION
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT 2 * 6371.0088 * ASIN(SQRT(
+        POWER(SIN(RADIANS(lat2 - lat1) / 2), 2)
+        + COS(RADIANS(lat1)) * COS(RADIANS(lat2)) * POWER(SIN(RADIANS(lng2 - lng1) / 2), 2)
+    ))
+$$;
@@ -0,0 +1,8 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS user_list_restaurants;
+
+DROP TABLE IF EXISTS user_lists;
+
+DROP TABLE IF EXISTS favorite_menu_items;
+
+DROP TABLE IF EXISTS favorite_restaurants;
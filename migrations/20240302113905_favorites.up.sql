@@ -0,0 +1,43 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS favorite_restaurants (
+        user_id INTEGER NOT NULL,
+        restaurant_id INTEGER NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (user_id, restaurant_id),
+        FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE,
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE
+    );
+
+CREATE TABLE
+    IF NOT EXISTS favorite_menu_items (
+        user_id INTEGER NOT NULL,
+        restaurant_menu_item_id INTEGER NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (user_id, restaurant_menu_item_id),
+        FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE,
+        FOREIGN KEY (restaurant_menu_item_id) REFERENCES restaurant_menu_items (restaurant_menu_item_id) ON DELETE CASCADE
+    );
+
+-- Share links are opt in and can be turned off, independently of whether
+-- the list is public.
+CREATE TABLE
+    IF NOT EXISTS user_lists (
+        user_list_id SERIAL PRIMARY KEY,
+        user_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        is_public BOOLEAN NOT NULL DEFAULT false,
+        share_token TEXT UNIQUE,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE
+    );
+
+CREATE TABLE
+    IF NOT EXISTS user_list_restaurants (
+        user_list_id INTEGER NOT NULL,
+        restaurant_id INTEGER NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (user_list_id, restaurant_id),
+        FOREIGN KEY (user_list_id) REFERENCES user_lists (user_list_id) ON DELETE CASCADE,
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE
+    );
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS restaurant_slug_redirects;
+
+DROP INDEX IF EXISTS restaurants_slug_idx;
+
+ALTER TABLE restaurants DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+ALTER TABLE restaurants ADD COLUMN IF NOT EXISTS slug VARCHAR(100);
+
+-- Existing rows get an ASCII-only slug suffixed with their id, cut to the 80
+-- characters the API accepts; new restaurants get a transliterated one from
+-- the API. The id suffix keeps them unique and clear of the reserved slugs.
+UPDATE restaurants
+SET slug = TRIM(TRAILING '-' FROM LEFT(
+        COALESCE(NULLIF(TRIM(BOTH '-' FROM REGEXP_REPLACE(LOWER(name), '[^a-z0-9]+', '-', 'g')), ''), 'restaurant'),
+        80 - LENGTH('-' || restaurant_id)
+    )) || '-' || restaurant_id
+WHERE slug IS NULL;
+
+ALTER TABLE restaurants ALTER COLUMN slug SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS restaurants_slug_idx ON restaurants (slug);
+
+CREATE TABLE
+    IF NOT EXISTS restaurant_slug_redirects (
+        slug VARCHAR(100) PRIMARY KEY NOT NULL,
+        restaurant_id INTEGER NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS restaurant_slug_redirects_restaurant_id_idx ON restaurant_slug_redirects (restaurant_id);
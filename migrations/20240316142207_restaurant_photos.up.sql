@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS restaurant_photos (
+        restaurant_photo_id SERIAL PRIMARY KEY,
+        restaurant_id INTEGER NOT NULL,
+        image_uri TEXT NOT NULL,
+        caption TEXT,
+        position INTEGER NOT NULL DEFAULT 0,
+        is_cover BOOLEAN NOT NULL DEFAULT false,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS restaurant_photos_restaurant_id_position_idx ON restaurant_photos (restaurant_id, position);
+
+CREATE UNIQUE INDEX IF NOT EXISTS restaurant_photos_cover_idx ON restaurant_photos (restaurant_id) WHERE is_cover;
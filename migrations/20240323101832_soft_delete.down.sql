@@ -0,0 +1,12 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS restaurant_menu_items_deleted_at_idx;
+
+DROP INDEX IF EXISTS restaurant_menus_deleted_at_idx;
+
+DROP INDEX IF EXISTS restaurants_deleted_at_idx;
+
+ALTER TABLE restaurant_menu_items DROP COLUMN IF EXISTS deleted_at;
+
+ALTER TABLE restaurant_menus DROP COLUMN IF EXISTS deleted_at;
+
+ALTER TABLE restaurants DROP COLUMN IF EXISTS deleted_at;
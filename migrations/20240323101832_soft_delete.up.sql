@@ -0,0 +1,12 @@
+-- Add up migration script here
+ALTER TABLE restaurants ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+ALTER TABLE restaurant_menus ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+ALTER TABLE restaurant_menu_items ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS restaurants_deleted_at_idx ON restaurants (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS restaurant_menus_deleted_at_idx ON restaurant_menus (deleted_at) WHERE deleted_at IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS restaurant_menu_items_deleted_at_idx ON restaurant_menu_items (deleted_at) WHERE deleted_at IS NOT NULL;
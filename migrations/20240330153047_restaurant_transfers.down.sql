@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS restaurant_ownership_changes;
+
+DROP TABLE IF EXISTS restaurant_transfers;
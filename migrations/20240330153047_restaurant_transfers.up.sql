@@ -0,0 +1,34 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS restaurant_transfers (
+        restaurant_transfer_id SERIAL PRIMARY KEY,
+        restaurant_id INTEGER NOT NULL,
+        from_user_id INTEGER NOT NULL,
+        to_user_id INTEGER NOT NULL,
+        initiated_by_user_id INTEGER NOT NULL,
+        status TEXT NOT NULL DEFAULT 'Pending',
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        expires_at TIMESTAMPTZ NOT NULL,
+        responded_at TIMESTAMPTZ,
+        CHECK (status IN ('Pending', 'Accepted', 'Declined', 'Cancelled', 'Expired')),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE,
+        FOREIGN KEY (from_user_id) REFERENCES users (user_id) ON DELETE CASCADE,
+        FOREIGN KEY (to_user_id) REFERENCES users (user_id) ON DELETE CASCADE,
+        FOREIGN KEY (initiated_by_user_id) REFERENCES users (user_id) ON DELETE CASCADE
+    );
+
+CREATE UNIQUE INDEX IF NOT EXISTS restaurant_transfers_pending_idx ON restaurant_transfers (restaurant_id) WHERE status = 'Pending';
+
+CREATE INDEX IF NOT EXISTS restaurant_transfers_to_user_id_idx ON restaurant_transfers (to_user_id);
+
+CREATE TABLE
+    IF NOT EXISTS restaurant_ownership_changes (
+        restaurant_ownership_change_id SERIAL PRIMARY KEY,
+        restaurant_id INTEGER NOT NULL,
+        previous_user_id INTEGER NOT NULL,
+        new_user_id INTEGER NOT NULL,
+        restaurant_transfer_id INTEGER,
+        changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE,
+        FOREIGN KEY (restaurant_transfer_id) REFERENCES restaurant_transfers (restaurant_transfer_id) ON DELETE SET NULL
+    );
@@ -0,0 +1,14 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS restaurant_menu_item_overrides;
+
+DELETE FROM restaurant_menus WHERE restaurant_id IS NULL;
+
+ALTER TABLE restaurant_menus
+DROP CONSTRAINT IF EXISTS restaurant_menus_owner_check,
+DROP COLUMN IF EXISTS restaurant_chain_id,
+ALTER COLUMN restaurant_id SET NOT NULL;
+
+ALTER TABLE restaurants
+DROP COLUMN IF EXISTS restaurant_chain_id;
+
+DROP TABLE IF EXISTS restaurant_chains;
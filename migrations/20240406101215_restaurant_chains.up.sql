@@ -0,0 +1,37 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS restaurant_chains (
+        restaurant_chain_id SERIAL PRIMARY KEY,
+        name TEXT NOT NULL,
+        user_id INTEGER NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        FOREIGN KEY (user_id) REFERENCES users (user_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS restaurant_chains_user_id_idx ON restaurant_chains (user_id);
+
+ALTER TABLE restaurants
+ADD COLUMN IF NOT EXISTS restaurant_chain_id INTEGER REFERENCES restaurant_chains (restaurant_chain_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS restaurants_restaurant_chain_id_idx ON restaurants (restaurant_chain_id);
+
+-- A menu belongs either to a single restaurant or to a chain, in which case
+-- every branch of the chain serves it.
+ALTER TABLE restaurant_menus
+ALTER COLUMN restaurant_id DROP NOT NULL,
+ADD COLUMN IF NOT EXISTS restaurant_chain_id INTEGER REFERENCES restaurant_chains (restaurant_chain_id) ON DELETE CASCADE,
+ADD CONSTRAINT restaurant_menus_owner_check CHECK (num_nonnulls (restaurant_id, restaurant_chain_id) = 1);
+
+CREATE INDEX IF NOT EXISTS restaurant_menus_restaurant_chain_id_idx ON restaurant_menus (restaurant_chain_id);
+
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_item_overrides (
+        restaurant_id INTEGER NOT NULL,
+        restaurant_menu_item_id INTEGER NOT NULL,
+        price FLOAT,
+        is_available BOOLEAN NOT NULL DEFAULT true,
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (restaurant_id, restaurant_menu_item_id),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE,
+        FOREIGN KEY (restaurant_menu_item_id) REFERENCES restaurant_menu_items (restaurant_menu_item_id) ON DELETE CASCADE
+    );
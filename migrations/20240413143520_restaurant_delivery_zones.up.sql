@@ -0,0 +1,31 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS restaurant_delivery_zones (
+        restaurant_delivery_zone_id SERIAL PRIMARY KEY,
+        restaurant_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        zone_type TEXT NOT NULL,
+        center_latitude FLOAT,
+        center_longitude FLOAT,
+        radius_km FLOAT,
+        polygon JSONB,
+        -- Precomputed so candidate zones can be found before the exact check.
+        min_latitude FLOAT NOT NULL,
+        max_latitude FLOAT NOT NULL,
+        min_longitude FLOAT NOT NULL,
+        max_longitude FLOAT NOT NULL,
+        minimum_order FLOAT NOT NULL DEFAULT 0,
+        delivery_fee FLOAT NOT NULL DEFAULT 0,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        CHECK (zone_type IN ('Radius', 'Polygon')),
+        CHECK (
+            (zone_type = 'Radius' AND center_latitude IS NOT NULL AND center_longitude IS NOT NULL AND radius_km > 0)
+            OR (zone_type = 'Polygon' AND polygon IS NOT NULL)
+        ),
+        CHECK (minimum_order >= 0 AND delivery_fee >= 0),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS restaurant_delivery_zones_restaurant_id_idx ON restaurant_delivery_zones (restaurant_id);
+
+CREATE INDEX IF NOT EXISTS restaurant_delivery_zones_bounding_box_idx ON restaurant_delivery_zones (min_latitude, max_latitude);
@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE restaurants
+DROP COLUMN IF EXISTS price_level,
+DROP COLUMN IF EXISTS average_price;
+
+DROP FUNCTION IF EXISTS price_level_for;
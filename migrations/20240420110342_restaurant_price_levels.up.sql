@@ -0,0 +1,28 @@
+-- Add up migration script here
+-- 1 = €, 2 = €€, 3 = €€€
+CREATE OR REPLACE FUNCTION price_level_for(price DOUBLE PRECISION) RETURNS SMALLINT
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT CASE WHEN price < 15 THEN 1 WHEN price < 30 THEN 2 ELSE 3 END::SMALLINT
+$$;
+
+ALTER TABLE restaurants
+ADD COLUMN IF NOT EXISTS average_price FLOAT,
+ADD COLUMN IF NOT EXISTS price_level SMALLINT;
+
+CREATE INDEX IF NOT EXISTS restaurants_price_level_idx ON restaurants (price_level);
+
+UPDATE restaurants r
+SET
+    (average_price, price_level) = (
+        SELECT AVG(prices.price), price_level_for(AVG(prices.price))
+        FROM (
+            SELECT COALESCE(rmio.price, rmi.price) AS price
+            FROM restaurant_menus rm
+            JOIN restaurant_menu_items rmi ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+            LEFT JOIN restaurant_menu_item_overrides rmio
+            ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id AND rmio.restaurant_id = r.restaurant_id
+            WHERE (rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id)
+            AND rm.is_active = true AND rm.deleted_at IS NULL AND rmi.deleted_at IS NULL
+            AND COALESCE(rmio.is_available, true)
+        ) prices
+    );
@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS restaurant_email_verifications;
+
+ALTER TABLE restaurants
+DROP COLUMN IF EXISTS is_published,
+DROP COLUMN IF EXISTS is_verified;
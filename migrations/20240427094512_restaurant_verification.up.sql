@@ -0,0 +1,18 @@
+-- Add up migration script here
+ALTER TABLE restaurants
+ADD COLUMN IF NOT EXISTS is_verified BOOLEAN NOT NULL DEFAULT false,
+ADD COLUMN IF NOT EXISTS is_published BOOLEAN NOT NULL DEFAULT false;
+
+-- Restaurants created before verification existed stay visible.
+UPDATE restaurants SET is_published = true;
+
+CREATE TABLE
+    IF NOT EXISTS restaurant_email_verifications (
+        restaurant_id INTEGER PRIMARY KEY,
+        email VARCHAR(255) NOT NULL,
+        code_hash VARCHAR(255) NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        expires_at TIMESTAMPTZ NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE
+    );
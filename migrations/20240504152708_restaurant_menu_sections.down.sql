@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE restaurant_menu_items
+DROP COLUMN IF EXISTS position,
+DROP COLUMN IF EXISTS restaurant_menu_section_id;
+
+DROP TABLE IF EXISTS restaurant_menu_sections;
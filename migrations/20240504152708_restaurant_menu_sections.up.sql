@@ -0,0 +1,28 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_sections (
+        restaurant_menu_section_id SERIAL PRIMARY KEY,
+        restaurant_menu_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        position INTEGER NOT NULL DEFAULT 0,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        FOREIGN KEY (restaurant_menu_id) REFERENCES restaurant_menus (restaurant_menu_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS restaurant_menu_sections_restaurant_menu_id_position_idx ON restaurant_menu_sections (restaurant_menu_id, position);
+
+-- Items without a section are listed before the sections.
+ALTER TABLE restaurant_menu_items
+ADD COLUMN IF NOT EXISTS restaurant_menu_section_id INTEGER,
+ADD COLUMN IF NOT EXISTS position INTEGER NOT NULL DEFAULT 0,
+ADD FOREIGN KEY (restaurant_menu_section_id) REFERENCES restaurant_menu_sections (restaurant_menu_section_id) ON DELETE SET NULL;
+
+UPDATE restaurant_menu_items
+SET position = ordered.position
+FROM (
+    SELECT restaurant_menu_item_id, ROW_NUMBER() OVER (PARTITION BY restaurant_menu_id ORDER BY restaurant_menu_item_id) - 1 AS position
+    FROM restaurant_menu_items
+) ordered
+WHERE restaurant_menu_items.restaurant_menu_item_id = ordered.restaurant_menu_item_id;
+
+CREATE INDEX IF NOT EXISTS restaurant_menu_items_restaurant_menu_id_position_idx ON restaurant_menu_items (restaurant_menu_id, position);
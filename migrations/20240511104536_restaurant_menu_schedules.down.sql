@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP FUNCTION IF EXISTS menu_available_at;
+
+DROP FUNCTION IF EXISTS menu_schedule_matches;
+
+DROP TABLE IF EXISTS restaurant_menu_schedules;
+
+ALTER TABLE restaurants
+DROP COLUMN IF EXISTS timezone;
@@ -0,0 +1,59 @@
+-- Add up migration script here
+ALTER TABLE restaurants
+ADD COLUMN IF NOT EXISTS timezone VARCHAR(64) NOT NULL DEFAULT 'UTC';
+
+-- Days are ISO numbers, 1 = Monday to 7 = Sunday. A time range ending before
+-- it starts runs past midnight and belongs to the day it started on.
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_schedules (
+        restaurant_menu_schedule_id SERIAL PRIMARY KEY,
+        restaurant_menu_id INTEGER NOT NULL,
+        days_of_week SMALLINT[] NOT NULL DEFAULT '{1,2,3,4,5,6,7}',
+        start_time TIME,
+        end_time TIME,
+        start_date DATE,
+        end_date DATE,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        CHECK ((start_time IS NULL) = (end_time IS NULL) AND start_time <> end_time),
+        CHECK (start_date IS NULL OR end_date IS NULL OR start_date <= end_date),
+        FOREIGN KEY (restaurant_menu_id) REFERENCES restaurant_menus (restaurant_menu_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS restaurant_menu_schedules_restaurant_menu_id_idx ON restaurant_menu_schedules (restaurant_menu_id);
+
+-- Whether a single schedule covers the given restaurant-local time.
+CREATE OR REPLACE FUNCTION menu_schedule_matches(
+    days_of_week SMALLINT[],
+    start_time TIME,
+    end_time TIME,
+    start_date DATE,
+    end_date DATE,
+    local_at TIMESTAMP
+) RETURNS BOOLEAN
+LANGUAGE SQL IMMUTABLE AS $$
+    SELECT EXTRACT(ISODOW FROM d.day_at)::SMALLINT = ANY(days_of_week)
+    AND (start_date IS NULL OR d.day_at::DATE >= start_date)
+    AND (end_date IS NULL OR d.day_at::DATE <= end_date)
+    AND (
+        start_time IS NULL
+        OR (start_time < end_time AND local_at::TIME >= start_time AND local_at::TIME < end_time)
+        OR (end_time < start_time AND (local_at::TIME >= start_time OR local_at::TIME < end_time))
+    )
+    FROM (
+        SELECT CASE
+            WHEN end_time < start_time AND local_at::TIME < end_time THEN local_at - INTERVAL '1 day'
+            ELSE local_at
+        END AS day_at
+    ) d
+$$;
+
+-- Menus without any schedule are always available.
+CREATE OR REPLACE FUNCTION menu_available_at(menu_id INTEGER, local_at TIMESTAMP) RETURNS BOOLEAN
+LANGUAGE SQL STABLE AS $$
+    SELECT NOT EXISTS (SELECT 1 FROM restaurant_menu_schedules WHERE restaurant_menu_id = menu_id)
+    OR EXISTS (
+        SELECT 1 FROM restaurant_menu_schedules s
+        WHERE s.restaurant_menu_id = menu_id
+        AND menu_schedule_matches(s.days_of_week, s.start_time, s.end_time, s.start_date, s.end_date, local_at)
+    )
+$$;
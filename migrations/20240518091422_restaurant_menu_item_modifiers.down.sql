@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS restaurant_menu_item_modifier_options;
+
+DROP TABLE IF EXISTS restaurant_menu_item_modifier_groups;
@@ -0,0 +1,32 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_item_modifier_groups (
+        restaurant_menu_item_modifier_group_id SERIAL PRIMARY KEY,
+        restaurant_menu_item_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        selection_type TEXT NOT NULL DEFAULT 'Single',
+        is_required BOOLEAN NOT NULL DEFAULT false,
+        min_selections INTEGER NOT NULL DEFAULT 0,
+        -- NULL means no upper bound.
+        max_selections INTEGER,
+        position INTEGER NOT NULL DEFAULT 0,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        CHECK (selection_type IN ('Single', 'Multiple')),
+        CHECK (min_selections >= 0 AND (max_selections IS NULL OR max_selections >= GREATEST(min_selections, 1))),
+        FOREIGN KEY (restaurant_menu_item_id) REFERENCES restaurant_menu_items (restaurant_menu_item_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS restaurant_menu_item_modifier_groups_item_id_idx ON restaurant_menu_item_modifier_groups (restaurant_menu_item_id, position);
+
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_item_modifier_options (
+        restaurant_menu_item_modifier_option_id SERIAL PRIMARY KEY,
+        restaurant_menu_item_modifier_group_id INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        price_delta FLOAT NOT NULL DEFAULT 0,
+        position INTEGER NOT NULL DEFAULT 0,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        FOREIGN KEY (restaurant_menu_item_modifier_group_id) REFERENCES restaurant_menu_item_modifier_groups (restaurant_menu_item_modifier_group_id) ON DELETE CASCADE
+    );
+
+CREATE INDEX IF NOT EXISTS restaurant_menu_item_modifier_options_group_id_idx ON restaurant_menu_item_modifier_options (restaurant_menu_item_modifier_group_id, position);
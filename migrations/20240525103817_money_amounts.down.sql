@@ -0,0 +1,43 @@
+-- Add down migration script here
+DROP FUNCTION IF EXISTS price_level_for (DOUBLE PRECISION, TEXT);
+
+CREATE OR REPLACE FUNCTION price_level_for(price DOUBLE PRECISION) RETURNS SMALLINT
+LANGUAGE SQL IMMUTABLE STRICT AS $$
+    SELECT CASE WHEN price < 15 THEN 1 WHEN price < 30 THEN 2 ELSE 3 END::SMALLINT
+$$;
+
+DROP FUNCTION IF EXISTS menu_money;
+
+DROP TYPE IF EXISTS money_amount;
+
+ALTER TABLE restaurant_menu_item_modifier_options
+RENAME COLUMN price_delta_minor TO price_delta;
+
+ALTER TABLE restaurant_menu_item_modifier_options
+ALTER COLUMN price_delta TYPE FLOAT USING price_delta / 100.0;
+
+ALTER TABLE restaurant_menu_item_overrides
+DROP CONSTRAINT IF EXISTS restaurant_menu_item_overrides_price_minor_check;
+
+ALTER TABLE restaurant_menu_item_overrides
+RENAME COLUMN price_minor TO price;
+
+ALTER TABLE restaurant_menu_item_overrides
+ALTER COLUMN price TYPE FLOAT USING price / 100.0;
+
+ALTER TABLE restaurant_menu_items
+DROP CONSTRAINT IF EXISTS restaurant_menu_items_price_minor_check;
+
+ALTER TABLE restaurant_menu_items
+RENAME COLUMN price_minor TO price;
+
+ALTER TABLE restaurant_menu_items
+ALTER COLUMN price TYPE FLOAT USING price / 100.0;
+
+ALTER TABLE restaurant_chains
+DROP COLUMN IF EXISTS currency;
+
+ALTER TABLE restaurants
+DROP COLUMN IF EXISTS currency;
+
+DROP TABLE IF EXISTS currencies;
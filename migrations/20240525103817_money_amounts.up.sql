@@ -0,0 +1,112 @@
+-- Add up migration script here
+-- ISO 4217 currencies, exponent is the number of minor unit digits
+-- (2 for EUR cents, 0 for JPY, 3 for TND millimes). Price level thresholds
+-- are in major units, set to roughly what 15 € and 30 € buy so that €€ means
+-- the same in every market.
+CREATE TABLE
+    IF NOT EXISTS currencies (
+        code TEXT PRIMARY KEY,
+        exponent SMALLINT NOT NULL,
+        moderate_price DOUBLE PRECISION NOT NULL,
+        expensive_price DOUBLE PRECISION NOT NULL,
+        CHECK (code ~ '^[A-Z]{3}$'),
+        CHECK (exponent BETWEEN 0 AND 4),
+        CHECK (0 < moderate_price AND moderate_price < expensive_price)
+    );
+
+INSERT INTO
+    currencies (code, exponent, moderate_price, expensive_price)
+VALUES
+    ('AED', 2, 60, 120),
+    ('AUD', 2, 25, 50),
+    ('BHD', 3, 6, 12),
+    ('BRL', 2, 90, 180),
+    ('CAD', 2, 22, 45),
+    ('CHF', 2, 15, 30),
+    ('CNY', 2, 120, 240),
+    ('CZK', 2, 375, 750),
+    ('DKK', 2, 110, 220),
+    ('DZD', 2, 2200, 4400),
+    ('EGP', 2, 800, 1600),
+    ('EUR', 2, 15, 30),
+    ('GBP', 2, 13, 26),
+    ('HUF', 2, 6000, 12000),
+    ('INR', 2, 1350, 2700),
+    ('JOD', 3, 12, 24),
+    ('JPY', 0, 2500, 5000),
+    ('KRW', 0, 22000, 45000),
+    ('KWD', 3, 5, 10),
+    ('MAD', 2, 160, 320),
+    ('MXN', 2, 300, 600),
+    ('NOK', 2, 175, 350),
+    ('NZD', 2, 27, 54),
+    ('OMR', 3, 6, 12),
+    ('PLN', 2, 65, 130),
+    ('QAR', 2, 60, 120),
+    ('SAR', 2, 60, 120),
+    ('SEK', 2, 170, 340),
+    ('TND', 3, 50, 100),
+    ('TRY', 2, 550, 1100),
+    ('USD', 2, 16, 32),
+    ('ZAR', 2, 300, 600) ON CONFLICT (code) DO NOTHING;
+
+-- Chain menus are priced in the chain currency, branches must share it.
+ALTER TABLE restaurants
+ADD COLUMN IF NOT EXISTS currency TEXT NOT NULL DEFAULT 'EUR' REFERENCES currencies (code);
+
+ALTER TABLE restaurant_chains
+ADD COLUMN IF NOT EXISTS currency TEXT NOT NULL DEFAULT 'EUR' REFERENCES currencies (code);
+
+-- Existing prices were all entered in euros.
+ALTER TABLE restaurant_menu_items
+ALTER COLUMN price TYPE BIGINT USING ROUND(price * 100)::BIGINT;
+
+ALTER TABLE restaurant_menu_items
+RENAME COLUMN price TO price_minor;
+
+ALTER TABLE restaurant_menu_items
+ADD CONSTRAINT restaurant_menu_items_price_minor_check CHECK (price_minor >= 0);
+
+ALTER TABLE restaurant_menu_item_overrides
+ALTER COLUMN price TYPE BIGINT USING ROUND(price * 100)::BIGINT;
+
+ALTER TABLE restaurant_menu_item_overrides
+RENAME COLUMN price TO price_minor;
+
+ALTER TABLE restaurant_menu_item_overrides
+ADD CONSTRAINT restaurant_menu_item_overrides_price_minor_check CHECK (price_minor >= 0);
+
+ALTER TABLE restaurant_menu_item_modifier_options
+ALTER COLUMN price_delta DROP DEFAULT;
+
+ALTER TABLE restaurant_menu_item_modifier_options
+ALTER COLUMN price_delta TYPE BIGINT USING ROUND(price_delta * 100)::BIGINT;
+
+ALTER TABLE restaurant_menu_item_modifier_options
+RENAME COLUMN price_delta TO price_delta_minor;
+
+ALTER TABLE restaurant_menu_item_modifier_options
+ALTER COLUMN price_delta_minor SET DEFAULT 0;
+
+CREATE TYPE money_amount AS (amount_minor BIGINT, currency TEXT, exponent SMALLINT);
+
+-- Pairs an amount with the currency of the restaurant or chain owning the menu.
+CREATE OR REPLACE FUNCTION menu_money(amount_minor BIGINT, menu_id INTEGER) RETURNS money_amount
+LANGUAGE SQL STABLE STRICT AS $$
+    SELECT ROW(amount_minor, currencies.code, currencies.exponent)::money_amount
+    FROM restaurant_menus rm
+    LEFT JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+    LEFT JOIN restaurant_chains rc ON rc.restaurant_chain_id = rm.restaurant_chain_id
+    JOIN currencies ON currencies.code = COALESCE(r.currency, rc.currency)
+    WHERE rm.restaurant_menu_id = menu_id
+$$;
+
+DROP FUNCTION IF EXISTS price_level_for (DOUBLE PRECISION);
+
+-- 1 = €, 2 = €€, 3 = €€€, price is in major units of the currency
+CREATE OR REPLACE FUNCTION price_level_for(price DOUBLE PRECISION, currency TEXT) RETURNS SMALLINT
+LANGUAGE SQL STABLE STRICT AS $$
+    SELECT CASE WHEN price < moderate_price THEN 1 WHEN price < expensive_price THEN 2 ELSE 3 END::SMALLINT
+    FROM currencies
+    WHERE code = currency
+$$;
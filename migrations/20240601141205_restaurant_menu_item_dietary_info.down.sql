@@ -0,0 +1,13 @@
+-- Add down migration script here
+ALTER TABLE restaurant_menu_items
+DROP CONSTRAINT IF EXISTS restaurant_menu_items_nutrition_check,
+DROP CONSTRAINT IF EXISTS restaurant_menu_items_spicy_level_check,
+DROP CONSTRAINT IF EXISTS restaurant_menu_items_diet_labels_check,
+DROP CONSTRAINT IF EXISTS restaurant_menu_items_allergens_check,
+DROP COLUMN IF EXISTS fat_grams,
+DROP COLUMN IF EXISTS carbohydrate_grams,
+DROP COLUMN IF EXISTS protein_grams,
+DROP COLUMN IF EXISTS calories,
+DROP COLUMN IF EXISTS spicy_level,
+DROP COLUMN IF EXISTS diet_labels,
+DROP COLUMN IF EXISTS allergens;
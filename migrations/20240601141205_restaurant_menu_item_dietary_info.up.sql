@@ -0,0 +1,20 @@
+-- Add up migration script here
+-- Allergens are the 14 the EU requires to be declared.
+ALTER TABLE restaurant_menu_items
+ADD COLUMN IF NOT EXISTS allergens TEXT[] NOT NULL DEFAULT '{}',
+ADD COLUMN IF NOT EXISTS diet_labels TEXT[] NOT NULL DEFAULT '{}',
+ADD COLUMN IF NOT EXISTS spicy_level SMALLINT,
+ADD COLUMN IF NOT EXISTS calories INTEGER,
+ADD COLUMN IF NOT EXISTS protein_grams FLOAT,
+ADD COLUMN IF NOT EXISTS carbohydrate_grams FLOAT,
+ADD COLUMN IF NOT EXISTS fat_grams FLOAT,
+ADD CONSTRAINT restaurant_menu_items_allergens_check CHECK (
+    allergens <@ ARRAY['celery', 'crustaceans', 'eggs', 'fish', 'gluten', 'lupin', 'milk', 'molluscs', 'mustard', 'nuts', 'peanuts', 'sesame', 'soybeans', 'sulphites']
+),
+ADD CONSTRAINT restaurant_menu_items_diet_labels_check CHECK (
+    diet_labels <@ ARRAY['gluten_free', 'halal', 'vegan', 'vegetarian']
+),
+ADD CONSTRAINT restaurant_menu_items_spicy_level_check CHECK (spicy_level BETWEEN 0 AND 3),
+ADD CONSTRAINT restaurant_menu_items_nutrition_check CHECK (
+    calories >= 0 AND protein_grams >= 0 AND carbohydrate_grams >= 0 AND fat_grams >= 0
+);
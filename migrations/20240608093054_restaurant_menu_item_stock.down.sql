@@ -0,0 +1,13 @@
+-- Add down migration script here
+DROP FUNCTION IF EXISTS stock_reset_due;
+
+DROP FUNCTION IF EXISTS menu_item_availability;
+
+ALTER TABLE restaurant_menu_items
+DROP CONSTRAINT IF EXISTS restaurant_menu_items_stock_check,
+DROP CONSTRAINT IF EXISTS restaurant_menu_items_availability_check,
+DROP COLUMN IF EXISTS stock_reset_on,
+DROP COLUMN IF EXISTS remaining_stock,
+DROP COLUMN IF EXISTS daily_stock,
+DROP COLUMN IF EXISTS sold_out_until,
+DROP COLUMN IF EXISTS availability;
@@ -0,0 +1,36 @@
+-- Add up migration script here
+-- Items without a daily_stock are not counted. remaining_stock is refilled
+-- from daily_stock once per local day, stock_reset_on is that local date.
+ALTER TABLE restaurant_menu_items
+ADD COLUMN IF NOT EXISTS availability TEXT NOT NULL DEFAULT 'available',
+ADD COLUMN IF NOT EXISTS sold_out_until TIMESTAMPTZ,
+ADD COLUMN IF NOT EXISTS daily_stock INTEGER,
+ADD COLUMN IF NOT EXISTS remaining_stock INTEGER,
+ADD COLUMN IF NOT EXISTS stock_reset_on DATE,
+ADD CONSTRAINT restaurant_menu_items_availability_check CHECK (
+    availability IN ('available', 'sold_out', 'hidden')
+    AND (sold_out_until IS NULL OR availability = 'sold_out')
+),
+ADD CONSTRAINT restaurant_menu_items_stock_check CHECK (
+    daily_stock >= 0 AND remaining_stock >= 0
+    AND (daily_stock IS NOT NULL OR remaining_stock IS NULL)
+);
+
+-- What customers see: a sold out item comes back once sold_out_until has
+-- passed, and a counted item is sold out when nothing is left.
+CREATE OR REPLACE FUNCTION menu_item_availability(availability TEXT, sold_out_until TIMESTAMPTZ, remaining_stock INTEGER) RETURNS TEXT
+LANGUAGE SQL STABLE AS $$
+    SELECT CASE
+        WHEN availability = 'hidden' THEN 'hidden'
+        WHEN availability = 'sold_out' AND (sold_out_until IS NULL OR sold_out_until > NOW()) THEN 'sold_out'
+        WHEN remaining_stock = 0 THEN 'sold_out'
+        ELSE 'available'
+    END
+$$;
+
+-- Whether a counted item last refilled on stock_reset_on is due for a refill,
+-- which happens once a new day has started in the given timezone.
+CREATE OR REPLACE FUNCTION stock_reset_due(stock_reset_on DATE, timezone TEXT, at TIMESTAMPTZ) RETURNS BOOLEAN
+LANGUAGE SQL STABLE AS $$
+    SELECT stock_reset_on IS NULL OR stock_reset_on < (at AT TIME ZONE timezone)::DATE
+$$;
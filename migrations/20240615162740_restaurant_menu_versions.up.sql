@@ -0,0 +1,30 @@
+-- Add up migration script here
+-- Snapshots of a menu name and items. A menu has at most one draft being
+-- edited and one published version, the ones published before are archived.
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_versions (
+        restaurant_menu_version_id SERIAL PRIMARY KEY,
+        restaurant_menu_id INTEGER NOT NULL,
+        version_number INTEGER,
+        status TEXT NOT NULL DEFAULT 'draft',
+        content JSONB NOT NULL,
+        restored_from_version_id INTEGER,
+        created_by INTEGER,
+        published_by INTEGER,
+        published_at TIMESTAMPTZ,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        CHECK (status IN ('draft', 'published', 'archived')),
+        CHECK ((status = 'draft') = (version_number IS NULL)),
+        UNIQUE (restaurant_menu_id, version_number),
+        FOREIGN KEY (restaurant_menu_id) REFERENCES restaurant_menus (restaurant_menu_id) ON DELETE CASCADE,
+        FOREIGN KEY (restored_from_version_id) REFERENCES restaurant_menu_versions (restaurant_menu_version_id) ON DELETE SET NULL,
+        FOREIGN KEY (created_by) REFERENCES users (user_id) ON DELETE SET NULL,
+        FOREIGN KEY (published_by) REFERENCES users (user_id) ON DELETE SET NULL
+    );
+
+CREATE UNIQUE INDEX IF NOT EXISTS restaurant_menu_versions_draft_idx ON restaurant_menu_versions (restaurant_menu_id)
+WHERE status = 'draft';
+
+CREATE UNIQUE INDEX IF NOT EXISTS restaurant_menu_versions_published_idx ON restaurant_menu_versions (restaurant_menu_id)
+WHERE status = 'published';
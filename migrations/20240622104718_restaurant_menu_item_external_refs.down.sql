@@ -0,0 +1,4 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS restaurant_menu_items_restaurant_menu_id_external_ref_idx;
+
+ALTER TABLE restaurant_menu_items DROP COLUMN IF EXISTS external_ref;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+-- The key an item is known by in imported files, e.g. the SKU of the
+-- platform the restaurant migrates from. Items without one go by their id.
+ALTER TABLE restaurant_menu_items ADD COLUMN IF NOT EXISTS external_ref TEXT;
+
+CREATE INDEX IF NOT EXISTS restaurant_menu_items_restaurant_menu_id_external_ref_idx ON restaurant_menu_items (restaurant_menu_id, external_ref)
+WHERE deleted_at IS NULL;
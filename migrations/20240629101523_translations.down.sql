@@ -0,0 +1,10 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS restaurant_menu_item_translations;
+
+DROP TABLE IF EXISTS restaurant_menu_translations;
+
+DROP TABLE IF EXISTS restaurant_translations;
+
+ALTER TABLE restaurants
+DROP COLUMN IF EXISTS default_locale,
+DROP COLUMN IF EXISTS description;
@@ -0,0 +1,39 @@
+-- Add up migration script here
+-- The text stored on restaurants, menus and items is in the restaurant's
+-- default_locale. Translations hold the other locales and fall back to it,
+-- a translation without a description keeps the default one.
+ALTER TABLE restaurants
+ADD COLUMN IF NOT EXISTS description TEXT NOT NULL DEFAULT '',
+ADD COLUMN IF NOT EXISTS default_locale TEXT NOT NULL DEFAULT 'en';
+
+CREATE TABLE
+    IF NOT EXISTS restaurant_translations (
+        restaurant_id INTEGER NOT NULL,
+        locale TEXT NOT NULL,
+        name TEXT NOT NULL,
+        description TEXT,
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (restaurant_id, locale),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE
+    );
+
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_translations (
+        restaurant_menu_id INTEGER NOT NULL,
+        locale TEXT NOT NULL,
+        name TEXT NOT NULL,
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (restaurant_menu_id, locale),
+        FOREIGN KEY (restaurant_menu_id) REFERENCES restaurant_menus (restaurant_menu_id) ON DELETE CASCADE
+    );
+
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_item_translations (
+        restaurant_menu_item_id INTEGER NOT NULL,
+        locale TEXT NOT NULL,
+        name TEXT NOT NULL,
+        description TEXT,
+        updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (restaurant_menu_item_id, locale),
+        FOREIGN KEY (restaurant_menu_item_id) REFERENCES restaurant_menu_items (restaurant_menu_item_id) ON DELETE CASCADE
+    );
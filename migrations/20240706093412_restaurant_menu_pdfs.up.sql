@@ -0,0 +1,14 @@
+-- Add up migration script here
+-- Last rendered PDF menu per template and language. fingerprint hashes what
+-- was printed, a request whose menu hashes differently renders it again.
+CREATE TABLE
+    IF NOT EXISTS restaurant_menu_pdfs (
+        restaurant_id INTEGER NOT NULL,
+        template TEXT NOT NULL,
+        locale TEXT NOT NULL,
+        fingerprint TEXT NOT NULL,
+        pdf BYTEA NOT NULL,
+        created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+        PRIMARY KEY (restaurant_id, template, locale),
+        FOREIGN KEY (restaurant_id) REFERENCES restaurants (restaurant_id) ON DELETE CASCADE
+    );
@@ -14,47 +14,61 @@ use crate::{
 
 use super::jwt::decode_jwt;
 
+async fn get_current_user(state: &AppState, headers: &HeaderMap) -> Option<User> {
+    let token = get_token(headers)?;
+    let claims = decode_jwt(token.to_string(), &state.env.jwt_secret)?;
+
+    sqlx::query_as!(
+        User,
+        "SELECT email,name,last_name,user_id,role,status,email_validated from users where user_id = $1 AND (status = 'Accepted' OR role = 'User' OR role = 'Admin')",
+        claims.sub
+    )
+    .fetch_one(&state.db)
+    .await
+    .ok()
+}
+
 pub async fn auth_middleware(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     mut request: Request,
     next: Next,
 ) -> Result<Response, AppResult<()>> {
-    match get_token(&headers) {
-        Some(token) => match decode_jwt(token.to_string(), &state.env.jwt_secret) {
-            Some(claims) => {
-                let res = sqlx::query_as!(
-                    User,
-                    "SELECT email,name,last_name,user_id,role,status,email_validated from users where user_id = $1 AND (status = 'Accepted' OR role = 'User' OR role = 'Admin')",
-                    claims.sub
-                )
-                .fetch_one(&state.db)
-                .await;
+    if get_token(&headers).is_none() {
+        return Err(AppResult::Error(
+            StatusCode::UNAUTHORIZED,
+            String::from("Unauthorized! Missing token."),
+        ));
+    }
 
-                match res {
-                    Ok(user) => {
-                        request.extensions_mut().insert(Arc::new(user));
-                        let response = next.run(request).await;
-                        Ok(response)
-                    }
-                    Err(_) => Err(AppResult::Error(
-                        StatusCode::UNAUTHORIZED,
-                        String::from("Unauthorized! Invalid token."),
-                    )),
-                }
-            }
-            None => Err(AppResult::Error(
-                StatusCode::UNAUTHORIZED,
-                String::from("Unauthorized! Invalid token."),
-            )),
-        },
+    match get_current_user(&state, &headers).await {
+        Some(user) => {
+            request.extensions_mut().insert(Arc::new(user));
+            let response = next.run(request).await;
+            Ok(response)
+        }
         None => Err(AppResult::Error(
             StatusCode::UNAUTHORIZED,
-            String::from("Unauthorized! Missing token."),
+            String::from("Unauthorized! Invalid token."),
         )),
     }
 }
 
+// Same as `auth_middleware` but lets anonymous callers through, so public
+// routes can personalize their response when a valid token is sent.
+pub async fn optional_auth_middleware(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Some(user) = get_current_user(&state, &headers).await {
+        request.extensions_mut().insert(Arc::new(user));
+    }
+
+    next.run(request).await
+}
+
 fn get_token(headers: &HeaderMap) -> Option<&str> {
     if let Some(header) = headers.get("Authorization") {
         if let Ok(value) = header.to_str() {
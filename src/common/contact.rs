@@ -0,0 +1,381 @@
+struct PhoneCountry {
+    iso_code: &'static str,
+    calling_code: &'static str,
+    // Allowed lengths of the national significant number, trunk prefix excluded.
+    min_length: usize,
+    max_length: usize,
+    // Dropped from national numbers, e.g. the 0 in 06 12 34 56 78.
+    trunk_prefix: Option<char>,
+}
+
+const PHONE_COUNTRIES: [PhoneCountry; 24] = [
+    PhoneCountry {
+        iso_code: "AE",
+        calling_code: "971",
+        min_length: 8,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "AT",
+        calling_code: "43",
+        min_length: 4,
+        max_length: 13,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "BE",
+        calling_code: "32",
+        min_length: 8,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "CA",
+        calling_code: "1",
+        min_length: 10,
+        max_length: 10,
+        trunk_prefix: None,
+    },
+    PhoneCountry {
+        iso_code: "CH",
+        calling_code: "41",
+        min_length: 9,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "DE",
+        calling_code: "49",
+        min_length: 6,
+        max_length: 13,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "DZ",
+        calling_code: "213",
+        min_length: 8,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "EG",
+        calling_code: "20",
+        min_length: 9,
+        max_length: 10,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "ES",
+        calling_code: "34",
+        min_length: 9,
+        max_length: 9,
+        trunk_prefix: None,
+    },
+    PhoneCountry {
+        iso_code: "FR",
+        calling_code: "33",
+        min_length: 9,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "GB",
+        calling_code: "44",
+        min_length: 9,
+        max_length: 10,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "GR",
+        calling_code: "30",
+        min_length: 10,
+        max_length: 10,
+        trunk_prefix: None,
+    },
+    PhoneCountry {
+        iso_code: "IE",
+        calling_code: "353",
+        min_length: 7,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "IT",
+        calling_code: "39",
+        min_length: 6,
+        max_length: 11,
+        trunk_prefix: None,
+    },
+    PhoneCountry {
+        iso_code: "LU",
+        calling_code: "352",
+        min_length: 4,
+        max_length: 11,
+        trunk_prefix: None,
+    },
+    PhoneCountry {
+        iso_code: "MA",
+        calling_code: "212",
+        min_length: 9,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "NL",
+        calling_code: "31",
+        min_length: 9,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "PL",
+        calling_code: "48",
+        min_length: 9,
+        max_length: 9,
+        trunk_prefix: None,
+    },
+    PhoneCountry {
+        iso_code: "PT",
+        calling_code: "351",
+        min_length: 9,
+        max_length: 9,
+        trunk_prefix: None,
+    },
+    PhoneCountry {
+        iso_code: "SA",
+        calling_code: "966",
+        min_length: 8,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "SE",
+        calling_code: "46",
+        min_length: 7,
+        max_length: 9,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "TN",
+        calling_code: "216",
+        min_length: 8,
+        max_length: 8,
+        trunk_prefix: None,
+    },
+    PhoneCountry {
+        iso_code: "TR",
+        calling_code: "90",
+        min_length: 10,
+        max_length: 10,
+        trunk_prefix: Some('0'),
+    },
+    PhoneCountry {
+        iso_code: "US",
+        calling_code: "1",
+        min_length: 10,
+        max_length: 10,
+        trunk_prefix: None,
+    },
+];
+
+// E.164 caps numbers at 15 digits, calling code included.
+const MAX_E164_DIGITS: usize = 15;
+const MIN_E164_DIGITS: usize = 8;
+
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+// Deliberately pragmatic: one @, a sane local part and a dotted domain made
+// of letters, digits and inner hyphens.
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+
+    let is_valid_local = !local.is_empty()
+        && local.len() <= 64
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+/=?^_`{|}~.-".contains(c));
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    let is_valid_domain = domain.len() <= 253
+        && labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && labels
+            .last()
+            .is_some_and(|tld| tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()));
+
+    is_valid_local && is_valid_domain
+}
+
+fn find_country(iso_code: &str) -> Option<&'static PhoneCountry> {
+    PHONE_COUNTRIES
+        .iter()
+        .find(|country| country.iso_code.eq_ignore_ascii_case(iso_code.trim()))
+}
+
+pub fn is_known_country(iso_code: &str) -> bool {
+    find_country(iso_code).is_some()
+}
+
+fn is_valid_international(digits: &str) -> bool {
+    if !(MIN_E164_DIGITS..=MAX_E164_DIGITS).contains(&digits.len()) || digits.starts_with('0') {
+        return false;
+    }
+
+    // Numbers under a known calling code also get their national length
+    // checked, unknown codes only the overall E.164 length.
+    let matching_countries: Vec<&PhoneCountry> = PHONE_COUNTRIES
+        .iter()
+        .filter(|country| digits.starts_with(country.calling_code))
+        .collect();
+
+    matching_countries.is_empty()
+        || matching_countries.iter().any(|country| {
+            let national_length = digits.len() - country.calling_code.len();
+            (country.min_length..=country.max_length).contains(&national_length)
+        })
+}
+
+// Returns the number as +<digits>. International input (+ or 00) is taken as
+// is, national input needs the country it was dialled in.
+pub fn normalize_phone(phone: &str, country: Option<&str>) -> Option<String> {
+    let phone = phone.trim();
+    let is_international = phone.starts_with('+');
+
+    if !phone
+        .chars()
+        .enumerate()
+        .all(|(index, c)| c.is_ascii_digit() || " -.()/".contains(c) || (c == '+' && index == 0))
+    {
+        return None;
+    }
+
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    let international_digits = if is_international {
+        digits
+    } else if let Some(digits) = digits.strip_prefix("00") {
+        digits.to_string()
+    } else {
+        let country = find_country(country?)?;
+        let national = match country.trunk_prefix {
+            Some(trunk_prefix) => digits.strip_prefix(trunk_prefix).unwrap_or(&digits),
+            None => &digits,
+        };
+        if !(country.min_length..=country.max_length).contains(&national.len()) {
+            return None;
+        }
+        format!("{}{}", country.calling_code, national)
+    };
+
+    is_valid_international(&international_digits).then(|| format!("+{}", international_digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_email_trims_and_lowercases() {
+        assert_eq!(normalize_email("  Owner@Example.COM "), "owner@example.com");
+    }
+
+    #[test]
+    fn is_valid_email_accepts_common_addresses() {
+        assert!(is_valid_email("owner@example.com"));
+        assert!(is_valid_email("first.last+menu@mail.example.co.uk"));
+        assert!(is_valid_email("o'brien@my-restaurant.ie"));
+    }
+
+    #[test]
+    fn is_valid_email_rejects_malformed_addresses() {
+        for email in [
+            "",
+            "owner",
+            "@example.com",
+            "owner@",
+            "owner@localhost",
+            "owner@@example.com",
+            ".owner@example.com",
+            "ow..ner@example.com",
+            "owner@-example.com",
+            "owner@example..com",
+            "owner@example.c",
+            "owner@example.c0m",
+            "own er@example.com",
+        ] {
+            assert!(!is_valid_email(email), "{}", email);
+        }
+    }
+
+    #[test]
+    fn is_known_country_ignores_case() {
+        assert!(is_known_country("fr"));
+        assert!(is_known_country(" TN "));
+        assert!(!is_known_country("XX"));
+    }
+
+    #[test]
+    fn normalize_phone_keeps_international_numbers() {
+        assert_eq!(
+            normalize_phone("+33 6 12 34 56 78", None),
+            Some(String::from("+33612345678"))
+        );
+        assert_eq!(
+            normalize_phone("0033 (6) 12-34-56-78", None),
+            Some(String::from("+33612345678"))
+        );
+        // Unknown calling codes only get the E.164 length check.
+        assert_eq!(
+            normalize_phone("+886 912 345 678", None),
+            Some(String::from("+886912345678"))
+        );
+    }
+
+    #[test]
+    fn normalize_phone_uses_the_country_for_national_numbers() {
+        assert_eq!(
+            normalize_phone("06 12 34 56 78", Some("FR")),
+            Some(String::from("+33612345678"))
+        );
+        assert_eq!(
+            normalize_phone("71 234 567", Some("tn")),
+            Some(String::from("+21671234567"))
+        );
+        assert_eq!(
+            normalize_phone("(212) 555-0123", Some("US")),
+            Some(String::from("+12125550123"))
+        );
+        assert_eq!(normalize_phone("06 12 34 56 78", None), None);
+        assert_eq!(normalize_phone("06 12 34 56 78", Some("XX")), None);
+    }
+
+    #[test]
+    fn normalize_phone_rejects_invalid_numbers() {
+        for (phone, country) in [
+            ("", Some("FR")),
+            ("+33 6 12 34", None),
+            ("+33 6 12 34 56 78 90", None),
+            ("06 12 34 56", Some("FR")),
+            ("+0 612 345 678", None),
+            ("+1234567890123456", None),
+            ("06 12 34 56 78 ext 9", Some("FR")),
+            ("06+12345678", Some("FR")),
+        ] {
+            assert_eq!(normalize_phone(phone, country), None, "{}", phone);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// RFC 4180 CSV: comma separated, fields with commas, quotes or line breaks
+// are quoted and their quotes doubled.
+
+// Records with the line they start on, 1 for the header. Blank lines are
+// skipped. Err holds the line of an unterminated quote.
+pub fn parse_csv(text: &str) -> Result<Vec<(usize, Vec<String>)>, usize> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut is_quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if is_quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => is_quoted = false,
+                _ => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => is_quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if !(record.len() == 1 && record[0].is_empty()) {
+                    records.push((record_line, std::mem::take(&mut record)));
+                }
+                record.clear();
+                line += 1;
+                record_line = line;
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if is_quoted {
+        return Err(record_line);
+    }
+
+    record.push(field);
+    if !(record.len() == 1 && record[0].is_empty()) {
+        records.push((record_line, record));
+    }
+
+    Ok(records)
+}
+
+pub fn write_csv_record(csv: &mut String, fields: &[&str]) {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            csv.push(',');
+        }
+
+        if field.contains([',', '"', '\n', '\r']) {
+            csv.push('"');
+            csv.push_str(&field.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(field);
+        }
+    }
+    csv.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(fields: &[&str]) -> Vec<String> {
+        fields.iter().map(|field| field.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_csv_splits_records_and_fields() {
+        assert_eq!(
+            parse_csv("name,price\nPizza,9.50\r\nPasta,8\n"),
+            Ok(vec![
+                (1, record(&["name", "price"])),
+                (2, record(&["Pizza", "9.50"])),
+                (3, record(&["Pasta", "8"])),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_csv_reads_quoted_fields() {
+        assert_eq!(
+            parse_csv("name,description\n\"Pizza, large\",\"The \"\"best\"\"\nin town\"\nPasta,\n"),
+            Ok(vec![
+                (1, record(&["name", "description"])),
+                (2, record(&["Pizza, large", "The \"best\"\nin town"])),
+                (4, record(&["Pasta", ""])),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_csv_skips_bom_and_blank_lines() {
+        assert_eq!(
+            parse_csv("\u{feff}name\n\nPizza\n\n"),
+            Ok(vec![(1, record(&["name"])), (3, record(&["Pizza"]))])
+        );
+        assert_eq!(parse_csv(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn parse_csv_reports_unterminated_quotes() {
+        assert_eq!(parse_csv("name\nPizza\n\"Pasta,8\n"), Err(3));
+    }
+
+    #[test]
+    fn write_csv_record_quotes_when_needed() {
+        let mut csv = String::new();
+        write_csv_record(&mut csv, &["Pizza", "9.50"]);
+        write_csv_record(&mut csv, &["Pizza, large", "The \"best\"\nin town", ""]);
+
+        assert_eq!(
+            csv,
+            "Pizza,9.50\r\n\"Pizza, large\",\"The \"\"best\"\"\nin town\",\r\n"
+        );
+    }
+
+    #[test]
+    fn write_csv_record_round_trips() {
+        let fields = ["a,b", "say \"hi\"", "line\r\nbreak", "plain"];
+        let mut csv = String::new();
+        write_csv_record(&mut csv, &fields);
+
+        assert_eq!(parse_csv(&csv), Ok(vec![(1, record(&fields))]));
+    }
+}
@@ -0,0 +1,67 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use axum::http::{header::IF_NONE_MATCH, HeaderMap};
+
+// Weak tag over the serialized body, so it changes whenever anything the
+// client would see changes.
+pub fn etag_for(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("W/\"{:016x}\"", hasher.finish())
+}
+
+pub fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag.trim_start_matches("W/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn if_none_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn etag_follows_the_body() {
+        assert_eq!(etag_for(b"{\"menus\":[]}"), etag_for(b"{\"menus\":[]}"));
+        assert_ne!(etag_for(b"{\"menus\":[]}"), etag_for(b"{\"menus\":[1]}"));
+        assert!(etag_for(b"").starts_with("W/\""));
+    }
+
+    #[test]
+    fn matching_tags_are_not_modified() {
+        let etag = etag_for(b"menu");
+
+        assert!(is_not_modified(&if_none_match(&etag), &etag));
+        assert!(is_not_modified(
+            &if_none_match(&format!("\"other\", {}", etag.trim_start_matches("W/"))),
+            &etag
+        ));
+        assert!(is_not_modified(&if_none_match("*"), &etag));
+    }
+
+    #[test]
+    fn missing_or_stale_tags_are_modified() {
+        let etag = etag_for(b"menu");
+
+        assert!(!is_not_modified(&HeaderMap::new(), &etag));
+        assert!(!is_not_modified(
+            &if_none_match("W/\"0000000000000000\""),
+            &etag
+        ));
+    }
+}
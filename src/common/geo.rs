@@ -0,0 +1,301 @@
+use serde_json::Value;
+
+// Same mean earth radius as the haversine_km SQL function.
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+// A degree is about 111.19 km on that sphere, rounded down so bounding boxes
+// err on the large side.
+const KM_PER_DEGREE_LATITUDE: f64 = 111.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Point {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+pub struct BoundingBox {
+    pub min_latitude: f64,
+    pub max_latitude: f64,
+    pub min_longitude: f64,
+    pub max_longitude: f64,
+}
+
+// Outer ring first, holes after, as in GeoJSON.
+pub type Polygon = Vec<Vec<Point>>;
+
+pub fn is_valid_point(latitude: f64, longitude: f64) -> bool {
+    (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)
+}
+
+// Parses "lat,lng" as used in query strings.
+pub fn parse_point(text: &str) -> Option<Point> {
+    let (latitude, longitude) = text.split_once(',')?;
+    let latitude = latitude.trim().parse::<f64>().ok()?;
+    let longitude = longitude.trim().parse::<f64>().ok()?;
+
+    is_valid_point(latitude, longitude).then_some(Point {
+        latitude,
+        longitude,
+    })
+}
+
+pub fn haversine_km(from: Point, to: Point) -> f64 {
+    let d_latitude = (to.latitude - from.latitude).to_radians();
+    let d_longitude = (to.longitude - from.longitude).to_radians();
+    let a = (d_latitude / 2.0).sin().powi(2)
+        + from.latitude.to_radians().cos()
+            * to.latitude.to_radians().cos()
+            * (d_longitude / 2.0).sin().powi(2);
+
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+// Slightly generous box around a circle, only meant for prefiltering.
+pub fn radius_bounding_box(center: Point, radius_km: f64) -> BoundingBox {
+    let d_latitude = radius_km / KM_PER_DEGREE_LATITUDE;
+    let min_latitude = (center.latitude - d_latitude).max(-90.0);
+    let max_latitude = (center.latitude + d_latitude).min(90.0);
+
+    let widest_latitude = min_latitude.abs().max(max_latitude.abs());
+    let cos_latitude = widest_latitude.to_radians().cos();
+    let (min_longitude, max_longitude) = if widest_latitude >= 89.0 {
+        (-180.0, 180.0)
+    } else {
+        let d_longitude = radius_km / (KM_PER_DEGREE_LATITUDE * cos_latitude);
+        (
+            (center.longitude - d_longitude).max(-180.0),
+            (center.longitude + d_longitude).min(180.0),
+        )
+    };
+
+    BoundingBox {
+        min_latitude,
+        max_latitude,
+        min_longitude,
+        max_longitude,
+    }
+}
+
+pub fn polygons_bounding_box(polygons: &[Polygon]) -> BoundingBox {
+    let mut bounding_box = BoundingBox {
+        min_latitude: f64::MAX,
+        max_latitude: f64::MIN,
+        min_longitude: f64::MAX,
+        max_longitude: f64::MIN,
+    };
+
+    for point in polygons
+        .iter()
+        .filter_map(|polygon| polygon.first())
+        .flatten()
+    {
+        bounding_box.min_latitude = bounding_box.min_latitude.min(point.latitude);
+        bounding_box.max_latitude = bounding_box.max_latitude.max(point.latitude);
+        bounding_box.min_longitude = bounding_box.min_longitude.min(point.longitude);
+        bounding_box.max_longitude = bounding_box.max_longitude.max(point.longitude);
+    }
+
+    bounding_box
+}
+
+fn parse_ring(value: &Value) -> Option<Vec<Point>> {
+    let mut ring = value
+        .as_array()?
+        .iter()
+        .map(|position| {
+            let position = position.as_array()?;
+            // GeoJSON positions are [longitude, latitude].
+            let longitude = position.first()?.as_f64()?;
+            let latitude = position.get(1)?.as_f64()?;
+            is_valid_point(latitude, longitude).then_some(Point {
+                latitude,
+                longitude,
+            })
+        })
+        .collect::<Option<Vec<Point>>>()?;
+
+    if ring.first() != ring.last() {
+        ring.push(*ring.first()?);
+    }
+
+    // A closed ring needs at least three distinct corners.
+    (ring.len() >= 4).then_some(ring)
+}
+
+fn parse_polygon(value: &Value) -> Option<Polygon> {
+    let rings = value
+        .as_array()?
+        .iter()
+        .map(parse_ring)
+        .collect::<Option<Polygon>>()?;
+
+    (!rings.is_empty()).then_some(rings)
+}
+
+// Accepts a Polygon or MultiPolygon geometry, bare or wrapped in a Feature.
+pub fn parse_geojson_polygons(value: &Value) -> Option<Vec<Polygon>> {
+    match value.get("type")?.as_str()? {
+        "Feature" => parse_geojson_polygons(value.get("geometry")?),
+        "Polygon" => Some(vec![parse_polygon(value.get("coordinates")?)?]),
+        "MultiPolygon" => {
+            let polygons = value
+                .get("coordinates")?
+                .as_array()?
+                .iter()
+                .map(parse_polygon)
+                .collect::<Option<Vec<Polygon>>>()?;
+            (!polygons.is_empty()).then_some(polygons)
+        }
+        _ => None,
+    }
+}
+
+// Ray casting on plain latitude/longitude, which is accurate enough at
+// delivery zone scale.
+fn ring_contains(ring: &[Point], point: Point) -> bool {
+    let mut is_inside = false;
+    let mut previous = match ring.last() {
+        Some(previous) => *previous,
+        None => return false,
+    };
+
+    for &current in ring {
+        if (current.latitude > point.latitude) != (previous.latitude > point.latitude) {
+            let crossing_longitude = (previous.longitude - current.longitude)
+                * (point.latitude - current.latitude)
+                / (previous.latitude - current.latitude)
+                + current.longitude;
+            if point.longitude < crossing_longitude {
+                is_inside = !is_inside;
+            }
+        }
+        previous = current;
+    }
+
+    is_inside
+}
+
+pub fn polygons_contain(polygons: &[Polygon], point: Point) -> bool {
+    polygons.iter().any(|polygon| match polygon.split_first() {
+        Some((outer_ring, holes)) => {
+            ring_contains(outer_ring, point) && !holes.iter().any(|hole| ring_contains(hole, point))
+        }
+        None => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn point(latitude: f64, longitude: f64) -> Point {
+        Point {
+            latitude,
+            longitude,
+        }
+    }
+
+    // A 4x4 square with a 2x2 hole in the middle, as GeoJSON [lng, lat].
+    fn square_with_hole() -> Vec<Polygon> {
+        parse_geojson_polygons(&json!({
+            "type": "Polygon",
+            "coordinates": [
+                [[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0], [0.0, 0.0]],
+                [[1.0, 1.0], [3.0, 1.0], [3.0, 3.0], [1.0, 3.0]]
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_point_reads_lat_lng() {
+        assert!(parse_point("36.8, 10.18") == Some(point(36.8, 10.18)));
+        assert!(parse_point("91,0").is_none());
+        assert!(parse_point("0,181").is_none());
+        assert!(parse_point("36.8").is_none());
+        assert!(parse_point("a,b").is_none());
+    }
+
+    #[test]
+    fn haversine_km_matches_known_distance() {
+        // Paris to London, about 344 km.
+        let distance = haversine_km(point(48.8566, 2.3522), point(51.5074, -0.1278));
+        assert!((distance - 343.5).abs() < 1.0);
+        assert_eq!(haversine_km(point(1.0, 1.0), point(1.0, 1.0)), 0.0);
+    }
+
+    #[test]
+    fn radius_bounding_box_contains_the_circle() {
+        let center = point(36.8, 10.18);
+        let bounding_box = radius_bounding_box(center, 5.0);
+        for corner in [
+            point(bounding_box.min_latitude, center.longitude),
+            point(bounding_box.max_latitude, center.longitude),
+            point(center.latitude, bounding_box.min_longitude),
+            point(center.latitude, bounding_box.max_longitude),
+        ] {
+            assert!(haversine_km(center, corner) >= 5.0);
+        }
+
+        let polar = radius_bounding_box(point(89.5, 0.0), 100.0);
+        assert_eq!(polar.min_longitude, -180.0);
+        assert_eq!(polar.max_longitude, 180.0);
+    }
+
+    #[test]
+    fn parse_geojson_polygons_closes_rings() {
+        let polygons = square_with_hole();
+        assert_eq!(polygons.len(), 1);
+        assert_eq!(polygons[0].len(), 2);
+        assert!(polygons[0][1].first() == polygons[0][1].last());
+        assert!(polygons[0][0][1] == point(0.0, 4.0));
+    }
+
+    #[test]
+    fn parse_geojson_polygons_accepts_features_and_multipolygons() {
+        let feature = json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "MultiPolygon",
+                "coordinates": [
+                    [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]]],
+                    [[[5.0, 5.0], [6.0, 5.0], [6.0, 6.0]]]
+                ]
+            }
+        });
+        assert_eq!(parse_geojson_polygons(&feature).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn parse_geojson_polygons_rejects_invalid_geometries() {
+        for value in [
+            json!({ "type": "Point", "coordinates": [0.0, 0.0] }),
+            json!({ "type": "Polygon", "coordinates": [] }),
+            json!({ "type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 1.0]]] }),
+            json!({ "type": "Polygon", "coordinates": [[[0.0, 0.0], [1.0, 95.0], [2.0, 0.0]]] }),
+            json!({ "type": "MultiPolygon", "coordinates": [] }),
+        ] {
+            assert!(parse_geojson_polygons(&value).is_none());
+        }
+    }
+
+    #[test]
+    fn polygons_contain_respects_holes() {
+        let polygons = square_with_hole();
+        assert!(polygons_contain(&polygons, point(0.5, 0.5)));
+        assert!(polygons_contain(&polygons, point(3.5, 2.0)));
+        assert!(!polygons_contain(&polygons, point(2.0, 2.0)));
+        assert!(!polygons_contain(&polygons, point(5.0, 2.0)));
+        assert!(!polygons_contain(&[], point(2.0, 2.0)));
+    }
+
+    #[test]
+    fn polygons_bounding_box_uses_outer_rings() {
+        let bounding_box = polygons_bounding_box(&square_with_hole());
+        assert_eq!(bounding_box.min_latitude, 0.0);
+        assert_eq!(bounding_box.max_latitude, 4.0);
+        assert_eq!(bounding_box.min_longitude, 0.0);
+        assert_eq!(bounding_box.max_longitude, 4.0);
+    }
+}
@@ -0,0 +1,156 @@
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{header::ACCEPT_LANGUAGE, request::Parts},
+};
+use serde::Deserialize;
+
+const MAX_LOCALE_LENGTH: usize = 35;
+
+// BCP 47 tags such as "fr" or "pt-BR", stored lowercase as "pt-br".
+pub fn normalize_locale(locale: &str) -> Option<String> {
+    let locale = locale.trim().replace('_', "-").to_lowercase();
+    let mut subtags = locale.split('-');
+    let language = subtags.next()?;
+
+    let is_valid = locale.len() <= MAX_LOCALE_LENGTH
+        && (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_lowercase())
+        && subtags.all(|subtag| {
+            (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+
+    is_valid.then_some(locale)
+}
+
+#[derive(Deserialize)]
+struct LangInput {
+    lang: Option<String>,
+}
+
+// The locales a client asked for, best first: ?lang= then Accept-Language.
+// "fr-ch" is followed by "fr" so regional requests still match. Empty when
+// the client has no preference, texts are then left in the default locale.
+pub struct RequestLocales(pub Vec<String>);
+
+impl RequestLocales {
+    fn push(&mut self, locale: &str) {
+        let Some(locale) = normalize_locale(locale) else {
+            return;
+        };
+        let language = locale.split('-').next().unwrap_or_default().to_string();
+
+        for locale in [locale, language] {
+            if !self.0.contains(&locale) {
+                self.0.push(locale);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for RequestLocales {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mut locales = RequestLocales(Vec::new());
+
+        if let Ok(Query(LangInput { lang: Some(lang) })) = Query::try_from_uri(&parts.uri) {
+            locales.push(&lang);
+        }
+
+        // "de-CH, fr;q=0.8, *;q=0.5", a stable sort keeps equal weights in order.
+        let mut ranges: Vec<(&str, f32)> = parts
+            .headers
+            .get_all(ACCEPT_LANGUAGE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|range| {
+                let mut params = range.split(';');
+                let tag = params.next()?.trim();
+                let weight = params
+                    .filter_map(|param| param.trim().strip_prefix("q="))
+                    .find_map(|weight| weight.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (tag != "*" && weight > 0.0).then_some((tag, weight))
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (tag, _) in ranges {
+            locales.push(tag);
+        }
+
+        Ok(locales)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+
+    use super::*;
+
+    async fn request_locales(uri: &str, accept_language: Option<&str>) -> Vec<String> {
+        let mut request = Request::builder().uri(uri);
+        if let Some(accept_language) = accept_language {
+            request = request.header(ACCEPT_LANGUAGE, accept_language);
+        }
+        let (mut parts, _) = request.body(()).unwrap().into_parts();
+
+        let Ok(RequestLocales(locales)) = RequestLocales::from_request_parts(&mut parts, &()).await;
+        locales
+    }
+
+    #[test]
+    fn normalize_locale_lowercases_and_uses_hyphens() {
+        assert_eq!(normalize_locale("fr"), Some(String::from("fr")));
+        assert_eq!(normalize_locale(" pt_BR "), Some(String::from("pt-br")));
+        assert_eq!(
+            normalize_locale("zh-Hant-TW"),
+            Some(String::from("zh-hant-tw"))
+        );
+        assert_eq!(normalize_locale("gsw"), Some(String::from("gsw")));
+    }
+
+    #[test]
+    fn normalize_locale_rejects_invalid_tags() {
+        for locale in [
+            "", "f", "fren", "f1", "fr-", "fr-x", "fr--ch", "fr-ch!", "*",
+        ] {
+            assert_eq!(normalize_locale(locale), None, "{}", locale);
+        }
+        assert_eq!(
+            normalize_locale(&format!("en{}", "-abcdefgh".repeat(4))),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn request_locales_put_lang_before_accept_language() {
+        assert_eq!(
+            request_locales("/menus?lang=de-CH", Some("fr;q=0.8, en")).await,
+            vec!["de-ch", "de", "en", "fr"]
+        );
+    }
+
+    #[tokio::test]
+    async fn request_locales_sort_accept_language_by_weight() {
+        assert_eq!(
+            request_locales("/menus", Some("fr;q=0.5, pt-BR, *;q=0.9, it;q=0, es;q=0.5")).await,
+            vec!["pt-br", "pt", "fr", "es"]
+        );
+    }
+
+    #[tokio::test]
+    async fn request_locales_skip_invalid_tags() {
+        assert_eq!(
+            request_locales("/menus?lang=nope!", Some("xx-!!, en-GB")).await,
+            vec!["en-gb", "en"]
+        );
+        assert!(request_locales("/menus", None).await.is_empty());
+    }
+}
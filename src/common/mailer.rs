@@ -0,0 +1,5 @@
+// Stand-in until a mail provider is wired in: messages are only logged so
+// codes can be picked up from the server output during development.
+pub async fn send_mail(to: &str, subject: &str, body: &str) {
+    tracing::info!(to, subject, body, "mail sent");
+}
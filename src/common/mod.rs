@@ -1,3 +1,15 @@
 pub mod auth_middleware;
+pub mod contact;
+pub mod csv;
+pub mod etag;
+pub mod geo;
 pub mod jwt;
+pub mod locale;
+pub mod mailer;
+pub mod money;
+pub mod pdf;
+pub mod png;
+pub mod qr;
 pub mod role_middleware;
+pub mod slug;
+pub mod word_filter;
@@ -0,0 +1,164 @@
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+
+// An exact amount in minor units (cents, millimes...) of an ISO 4217
+// currency. Decoded from the money_amount type built by menu_money().
+#[derive(sqlx::Type, Clone, Debug, PartialEq)]
+#[sqlx(type_name = "money_amount")]
+pub struct Money {
+    pub amount_minor: i64,
+    pub currency: String,
+    pub exponent: i16,
+}
+
+// Serialized as {"amount":"3.50","amount_minor":350,"currency":"EUR"}, the
+// decimal string keeps clients away from float rounding.
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Money", 3)?;
+        state.serialize_field(
+            "amount",
+            &format_minor_units(self.amount_minor, self.exponent),
+        )?;
+        state.serialize_field("amount_minor", &self.amount_minor)?;
+        state.serialize_field("currency", &self.currency)?;
+        state.end()
+    }
+}
+
+// A decimal amount as sent by clients, either "3.50" or 3.5. Converted to
+// minor units once the currency is known.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum MoneyInput {
+    Text(String),
+    Number(serde_json::Number),
+}
+
+impl MoneyInput {
+    pub fn to_minor_units(&self, exponent: i16) -> Option<i64> {
+        match self {
+            MoneyInput::Text(amount) => parse_minor_units(amount, exponent),
+            MoneyInput::Number(amount) => parse_minor_units(&amount.to_string(), exponent),
+        }
+    }
+}
+
+pub fn format_minor_units(amount_minor: i64, exponent: i16) -> String {
+    let sign = if amount_minor < 0 { "-" } else { "" };
+    let amount = amount_minor.unsigned_abs();
+    if exponent <= 0 {
+        return format!("{}{}", sign, amount);
+    }
+
+    let scale = 10u64.pow(exponent as u32);
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        amount / scale,
+        amount % scale,
+        width = exponent as usize
+    )
+}
+
+// Plain decimals only ("12", "12.5", "-0.75"). More fraction digits than the
+// currency has is rejected rather than rounded.
+pub fn parse_minor_units(amount: &str, exponent: i16) -> Option<i64> {
+    let amount = amount.trim();
+    let (is_negative, amount) = match amount.strip_prefix('-') {
+        Some(amount) => (true, amount),
+        None => (false, amount),
+    };
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+
+    if whole.is_empty()
+        || !whole.chars().all(|c| c.is_ascii_digit())
+        || !fraction.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    // 3.50 is as precise as 3.5, only significant digits count.
+    let fraction = fraction.trim_end_matches('0');
+    let exponent = exponent.max(0) as usize;
+    if fraction.len() > exponent {
+        return None;
+    }
+
+    let minor_units: i64 = format!("{}{:0<width$}", whole, fraction, width = exponent)
+        .parse()
+        .ok()?;
+
+    Some(if is_negative {
+        -minor_units
+    } else {
+        minor_units
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn format_minor_units_follows_the_exponent() {
+        assert_eq!(format_minor_units(350, 2), "3.50");
+        assert_eq!(format_minor_units(5, 2), "0.05");
+        assert_eq!(format_minor_units(-75, 2), "-0.75");
+        assert_eq!(format_minor_units(12500, 3), "12.500");
+        assert_eq!(format_minor_units(1200, 0), "1200");
+    }
+
+    #[test]
+    fn parse_minor_units_reads_plain_decimals() {
+        assert_eq!(parse_minor_units("3.5", 2), Some(350));
+        assert_eq!(parse_minor_units(" 3.50 ", 2), Some(350));
+        assert_eq!(parse_minor_units("12", 2), Some(1200));
+        assert_eq!(parse_minor_units("-0.75", 2), Some(-75));
+        assert_eq!(parse_minor_units("12.5", 3), Some(12500));
+        assert_eq!(parse_minor_units("1200", 0), Some(1200));
+        assert_eq!(parse_minor_units("1200.00", 0), Some(1200));
+    }
+
+    #[test]
+    fn parse_minor_units_rejects_extra_precision() {
+        assert_eq!(parse_minor_units("3.505", 2), None);
+        assert_eq!(parse_minor_units("0.5", 0), None);
+    }
+
+    #[test]
+    fn parse_minor_units_rejects_malformed_amounts() {
+        for amount in [
+            "", "-", ".5", "3,50", "1e3", "+3", "--3", "3.5.0", "abc", "3.-5",
+        ] {
+            assert_eq!(parse_minor_units(amount, 2), None, "{}", amount);
+        }
+        assert_eq!(parse_minor_units("99999999999999999999", 2), None);
+    }
+
+    #[test]
+    fn money_input_accepts_text_and_numbers() {
+        let text: MoneyInput = serde_json::from_value(json!("3.50")).unwrap();
+        let number: MoneyInput = serde_json::from_value(json!(3.5)).unwrap();
+        let integer: MoneyInput = serde_json::from_value(json!(12)).unwrap();
+
+        assert_eq!(text.to_minor_units(2), Some(350));
+        assert_eq!(number.to_minor_units(2), Some(350));
+        assert_eq!(integer.to_minor_units(3), Some(12000));
+    }
+
+    #[test]
+    fn money_serializes_with_a_decimal_string() {
+        let money = Money {
+            amount_minor: 350,
+            currency: String::from("EUR"),
+            exponent: 2,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&money).unwrap(),
+            json!({ "amount": "3.50", "amount_minor": 350, "currency": "EUR" })
+        );
+    }
+}
@@ -0,0 +1,651 @@
+// Minimal PDF 1.4 writer: text in the standard Type 1 fonts, filled
+// rectangles, lines and JPEG or PNG images. Only the images are embedded,
+// every PDF reader ships the standard fonts. Coordinates are in points from
+// the bottom left corner of the page, as in PDF.
+
+use std::fmt::Write;
+
+use deunicode::deunicode_char;
+
+pub const A4_WIDTH: f32 = 595.28;
+pub const A4_HEIGHT: f32 = 841.89;
+
+// Advance widths of ' ' to '~' in thousandths of the font size, from the
+// Adobe font metrics of the standard fonts.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+const TIMES_ROMAN_WIDTHS: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444, 921, 722, 667, 667, 722, 611,
+    556, 722, 722, 333, 389, 722, 611, 889, 722, 722, 556, 722, 667, 556, 611, 722, 722, 944, 722,
+    722, 611, 333, 278, 333, 469, 500, 333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500,
+    278, 778, 500, 500, 500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+const TIMES_BOLD_WIDTHS: [u16; 95] = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500, 930, 722, 667, 722, 722, 667,
+    611, 778, 778, 389, 500, 778, 667, 944, 722, 778, 611, 778, 722, 556, 667, 722, 722, 1000, 722,
+    722, 667, 333, 278, 333, 581, 500, 333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556,
+    278, 833, 556, 500, 556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+];
+const TIMES_ITALIC_WIDTHS: [u16; 95] = [
+    250, 333, 420, 500, 500, 833, 778, 214, 333, 333, 500, 675, 250, 333, 250, 278, 500, 500, 500,
+    500, 500, 500, 500, 500, 500, 500, 333, 333, 675, 675, 675, 500, 920, 611, 611, 667, 722, 611,
+    611, 722, 722, 333, 444, 667, 556, 833, 667, 722, 611, 722, 611, 500, 556, 722, 611, 833, 611,
+    556, 556, 389, 278, 389, 422, 500, 333, 500, 500, 444, 500, 444, 278, 500, 500, 278, 278, 444,
+    278, 722, 500, 500, 500, 500, 389, 389, 278, 500, 444, 667, 444, 444, 389, 400, 275, 400, 541,
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PdfFont {
+    Helvetica,
+    HelveticaBold,
+    HelveticaOblique,
+    TimesRoman,
+    TimesBold,
+    TimesItalic,
+}
+
+const FONTS: [PdfFont; 6] = [
+    PdfFont::Helvetica,
+    PdfFont::HelveticaBold,
+    PdfFont::HelveticaOblique,
+    PdfFont::TimesRoman,
+    PdfFont::TimesBold,
+    PdfFont::TimesItalic,
+];
+
+impl PdfFont {
+    fn base_font(self) -> &'static str {
+        match self {
+            PdfFont::Helvetica => "Helvetica",
+            PdfFont::HelveticaBold => "Helvetica-Bold",
+            PdfFont::HelveticaOblique => "Helvetica-Oblique",
+            PdfFont::TimesRoman => "Times-Roman",
+            PdfFont::TimesBold => "Times-Bold",
+            PdfFont::TimesItalic => "Times-Italic",
+        }
+    }
+
+    fn widths(self) -> &'static [u16; 95] {
+        match self {
+            // Oblique only slants the regular glyphs.
+            PdfFont::Helvetica | PdfFont::HelveticaOblique => &HELVETICA_WIDTHS,
+            PdfFont::HelveticaBold => &HELVETICA_BOLD_WIDTHS,
+            PdfFont::TimesRoman => &TIMES_ROMAN_WIDTHS,
+            PdfFont::TimesBold => &TIMES_BOLD_WIDTHS,
+            PdfFont::TimesItalic => &TIMES_ITALIC_WIDTHS,
+        }
+    }
+
+    fn resource_name(self) -> usize {
+        FONTS.iter().position(|&font| font == self).unwrap_or(0) + 1
+    }
+
+    fn byte_width(self, byte: u8) -> u16 {
+        let widths = self.widths();
+        let ascii_width = |c: u8| widths[(c - b' ') as usize];
+
+        match byte {
+            b' '..=b'~' => ascii_width(byte),
+            0x85 | 0x8C | 0x97 | 0x99 | 0x9C => 1000,
+            0x91 | 0x92 => ascii_width(b'\''),
+            0x93 | 0x94 => ascii_width(b'"'),
+            0xA0 => ascii_width(b' '),
+            // Accented letters are as wide as their base letter.
+            0xC0..=0xFF => deunicode_char(char::from(byte))
+                .and_then(|base| base.bytes().next())
+                .filter(|base| (b' '..=b'~').contains(base))
+                .map(ascii_width)
+                .unwrap_or_else(|| ascii_width(b'0')),
+            _ => ascii_width(b'0'),
+        }
+    }
+
+    pub fn text_width(self, text: &str, size: f32) -> f32 {
+        encode_text(text)
+            .into_iter()
+            .map(|byte| self.byte_width(byte) as f32)
+            .sum::<f32>()
+            * size
+            / 1000.0
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PdfColor(pub u8, pub u8, pub u8);
+
+impl PdfColor {
+    fn operands(self) -> String {
+        format!(
+            "{:.3} {:.3} {:.3}",
+            self.0 as f32 / 255.0,
+            self.1 as f32 / 255.0,
+            self.2 as f32 / 255.0
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PdfTextStyle {
+    pub font: PdfFont,
+    pub size: f32,
+    pub color: PdfColor,
+}
+
+impl PdfTextStyle {
+    pub fn text_width(&self, text: &str) -> f32 {
+        self.font.text_width(text, self.size)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct PdfLineStyle {
+    pub width: f32,
+    // Length of the dashes and the gaps between them, 0 for a solid line.
+    pub dash: f32,
+    pub color: PdfColor,
+}
+
+// The standard fonts use WinAnsiEncoding, Latin-1 plus a few typographic
+// signs. Anything else is transliterated to ASCII.
+fn encode_text(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            ' '..='~' | '\u{a0}'..='\u{ff}' => bytes.push(c as u32 as u8),
+            '\t' | '\n' | '\r' => bytes.push(b' '),
+            '€' => bytes.push(0x80),
+            '…' => bytes.push(0x85),
+            'Œ' => bytes.push(0x8C),
+            '‘' => bytes.push(0x91),
+            '’' => bytes.push(0x92),
+            '“' => bytes.push(0x93),
+            '”' => bytes.push(0x94),
+            '•' => bytes.push(0x95),
+            '–' => bytes.push(0x96),
+            '—' => bytes.push(0x97),
+            '™' => bytes.push(0x99),
+            'œ' => bytes.push(0x9C),
+            _ => bytes.extend(
+                deunicode_char(c)
+                    .unwrap_or("?")
+                    .bytes()
+                    .filter(|byte| (b' '..=b'~').contains(byte)),
+            ),
+        }
+    }
+    bytes
+}
+
+fn pdf_string(text: &str) -> String {
+    let mut string = String::from("(");
+    for byte in encode_text(text) {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                string.push('\\');
+                string.push(byte as char);
+            }
+            b' '..=b'~' => string.push(byte as char),
+            _ => {
+                let _ = write!(string, "\\{:03o}", byte);
+            }
+        }
+    }
+    string.push(')');
+    string
+}
+
+// Greedy line breaking on spaces, words longer than a line are cut.
+pub fn wrap_text(text: &str, style: &PdfTextStyle, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if style.text_width(&candidate) <= max_width {
+            line = candidate;
+            continue;
+        }
+
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            line.push(c);
+            if style.text_width(&line) > max_width && line.chars().count() > 1 {
+                line.pop();
+                lines.push(std::mem::replace(&mut line, c.to_string()));
+            }
+        }
+    }
+
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+enum PdfImageFilter {
+    Dct,
+    // PNG rows keep their filter byte, the Flate predictor undoes it.
+    PngFlate { colors: u8 },
+}
+
+pub struct PdfImage {
+    pub width: u32,
+    pub height: u32,
+    color_space: &'static str,
+    filter: PdfImageFilter,
+    data: Vec<u8>,
+}
+
+impl PdfImage {
+    // Baseline or progressive JPEG in gray or RGB, or 8 bit non interlaced
+    // gray or RGB PNG without alpha. None for anything else.
+    pub fn from_bytes(bytes: &[u8]) -> Option<PdfImage> {
+        if bytes.starts_with(&[0xFF, 0xD8]) {
+            parse_jpeg(bytes)
+        } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n']) {
+            parse_png(bytes)
+        } else {
+            None
+        }
+    }
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn parse_jpeg(bytes: &[u8]) -> Option<PdfImage> {
+    let mut at = 2;
+    loop {
+        if *bytes.get(at)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(at + 1)?;
+        match marker {
+            0xFF => at += 1,
+            0x01 | 0xD0..=0xD8 => at += 2,
+            0xC0..=0xC2 => {
+                let height = read_u16(bytes, at + 5)? as u32;
+                let width = read_u16(bytes, at + 7)? as u32;
+                let color_space = match *bytes.get(at + 9)? {
+                    1 => "DeviceGray",
+                    3 => "DeviceRGB",
+                    _ => return None,
+                };
+                return Some(PdfImage {
+                    width,
+                    height,
+                    color_space,
+                    filter: PdfImageFilter::Dct,
+                    data: bytes.to_vec(),
+                });
+            }
+            // Other frame types are lossless or arithmetic coded.
+            0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF | 0xD9 | 0xDA => return None,
+            _ => at += 2 + read_u16(bytes, at + 2)? as usize,
+        }
+    }
+}
+
+fn parse_png(bytes: &[u8]) -> Option<PdfImage> {
+    let mut at = 8;
+    let mut header = None;
+    let mut data = Vec::new();
+
+    while at + 8 <= bytes.len() {
+        let length = read_u32(bytes, at)? as usize;
+        let kind = bytes.get(at + 4..at + 8)?;
+        let chunk = bytes.get(at + 8..at + 8 + length)?;
+        match kind {
+            b"IHDR" => header = Some(chunk),
+            b"IDAT" => data.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+        at += 12 + length;
+    }
+
+    let header = header?;
+    let (width, height) = (read_u32(header, 0)?, read_u32(header, 4)?);
+    let (bit_depth, color_type, interlace) = (*header.get(8)?, *header.get(9)?, *header.get(12)?);
+    let (color_space, colors) = match color_type {
+        0 => ("DeviceGray", 1),
+        2 => ("DeviceRGB", 3),
+        _ => return None,
+    };
+    if bit_depth != 8 || interlace != 0 || data.is_empty() {
+        return None;
+    }
+
+    Some(PdfImage {
+        width,
+        height,
+        color_space,
+        filter: PdfImageFilter::PngFlate { colors },
+        data,
+    })
+}
+
+pub struct PdfDocument {
+    pub width: f32,
+    pub height: f32,
+    title: String,
+    pages: Vec<String>,
+    images: Vec<PdfImage>,
+}
+
+impl PdfDocument {
+    pub fn new(width: f32, height: f32, title: &str) -> PdfDocument {
+        PdfDocument {
+            width,
+            height,
+            title: title.to_string(),
+            pages: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    pub fn add_page(&mut self) {
+        self.pages.push(String::new());
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    // Draws on the given page, pages are numbered from 0.
+    fn content(&mut self, page: usize) -> &mut String {
+        if self.pages.is_empty() {
+            self.pages.push(String::new());
+        }
+        let page = page.min(self.pages.len() - 1);
+        &mut self.pages[page]
+    }
+
+    fn current_page(&self) -> usize {
+        self.pages.len().saturating_sub(1)
+    }
+
+    pub fn text(&mut self, x: f32, y: f32, style: &PdfTextStyle, text: &str) {
+        self.text_on(self.current_page(), x, y, style, text);
+    }
+
+    pub fn text_on(&mut self, page: usize, x: f32, y: f32, style: &PdfTextStyle, text: &str) {
+        let _ = writeln!(
+            self.content(page),
+            "BT /F{} {:.2} Tf {} rg {:.2} {:.2} Td {} Tj ET",
+            style.font.resource_name(),
+            style.size,
+            style.color.operands(),
+            x,
+            y,
+            pdf_string(text)
+        );
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: PdfColor) {
+        let page = self.current_page();
+        let _ = writeln!(
+            self.content(page),
+            "{} rg {:.2} {:.2} {:.2} {:.2} re f",
+            color.operands(),
+            x,
+            y,
+            width,
+            height
+        );
+    }
+
+    pub fn line(&mut self, from: (f32, f32), to: (f32, f32), style: &PdfLineStyle) {
+        let page = self.current_page();
+        let dash = if style.dash > 0.0 {
+            format!("[{:.2}] 0 d", style.dash)
+        } else {
+            String::from("[] 0 d")
+        };
+        let _ = writeln!(
+            self.content(page),
+            "{:.2} w {} {} RG {:.2} {:.2} m {:.2} {:.2} l S",
+            style.width,
+            dash,
+            style.color.operands(),
+            from.0,
+            from.1,
+            to.0,
+            to.1
+        );
+    }
+
+    // Returns the id to draw the image with, it is embedded once however
+    // often it is drawn.
+    pub fn add_image(&mut self, image: PdfImage) -> usize {
+        self.images.push(image);
+        self.images.len() - 1
+    }
+
+    pub fn image(&mut self, image: usize, x: f32, y: f32, width: f32, height: f32) {
+        let page = self.current_page();
+        let _ = writeln!(
+            self.content(page),
+            "q {:.2} 0 0 {:.2} {:.2} {:.2} cm /Im{} Do Q",
+            width,
+            height,
+            x,
+            y,
+            image + 1
+        );
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut pages = self.pages.clone();
+        if pages.is_empty() {
+            pages.push(String::new());
+        }
+
+        // 1 catalog, 2 page tree, 3 info, then fonts, images and a page and
+        // content object per page.
+        let first_font = 4;
+        let first_image = first_font + FONTS.len();
+        let first_page = first_image + self.images.len();
+        let objects_count = first_page + pages.len() * 2 - 1;
+
+        let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects_count);
+        let mut write_object = |pdf: &mut Vec<u8>, body: &[u8]| {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", offsets.len()).as_bytes());
+            pdf.extend_from_slice(body);
+            pdf.extend_from_slice(b"\nendobj\n");
+        };
+
+        write_object(&mut pdf, b"<< /Type /Catalog /Pages 2 0 R >>");
+        let kids: Vec<String> = (0..pages.len())
+            .map(|page| format!("{} 0 R", first_page + page * 2))
+            .collect();
+        write_object(
+            &mut pdf,
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                pages.len()
+            )
+            .as_bytes(),
+        );
+        write_object(
+            &mut pdf,
+            format!(
+                "<< /Title {} /Producer (first-api) >>",
+                pdf_string(&self.title)
+            )
+            .as_bytes(),
+        );
+
+        for font in FONTS {
+            write_object(
+                &mut pdf,
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>",
+                    font.base_font()
+                )
+                .as_bytes(),
+            );
+        }
+
+        for image in &self.images {
+            let filter = match image.filter {
+                PdfImageFilter::Dct => String::from("/Filter /DCTDecode"),
+                PdfImageFilter::PngFlate { colors } => format!(
+                    "/Filter /FlateDecode /DecodeParms << /Predictor 15 /Colors {} /BitsPerComponent 8 /Columns {} >>",
+                    colors, image.width
+                ),
+            };
+            let mut body = format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{} /BitsPerComponent 8 {} /Length {} >>\nstream\n",
+                image.width,
+                image.height,
+                image.color_space,
+                filter,
+                image.data.len()
+            )
+            .into_bytes();
+            body.extend_from_slice(&image.data);
+            body.extend_from_slice(b"\nendstream");
+            write_object(&mut pdf, &body);
+        }
+
+        let fonts: String = FONTS
+            .iter()
+            .map(|font| {
+                format!(
+                    "/F{} {} 0 R ",
+                    font.resource_name(),
+                    first_font + font.resource_name() - 1
+                )
+            })
+            .collect();
+        let images: String = (0..self.images.len())
+            .map(|image| format!("/Im{} {} 0 R ", image + 1, first_image + image))
+            .collect();
+
+        for (page, content) in pages.iter().enumerate() {
+            write_object(
+                &mut pdf,
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << {}>> /XObject << {}>> >> /Contents {} 0 R >>",
+                    self.width,
+                    self.height,
+                    fonts,
+                    images,
+                    first_page + page * 2 + 1
+                )
+                .as_bytes(),
+            );
+            let mut body = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+            body.extend_from_slice(content.as_bytes());
+            body.extend_from_slice(b"\nendstream");
+            write_object(&mut pdf, &body);
+        }
+
+        let xref_offset = pdf.len();
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len() + 1);
+        for offset in &offsets {
+            let _ = writeln!(xref, "{:010} 00000 n ", offset);
+        }
+        let _ = writeln!(
+            xref,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 3 0 R >>\nstartxref\n{}\n%%EOF",
+            offsets.len() + 1,
+            xref_offset
+        );
+        pdf.extend_from_slice(xref.as_bytes());
+        pdf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // At 1000 points the widths are the font metrics themselves, 'a' is 556
+    // and ' ' is 278 in Helvetica.
+    const STYLE: PdfTextStyle = PdfTextStyle {
+        font: PdfFont::Helvetica,
+        size: 1000.0,
+        color: PdfColor(0, 0, 0),
+    };
+
+    #[test]
+    fn encode_text_keeps_latin_1_and_win_ansi_signs() {
+        assert_eq!(encode_text("Crème brûlée"), b"Cr\xe8me br\xfbl\xe9e");
+        assert_eq!(
+            encode_text("€5 – “chef’s” œuf…"),
+            b"\x805 \x96 \x93chef\x92s\x94 \x9cuf\x85"
+        );
+    }
+
+    #[test]
+    fn encode_text_transliterates_everything_else() {
+        assert_eq!(encode_text("Łódź"), b"L\xf3dz");
+        assert_eq!(encode_text("a\tb\nc\rd"), b"a b c d");
+    }
+
+    #[test]
+    fn pdf_string_escapes_delimiters_and_non_ascii() {
+        assert_eq!(pdf_string(r"(é)\"), r"(\(\351\)\\)");
+        assert_eq!(pdf_string("Menu"), "(Menu)");
+    }
+
+    #[test]
+    fn text_width_uses_the_base_letter_for_accents() {
+        assert_eq!(STYLE.text_width("aa a"), 556.0 * 3.0 + 278.0);
+        assert_eq!(STYLE.text_width("é"), STYLE.text_width("e"));
+        assert_eq!(PdfFont::Helvetica.text_width("a", 10.0), 5.56);
+    }
+
+    #[test]
+    fn wrap_text_breaks_on_spaces() {
+        // "aa aa" is exactly 2502 wide.
+        assert_eq!(wrap_text("aa aa aa", &STYLE, 2502.0), vec!["aa aa", "aa"]);
+        assert_eq!(
+            wrap_text("aa aa aa", &STYLE, 2501.0),
+            vec!["aa", "aa", "aa"]
+        );
+        assert_eq!(wrap_text("  aa \n aa  ", &STYLE, 10000.0), vec!["aa aa"]);
+        assert!(wrap_text(" ", &STYLE, 10000.0).is_empty());
+    }
+
+    #[test]
+    fn wrap_text_cuts_words_longer_than_a_line() {
+        assert_eq!(wrap_text("aaaaa", &STYLE, 1200.0), vec!["aa", "aa", "a"]);
+        assert_eq!(
+            wrap_text("a aaaaa", &STYLE, 1200.0),
+            vec!["a", "aa", "aa", "a"]
+        );
+        // A single character always stays on the line, even when too wide.
+        assert_eq!(wrap_text("W", &STYLE, 100.0), vec!["W"]);
+    }
+}
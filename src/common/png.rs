@@ -0,0 +1,167 @@
+// Minimal PNG writer for 8 bit grayscale images. The pixels are stored
+// with uncompressed deflate blocks, which is plenty for small black and
+// white images such as QR codes.
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+const MAX_STORED_BLOCK_LENGTH: usize = 0xFFFF;
+
+// One byte per pixel, rows from top to bottom.
+pub fn encode_grayscale_png(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // Bit depth 8, grayscale, deflate, adaptive filtering, no interlace.
+    header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+    // Each row starts with its filter type, 0 for none.
+    let mut scanlines = Vec::with_capacity(pixels.len() + height as usize);
+    for row in pixels.chunks(width.max(1) as usize) {
+        scanlines.push(0);
+        scanlines.extend_from_slice(row);
+    }
+
+    let mut png = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib_stored(&scanlines));
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    // Deflate with a 32K window and no preset dictionary.
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_STORED_BLOCK_LENGTH).peekable();
+
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let length = block.len() as u16;
+        zlib.push(u8::from(blocks.peek().is_none()));
+        zlib.extend_from_slice(&length.to_le_bytes());
+        zlib.extend_from_slice(&(!length).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+
+    zlib.extend_from_slice(&adler32(data).to_be_bytes());
+    zlib
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    b << 16 | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Splits a PNG into (kind, data) pairs, checking each CRC on the way.
+    fn chunks(png: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        let mut chunks = Vec::new();
+        let mut rest = &png[8..];
+        while !rest.is_empty() {
+            let length = u32::from_be_bytes(rest[0..4].try_into().unwrap()) as usize;
+            let body = &rest[4..8 + length];
+            let crc = u32::from_be_bytes(rest[8 + length..12 + length].try_into().unwrap());
+            assert_eq!(crc32(body), crc);
+            chunks.push((body[..4].to_vec(), body[4..].to_vec()));
+            rest = &rest[12 + length..];
+        }
+        chunks
+    }
+
+    // Reads the stored deflate blocks back, checking the lengths and the
+    // Adler-32 trailer.
+    fn inflate_stored(zlib: &[u8]) -> Vec<u8> {
+        assert_eq!(zlib[0..2], [0x78, 0x01]);
+        let mut data = Vec::new();
+        let mut rest = &zlib[2..];
+        loop {
+            let is_final = rest[0] == 1;
+            let length = u16::from_le_bytes([rest[1], rest[2]]);
+            assert_eq!(u16::from_le_bytes([rest[3], rest[4]]), !length);
+            data.extend_from_slice(&rest[5..5 + length as usize]);
+            rest = &rest[5 + length as usize..];
+            if is_final {
+                break;
+            }
+        }
+        assert_eq!(rest, adler32(&data).to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn checksums_match_known_values() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        assert_eq!(adler32(b""), 1);
+    }
+
+    #[test]
+    fn encode_grayscale_png_writes_header_pixels_and_end() {
+        let png = encode_grayscale_png(3, 2, &[0, 255, 0, 255, 0, 255]);
+        assert_eq!(png[..8], PNG_SIGNATURE);
+
+        let chunks = chunks(&png);
+        let kinds: Vec<&[u8]> = chunks.iter().map(|(kind, _)| kind.as_slice()).collect();
+        assert_eq!(kinds, vec![&b"IHDR"[..], b"IDAT", b"IEND"]);
+        assert_eq!(chunks[0].1, [0, 0, 0, 3, 0, 0, 0, 2, 8, 0, 0, 0, 0]);
+        assert_eq!(inflate_stored(&chunks[1].1), [0, 0, 255, 0, 0, 255, 0, 255]);
+        assert!(chunks[2].1.is_empty());
+        assert_eq!(
+            png[png.len() - 12..],
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+        );
+    }
+
+    #[test]
+    fn zlib_stored_splits_long_data_into_blocks() {
+        let data: Vec<u8> = (0..MAX_STORED_BLOCK_LENGTH * 2 + 10)
+            .map(|i| i as u8)
+            .collect();
+        let zlib = zlib_stored(&data);
+        // Three block headers plus the zlib header and trailer.
+        assert_eq!(zlib.len(), data.len() + 3 * 5 + 2 + 4);
+        assert_eq!(zlib[2], 0);
+        assert_eq!(inflate_stored(&zlib), data);
+    }
+
+    #[test]
+    fn zlib_stored_writes_an_empty_final_block_for_no_data() {
+        assert_eq!(
+            zlib_stored(&[]),
+            [0x78, 0x01, 1, 0, 0, 0xFF, 0xFF, 0, 0, 0, 1]
+        );
+        assert!(inflate_stored(&zlib_stored(&[])).is_empty());
+    }
+}
@@ -0,0 +1,579 @@
+// QR codes (ISO/IEC 18004) in byte mode with the medium error correction
+// level, which still scans with about 15% of the code smudged or covered.
+
+const ECC_CODEWORDS_PER_BLOCK: [usize; 41] = [
+    0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28,
+    28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+];
+const ERROR_CORRECTION_BLOCKS: [usize; 41] = [
+    0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23,
+    25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+];
+// Format bits of the medium level.
+const ECC_LEVEL_BITS: u32 = 0;
+
+pub struct QrCode {
+    // Modules per side.
+    pub size: usize,
+    modules: Vec<bool>,
+    is_function: Vec<bool>,
+}
+
+impl QrCode {
+    // The smallest version that fits, None when the data is longer than the
+    // 2331 bytes of version 40.
+    pub fn encode(data: &[u8]) -> Option<QrCode> {
+        let version = (1..=40).find(|&version| {
+            let count_bits = if version < 10 { 8 } else { 16 };
+            4 + count_bits + data.len() * 8 <= data_codewords(version) * 8
+        })?;
+
+        let mut bits = BitBuffer::default();
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, if version < 10 { 8 } else { 16 });
+        for &byte in data {
+            bits.push(byte as u32, 8);
+        }
+
+        let capacity = data_codewords(version) * 8;
+        bits.push(0, (capacity - bits.0.len()).min(4));
+        bits.push(0, (8 - bits.0.len() % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.0.len() >= capacity {
+                break;
+            }
+            bits.push(pad, 8);
+        }
+
+        let codewords: Vec<u8> = bits
+            .0
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |acc, &bit| (acc << 1) | bit as u8))
+            .collect();
+
+        let size = version * 4 + 17;
+        let mut qr = QrCode {
+            size,
+            modules: vec![false; size * size],
+            is_function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version);
+        qr.draw_codewords(&add_ecc_and_interleave(version, &codewords));
+
+        let mask = (0..8)
+            .min_by_key(|&mask| {
+                qr.apply_mask(mask);
+                qr.draw_format_bits(mask);
+                let penalty = qr.penalty();
+                qr.apply_mask(mask);
+                penalty
+            })
+            .unwrap_or(0);
+        qr.apply_mask(mask);
+        qr.draw_format_bits(mask);
+
+        Some(qr)
+    }
+
+    // x is the column and y the row, from the top left corner.
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, is_dark: bool) {
+        self.modules[y * self.size + x] = is_dark;
+        self.is_function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4i32..=4 {
+                for dx in -4i32..=4 {
+                    let (xx, yy) = (x as i32 + dx, y as i32 + dy);
+                    if (0..size as i32).contains(&xx) && (0..size as i32).contains(&yy) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(xx as usize, yy as usize, distance != 2 && distance != 4);
+                    }
+                }
+            }
+        }
+
+        let positions = alignment_pattern_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // The finder patterns already take these corners.
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in -2i32..=2 {
+                    for dx in -2i32..=2 {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(
+                            (x as i32 + dx) as usize,
+                            (y as i32 + dy) as usize,
+                            distance != 1,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Reserves the format areas until the mask is known.
+        self.draw_format_bits(0);
+
+        if version >= 7 {
+            let mut remainder = version as u32;
+            for _ in 0..12 {
+                remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+            }
+            let bits = (version as u32) << 12 | remainder;
+
+            for i in 0..18 {
+                let is_dark = (bits >> i) & 1 == 1;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, is_dark);
+                self.set_function(b, a, is_dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = ECC_LEVEL_BITS << 3 | mask;
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    // Fills the data area in two module wide columns, zigzagging up and down
+    // from the bottom right corner.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size as i32 - 1;
+
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right as usize - j;
+                    let is_upward = (right + 1) & 2 == 0;
+                    let y = if is_upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.is_function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[i / 8] >> (7 - i % 8)) & 1 == 1;
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    // Applying the same mask twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let is_flipped = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if is_flipped && !self.is_function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    // Lower is easier to scan: long runs, 2x2 blocks, finder lookalikes and
+    // an unbalanced dark share all cost points.
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let mut penalty = 0;
+        let finder_like = [
+            [
+                true, false, true, true, true, false, true, false, false, false, false,
+            ],
+            [
+                false, false, false, false, true, false, true, true, true, false, true,
+            ],
+        ];
+
+        for is_row in [true, false] {
+            for a in 0..size {
+                let line: Vec<bool> = (0..size)
+                    .map(|b| {
+                        if is_row {
+                            self.is_dark(b, a)
+                        } else {
+                            self.is_dark(a, b)
+                        }
+                    })
+                    .collect();
+
+                let mut run = 1;
+                for b in 1..=size {
+                    if b < size && line[b] == line[b - 1] {
+                        run += 1;
+                        continue;
+                    }
+                    if run >= 5 {
+                        penalty += run - 2;
+                    }
+                    run = 1;
+                }
+
+                penalty += line
+                    .windows(11)
+                    .filter(|window| finder_like.iter().any(|pattern| window == pattern))
+                    .count()
+                    * 40;
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let is_dark = self.is_dark(x, y);
+                if is_dark == self.is_dark(x + 1, y)
+                    && is_dark == self.is_dark(x, y + 1)
+                    && is_dark == self.is_dark(x + 1, y + 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        let dark = self.modules.iter().filter(|&&is_dark| is_dark).count();
+        let total = size * size;
+        penalty + (dark * 100 / total).abs_diff(50) / 5 * 10
+    }
+}
+
+#[derive(Default)]
+struct BitBuffer(Vec<bool>);
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, length: usize) {
+        for i in (0..length).rev() {
+            self.0.push((value >> i) & 1 == 1);
+        }
+    }
+}
+
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[version] * ERROR_CORRECTION_BLOCKS[version]
+}
+
+fn alignment_pattern_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+
+    let alignments = version / 7 + 2;
+    let step = if version == 32 {
+        26
+    } else {
+        (version * 4 + alignments * 2 + 1) / (alignments * 2 - 2) * 2
+    };
+    let mut positions = vec![6];
+    let mut position = version * 4 + 17 - 7;
+    for _ in 0..alignments - 1 {
+        positions.insert(1, position);
+        position -= step;
+    }
+    positions
+}
+
+// Splits the data into blocks, appends the Reed-Solomon codewords of each
+// and interleaves them column by column.
+fn add_ecc_and_interleave(version: usize, data: &[u8]) -> Vec<u8> {
+    let blocks_count = ERROR_CORRECTION_BLOCKS[version];
+    let ecc_length = ECC_CODEWORDS_PER_BLOCK[version];
+    let raw_codewords = raw_data_modules(version) / 8;
+    let short_blocks = blocks_count - raw_codewords % blocks_count;
+    let short_block_length = raw_codewords / blocks_count;
+    let divisor = reed_solomon_divisor(ecc_length);
+
+    let mut blocks = Vec::with_capacity(blocks_count);
+    let mut start = 0;
+    for i in 0..blocks_count {
+        let length = short_block_length - ecc_length + usize::from(i >= short_blocks);
+        let mut block = data[start..start + length].to_vec();
+        start += length;
+        let ecc = reed_solomon_remainder(&block, &divisor);
+        if i < short_blocks {
+            block.push(0);
+        }
+        block.extend(ecc);
+        blocks.push(block);
+    }
+
+    let mut codewords = Vec::with_capacity(raw_codewords);
+    for i in 0..blocks[0].len() {
+        for (j, block) in blocks.iter().enumerate() {
+            // Skips the padding of the short blocks.
+            if i != short_block_length - ecc_length || j >= short_blocks {
+                codewords.push(block[i]);
+            }
+        }
+    }
+    codewords
+}
+
+// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut divisor = vec![0; degree];
+    divisor[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            divisor[j] = gf_multiply(divisor[j], root);
+            if j + 1 < degree {
+                divisor[j] ^= divisor[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    divisor
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = vec![0; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ remainder.remove(0);
+        remainder.push(0);
+        for (value, &coefficient) in remainder.iter_mut().zip(divisor) {
+            *value ^= gf_multiply(coefficient, factor);
+        }
+    }
+    remainder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Format information of the medium level for masks 0 to 7, from the
+    // table in the standard.
+    const FORMAT_BITS: [u32; 8] = [
+        0x5412, 0x5125, 0x5E7C, 0x5B4B, 0x45F9, 0x40CE, 0x4F97, 0x4AA0,
+    ];
+
+    fn read_format_bits(qr: &QrCode) -> (u32, u32) {
+        let size = qr.size;
+        let bit = |x: usize, y: usize, i: usize| u32::from(qr.is_dark(x, y)) << i;
+
+        let mut first = 0;
+        for i in 0..=5 {
+            first |= bit(8, i, i);
+        }
+        first |= bit(8, 7, 6) | bit(8, 8, 7) | bit(7, 8, 8);
+        for i in 9..15 {
+            first |= bit(14 - i, 8, i);
+        }
+
+        let mut second = 0;
+        for i in 0..8 {
+            second |= bit(size - 1 - i, 8, i);
+        }
+        for i in 8..15 {
+            second |= bit(8, size - 15 + i, i);
+        }
+        (first, second)
+    }
+
+    // Unmasks the symbol and reads the codewords back in placement order.
+    fn read_codewords(mut qr: QrCode) -> Vec<u8> {
+        let (format, _) = read_format_bits(&qr);
+        let mask = FORMAT_BITS.iter().position(|&bits| bits == format).unwrap();
+        qr.apply_mask(mask as u32);
+
+        let size = qr.size;
+        let mut bits = Vec::new();
+        let mut right = size as i32 - 1;
+        while right >= 1 {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for j in 0..2 {
+                    let x = right as usize - j;
+                    let y = if (right + 1) & 2 == 0 {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !qr.is_function[y * size + x] {
+                        bits.push(qr.is_dark(x, y));
+                    }
+                }
+            }
+            right -= 2;
+        }
+        bits.chunks_exact(8)
+            .map(|byte| byte.iter().fold(0, |acc, &bit| (acc << 1) | bit as u8))
+            .collect()
+    }
+
+    #[test]
+    fn data_codewords_match_byte_mode_capacities() {
+        for (version, bytes) in [(1, 14), (2, 26), (3, 42), (4, 62), (10, 213), (40, 2331)] {
+            let count_bits = if version < 10 { 8 } else { 16 };
+            assert_eq!(
+                (data_codewords(version) * 8 - 4 - count_bits) / 8,
+                bytes,
+                "{}",
+                version
+            );
+        }
+    }
+
+    #[test]
+    fn encode_picks_the_smallest_version() {
+        assert_eq!(QrCode::encode(b"").unwrap().size, 21);
+        assert_eq!(QrCode::encode(&[b'a'; 14]).unwrap().size, 21);
+        assert_eq!(QrCode::encode(&[b'a'; 15]).unwrap().size, 25);
+        assert_eq!(QrCode::encode(&[b'a'; 2331]).unwrap().size, 177);
+        assert!(QrCode::encode(&[b'a'; 2332]).is_none());
+    }
+
+    #[test]
+    fn alignment_patterns_are_placed_like_the_standard_table() {
+        assert!(alignment_pattern_positions(1).is_empty());
+        assert_eq!(alignment_pattern_positions(2), vec![6, 18]);
+        assert_eq!(alignment_pattern_positions(7), vec![6, 22, 38]);
+        assert_eq!(
+            alignment_pattern_positions(32),
+            vec![6, 34, 60, 86, 112, 138]
+        );
+        assert_eq!(
+            alignment_pattern_positions(40),
+            vec![6, 30, 58, 86, 114, 142, 170]
+        );
+    }
+
+    #[test]
+    fn gf_multiply_reduces_by_the_field_polynomial() {
+        assert_eq!(gf_multiply(0x80, 0x02), 0x1D);
+        assert_eq!(gf_multiply(0x53, 0x01), 0x53);
+        assert_eq!(gf_multiply(0x53, 0x00), 0x00);
+    }
+
+    #[test]
+    fn reed_solomon_matches_the_hello_world_example() {
+        // "HELLO WORLD" in alphanumeric mode at version 1-M.
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            reed_solomon_remainder(&data, &reed_solomon_divisor(10)),
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+    }
+
+    #[test]
+    fn finder_patterns_sit_in_three_corners() {
+        let qr = QrCode::encode(b"https://example.com").unwrap();
+        let size = qr.size;
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            assert!(qr.is_dark(x, y));
+            assert!(!qr.is_dark(x + 2, y));
+            assert!(qr.is_dark(x + 3, y));
+        }
+        assert!(!qr.is_dark(7, 7));
+    }
+
+    #[test]
+    fn format_bits_are_valid_and_written_twice() {
+        for data in [&b""[..], b"hello", &[0xA5; 100], &[b'x'; 500]] {
+            let qr = QrCode::encode(data).unwrap();
+            let (first, second) = read_format_bits(&qr);
+            assert!(FORMAT_BITS.contains(&first), "{}", data.len());
+            assert_eq!(first, second, "{}", data.len());
+        }
+    }
+
+    #[test]
+    fn version_1_codewords_decode_back_to_the_data() {
+        let data = b"menu/42";
+        let codewords = read_codewords(QrCode::encode(data).unwrap());
+        assert_eq!(codewords.len(), 26);
+
+        let (message, ecc) = codewords.split_at(16);
+        // Byte mode indicator, then the 8 bit length.
+        assert_eq!(message[0] >> 4, 0b0100);
+        assert_eq!(message[0] << 4 | message[1] >> 4, data.len() as u8);
+        let bytes: Vec<u8> = (0..data.len())
+            .map(|i| message[i + 1] << 4 | message[i + 2] >> 4)
+            .collect();
+        assert_eq!(bytes, data);
+        assert_eq!(message[9..], [0xEC, 0x11, 0xEC, 0x11, 0xEC, 0x11, 0xEC]);
+        assert_eq!(
+            reed_solomon_remainder(message, &reed_solomon_divisor(10)),
+            ecc
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use deunicode::deunicode;
+
+pub const MAX_SLUG_LENGTH: usize = 80;
+const MIN_SLUG_LENGTH: usize = 3;
+
+// Transliterates to ASCII first so "Café Šabić" becomes "cafe-sabic" instead
+// of losing every accented letter.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+
+    for c in deunicode(text).to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.truncate(MAX_SLUG_LENGTH);
+    slug.trim_matches('-').to_string()
+}
+
+// A slug made only of digits would be mistaken for an id in the routes.
+pub fn is_valid_slug(slug: &str) -> bool {
+    (MIN_SLUG_LENGTH..=MAX_SLUG_LENGTH).contains(&slug.len())
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && !slug.contains("--")
+        && !slug.chars().all(|c| c.is_ascii_digit())
+}
+
+// Returns `base`, or `base-2`, `base-3`... for the first one not taken.
+pub fn with_collision_suffix(base: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    if !is_taken(base) {
+        return base.to_string();
+    }
+
+    let mut suffix = 2;
+    loop {
+        let suffix_str = format!("-{}", suffix);
+        let mut candidate = base.to_string();
+        candidate.truncate(MAX_SLUG_LENGTH - suffix_str.len());
+        let candidate = format!("{}{}", candidate.trim_end_matches('-'), suffix_str);
+
+        if !is_taken(&candidate) {
+            return candidate;
+        }
+        suffix += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_transliterates_and_collapses_separators() {
+        assert_eq!(slugify("Café Šabić"), "cafe-sabic");
+        assert_eq!(slugify("  Pizza -- & Pasta!  "), "pizza-pasta");
+        assert_eq!(slugify("Ñandú 24/7"), "nandu-24-7");
+        assert_eq!(slugify("!!!"), "");
+    }
+
+    #[test]
+    fn slugify_stays_within_max_length() {
+        let slug = slugify(&"ab ".repeat(60));
+        assert!(slug.len() <= MAX_SLUG_LENGTH);
+        assert!(!slug.ends_with('-'));
+    }
+
+    #[test]
+    fn is_valid_slug_rejects_malformed_slugs() {
+        assert!(is_valid_slug("cafe-sabic"));
+        assert!(is_valid_slug("r2d2"));
+        assert!(!is_valid_slug("ab"));
+        assert!(!is_valid_slug("123"));
+        assert!(!is_valid_slug("-cafe"));
+        assert!(!is_valid_slug("cafe-"));
+        assert!(!is_valid_slug("cafe--sabic"));
+        assert!(!is_valid_slug("Cafe"));
+        assert!(!is_valid_slug(&"a".repeat(MAX_SLUG_LENGTH + 1)));
+    }
+
+    #[test]
+    fn with_collision_suffix_picks_first_free_slug() {
+        assert_eq!(with_collision_suffix("cafe", |_| false), "cafe");
+
+        let taken = ["cafe", "cafe-2"];
+        assert_eq!(
+            with_collision_suffix("cafe", |slug| taken.contains(&slug)),
+            "cafe-3"
+        );
+    }
+
+    #[test]
+    fn with_collision_suffix_truncates_long_bases() {
+        let base = "a".repeat(MAX_SLUG_LENGTH);
+        let slug = with_collision_suffix(&base, |slug| slug == base);
+        assert_eq!(slug.len(), MAX_SLUG_LENGTH);
+        assert!(slug.ends_with("-2"));
+    }
+}
@@ -0,0 +1,53 @@
+fn normalize(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect();
+
+    format!(" {} ", words.join(" "))
+}
+
+// Matches whole words (or whole phrases) case-insensitively, so a banned
+// "ass" does not flag "class".
+pub fn contains_banned_word(text: &str, banned_words: &[String]) -> bool {
+    let text = normalize(text);
+
+    banned_words.iter().any(|banned_word| {
+        let banned_word = normalize(banned_word);
+        banned_word.trim() != "" && text.contains(&banned_word)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn banned(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn contains_banned_word_matches_whole_words_only() {
+        let banned_words = banned(&["ass"]);
+        assert!(contains_banned_word("What an ASS!", &banned_words));
+        assert!(!contains_banned_word("A classy place", &banned_words));
+        assert!(!contains_banned_word("Best bass in town", &banned_words));
+    }
+
+    #[test]
+    fn contains_banned_word_matches_phrases_across_punctuation() {
+        let banned_words = banned(&["rip off"]);
+        assert!(contains_banned_word("Total rip-off, avoid", &banned_words));
+        assert!(!contains_banned_word(
+            "They rip the off cuts",
+            &banned_words
+        ));
+    }
+
+    #[test]
+    fn contains_banned_word_ignores_empty_entries() {
+        assert!(!contains_banned_word("anything", &banned(&["", " - "])));
+        assert!(!contains_banned_word("anything", &[]));
+    }
+}
@@ -3,6 +3,14 @@ pub struct Config {
     pub database_url: String,
     pub jwt_secret: String,
     pub jwt_expires_in: String,
+    pub review_banned_words: Vec<String>,
+    pub ranking_interval_seconds: u64,
+    pub trash_retention_days: i32,
+    pub trash_purge_interval_seconds: u64,
+    pub stock_reset_interval_seconds: u64,
+    pub default_phone_country: Option<String>,
+    // Page the table QR codes open, followed by /<restaurant slug>.
+    pub public_menu_url: String,
 }
 
 impl Config {
@@ -10,10 +18,51 @@ impl Config {
         let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
         let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
         let jwt_expires_in = std::env::var("JWT_EXPIRED_IN").expect("JWT_EXPIRED_IN must be set");
+        let review_banned_words = std::env::var("REVIEW_BANNED_WORDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|word| word.trim().to_string())
+            .filter(|word| !word.is_empty())
+            .collect();
+        // A zero period would make the tokio interval panic.
+        let ranking_interval_seconds = std::env::var("RANKING_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|seconds| seconds.parse().ok())
+            .filter(|&seconds| seconds > 0)
+            .unwrap_or(900);
+        let trash_retention_days = std::env::var("TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|days| days.parse().ok())
+            .unwrap_or(30);
+        let trash_purge_interval_seconds = std::env::var("TRASH_PURGE_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|seconds| seconds.parse().ok())
+            .filter(|&seconds| seconds > 0)
+            .unwrap_or(3600);
+        let stock_reset_interval_seconds = std::env::var("STOCK_RESET_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|seconds| seconds.parse().ok())
+            .filter(|&seconds| seconds > 0)
+            .unwrap_or(300);
+        let default_phone_country = std::env::var("DEFAULT_PHONE_COUNTRY")
+            .ok()
+            .map(|country| country.trim().to_uppercase())
+            .filter(|country| !country.is_empty());
+        let public_menu_url = std::env::var("PUBLIC_MENU_URL")
+            .unwrap_or_else(|_| String::from("http://localhost:3000/menu"))
+            .trim_end_matches('/')
+            .to_string();
         Config {
             database_url,
             jwt_secret,
             jwt_expires_in,
+            review_banned_words,
+            ranking_interval_seconds,
+            trash_retention_days,
+            trash_purge_interval_seconds,
+            stock_reset_interval_seconds,
+            default_phone_country,
+            public_menu_url,
         }
     }
 }
@@ -11,26 +11,119 @@ use axum::{
     Router,
 };
 
-use common::{auth_middleware::auth_middleware, role_middleware::role_middleware};
+use common::{
+    auth_middleware::{auth_middleware, optional_auth_middleware},
+    role_middleware::role_middleware,
+};
 use config::Config;
 use dotenv::dotenv;
 use modules::{
     auth::auth_controller::{
         login, login_restaurant_owner, register_restaurant_owner, register_user,
     },
+    favorites::favorites_controller::{
+        add_favorite_menu_item, add_favorite_restaurant, get_favorite_menu_items,
+        get_favorite_restaurants, remove_favorite_menu_item, remove_favorite_restaurant,
+    },
     files::files_controller::upload,
-    restaurant_menu_items::restaurant_menu_items_controller::{
-        create_restaurant_menu_item, delete_restaurant_menu_item, get_restaurant_meals,
-        get_restaurant_menu_items,
+    ranking::ranking_service::run_ranking_task,
+    restaurant_chains::restaurant_chains_controller::{
+        activate_restaurant_chain_menu, create_restaurant_chain, create_restaurant_chain_menu,
+        create_restaurant_chain_menu_item, delete_restaurant_chain, delete_restaurant_chain_menu,
+        delete_restaurant_chain_menu_item, delete_restaurant_menu_item_override,
+        disactivate_restaurant_chain_menu, get_my_restaurant_chains,
+        get_restaurant_chain_menu_items, get_restaurant_chain_menus,
+        get_restaurant_menu_item_overrides, update_restaurant_chain,
+        update_restaurant_chain_membership, upsert_restaurant_menu_item_override,
+    },
+    restaurant_delivery_zones::restaurant_delivery_zones_controller::{
+        check_restaurant_delivery, create_restaurant_delivery_zone,
+        delete_restaurant_delivery_zone, get_restaurant_delivery_zones,
+        update_restaurant_delivery_zone,
+    },
+    restaurant_menu_imports::restaurant_menu_imports_controller::{
+        export_restaurant_menus, import_restaurant_menus,
+    },
+    restaurant_menu_item_modifiers::restaurant_menu_item_modifiers_controller::{
+        create_restaurant_menu_item_modifier_group, create_restaurant_menu_item_modifier_option,
+        delete_restaurant_menu_item_modifier_group, delete_restaurant_menu_item_modifier_option,
+        get_restaurant_menu_item_modifiers, update_restaurant_menu_item_modifier_group,
+        update_restaurant_menu_item_modifier_option,
+    },
+    restaurant_menu_items::{
+        restaurant_menu_items_controller::{
+            copy_restaurant_menu_items, create_restaurant_menu_item,
+            decrement_restaurant_menu_item_stock, delete_restaurant_menu_item,
+            get_restaurant_meals, get_restaurant_menu_items,
+            update_restaurant_menu_item_availability, update_restaurant_menu_item_dietary_info,
+            update_restaurant_menu_item_stock,
+        },
+        restaurant_menu_items_service::run_stock_reset_task,
+    },
+    restaurant_menu_pdfs::restaurant_menu_pdfs_controller::get_restaurant_menu_pdf,
+    restaurant_menu_schedules::restaurant_menu_schedules_controller::{
+        create_restaurant_menu_schedule, delete_restaurant_menu_schedule,
+        get_restaurant_menu_schedules,
+    },
+    restaurant_menu_sections::restaurant_menu_sections_controller::{
+        create_restaurant_menu_section, delete_restaurant_menu_section,
+        get_restaurant_menu_sections, reorder_restaurant_menu, update_restaurant_menu_section,
+    },
+    restaurant_menu_versions::restaurant_menu_versions_controller::{
+        create_restaurant_menu_draft, delete_restaurant_menu_draft, get_restaurant_menu_draft,
+        get_restaurant_menu_draft_diff, get_restaurant_menu_version, get_restaurant_menu_versions,
+        publish_restaurant_menu_draft, rollback_restaurant_menu_version,
+        update_restaurant_menu_draft,
     },
     restaurant_menus::restaurant_menus_controller::{
-        activate_restaurant_menu, create_restaurant_menu, delete_restaurant_menu,
-        disactivate_restaurant_menu, get_restaurant_menus, get_restaurant_menus_pub,
+        activate_restaurant_menu, clone_restaurant_menu, create_restaurant_menu,
+        delete_restaurant_menu, disactivate_restaurant_menu, get_restaurant_full_menu,
+        get_restaurant_menus, get_restaurant_menus_pub,
+    },
+    restaurant_photos::restaurant_photos_controller::{
+        create_restaurant_photo, delete_restaurant_photo, get_restaurant_photos,
+        reorder_restaurant_photos, set_restaurant_cover_photo, update_restaurant_photo,
+    },
+    restaurant_qr_codes::restaurant_qr_codes_controller::{
+        get_restaurant_qr_code, get_restaurant_qr_code_sheet,
+    },
+    restaurant_transfers::restaurant_transfers_controller::{
+        accept_restaurant_transfer, cancel_restaurant_transfer, create_restaurant_transfer,
+        decline_restaurant_transfer, get_my_restaurant_transfers, get_restaurant_transfers,
+    },
+    restaurant_verifications::restaurant_verifications_controller::{
+        confirm_restaurant_verification, get_restaurant_verification, send_restaurant_verification,
     },
     restaurants::restaurants_controller::{
         create_restaurant, delete_restaurant, get_my_restaurants, get_restaurant, get_restaurants,
+        get_top_restaurants, publish_restaurant, unpublish_restaurant, update_restaurant_currency,
+        update_restaurant_description, update_restaurant_slug, update_restaurant_timezone,
+    },
+    reviews::reviews_controller::{
+        create_review, create_review_reply, delete_review, delete_review_reply,
+        get_restaurant_reviews, get_reviews_moderation_queue, hide_review, report_review,
+        restore_review, update_review, update_review_reply,
     },
     shared::shared_dto::AppResult,
+    translations::translations_controller::{
+        delete_restaurant_menu_item_translation, delete_restaurant_menu_translation,
+        delete_restaurant_translation, get_restaurant_menu_item_translations,
+        get_restaurant_menu_translations, get_restaurant_translations,
+        upsert_restaurant_menu_item_translation, upsert_restaurant_menu_translation,
+        upsert_restaurant_translation,
+    },
+    trash::{
+        trash_controller::{
+            get_trash, restore_restaurant, restore_restaurant_menu, restore_restaurant_menu_item,
+        },
+        trash_service::run_trash_purge_task,
+    },
+    user_lists::user_lists_controller::{
+        add_user_list_restaurant, create_user_list, delete_user_list, get_my_user_lists,
+        get_public_user_lists, get_shared_user_list, get_user_list,
+        regenerate_user_list_share_token, remove_user_list_restaurant,
+        revoke_user_list_share_token, update_user_list,
+    },
     users::{
         users_controller::{accept_restaurant_owner, block_restaurant_owner, get_me, get_users},
         users_dto::{RolesEnum, User},
@@ -95,6 +188,10 @@ async fn main() {
         Err(_) => println!("Admin seed failed"),
     };
 
+    tokio::spawn(run_ranking_task(shared_state.clone()));
+    tokio::spawn(run_trash_purge_task(shared_state.clone()));
+    tokio::spawn(run_stock_reset_task(shared_state.clone()));
+
     let restaurant_menu_items_routes = Router::new()
         .route("/", post(create_restaurant_menu_item))
         .route(
@@ -103,6 +200,49 @@ async fn main() {
         )
         .route("/", get(get_restaurant_menu_items));
 
+    let restaurant_menu_items_public_routes =
+        Router::new().route("/", get(get_restaurant_menu_items));
+
+    let restaurant_menu_item_modifiers_routes = Router::new()
+        .route("/", post(create_restaurant_menu_item_modifier_group))
+        .route(
+            "/:restaurant_menu_item_modifier_group_id",
+            patch(update_restaurant_menu_item_modifier_group),
+        )
+        .route(
+            "/:restaurant_menu_item_modifier_group_id",
+            delete(delete_restaurant_menu_item_modifier_group),
+        )
+        .route(
+            "/:restaurant_menu_item_modifier_group_id/options",
+            post(create_restaurant_menu_item_modifier_option),
+        )
+        .route(
+            "/:restaurant_menu_item_modifier_group_id/options/:restaurant_menu_item_modifier_option_id",
+            patch(update_restaurant_menu_item_modifier_option),
+        )
+        .route(
+            "/:restaurant_menu_item_modifier_group_id/options/:restaurant_menu_item_modifier_option_id",
+            delete(delete_restaurant_menu_item_modifier_option),
+        );
+
+    let restaurant_menu_versions_routes = Router::new()
+        .route("/", get(get_restaurant_menu_versions))
+        .route("/draft", post(create_restaurant_menu_draft))
+        .route("/draft", get(get_restaurant_menu_draft))
+        .route("/draft", patch(update_restaurant_menu_draft))
+        .route("/draft", delete(delete_restaurant_menu_draft))
+        .route("/draft/diff", get(get_restaurant_menu_draft_diff))
+        .route("/draft/publish", post(publish_restaurant_menu_draft))
+        .route(
+            "/:restaurant_menu_version_id",
+            get(get_restaurant_menu_version),
+        )
+        .route(
+            "/:restaurant_menu_version_id/rollback",
+            post(rollback_restaurant_menu_version),
+        );
+
     let restaurant_menus_routes = Router::new()
         .route("/", post(create_restaurant_menu))
         .route("/", get(get_restaurant_menus))
@@ -115,21 +255,240 @@ async fn main() {
             patch(disactivate_restaurant_menu),
         )
         .route("/:restaurant_menu_id", delete(delete_restaurant_menu))
+        .route("/:restaurant_menu_id/order", patch(reorder_restaurant_menu))
+        .route("/:restaurant_menu_id/clone", post(clone_restaurant_menu))
+        .route(
+            "/:restaurant_menu_id/translations",
+            get(get_restaurant_menu_translations),
+        )
+        .route(
+            "/:restaurant_menu_id/translations/:locale",
+            patch(upsert_restaurant_menu_translation),
+        )
+        .route(
+            "/:restaurant_menu_id/translations/:locale",
+            delete(delete_restaurant_menu_translation),
+        )
+        .route(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/translations",
+            get(get_restaurant_menu_item_translations),
+        )
+        .route(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/translations/:locale",
+            patch(upsert_restaurant_menu_item_translation),
+        )
+        .route(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/translations/:locale",
+            delete(delete_restaurant_menu_item_translation),
+        )
+        .route(
+            "/:restaurant_menu_id/items/copy",
+            post(copy_restaurant_menu_items),
+        )
+        .route(
+            "/:restaurant_menu_id/schedules",
+            get(get_restaurant_menu_schedules),
+        )
+        .route(
+            "/:restaurant_menu_id/schedules",
+            post(create_restaurant_menu_schedule),
+        )
+        .route(
+            "/:restaurant_menu_id/schedules/:restaurant_menu_schedule_id",
+            delete(delete_restaurant_menu_schedule),
+        )
+        .route(
+            "/:restaurant_menu_id/sections",
+            post(create_restaurant_menu_section),
+        )
+        .route(
+            "/:restaurant_menu_id/sections/:restaurant_menu_section_id",
+            patch(update_restaurant_menu_section),
+        )
+        .route(
+            "/:restaurant_menu_id/sections/:restaurant_menu_section_id",
+            delete(delete_restaurant_menu_section),
+        )
+        .route(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/dietary",
+            patch(update_restaurant_menu_item_dietary_info),
+        )
+        .route(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/availability",
+            patch(update_restaurant_menu_item_availability),
+        )
+        .route(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/stock",
+            patch(update_restaurant_menu_item_stock),
+        )
+        .route(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/stock/decrement",
+            post(decrement_restaurant_menu_item_stock),
+        )
+        .nest(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/modifiers",
+            restaurant_menu_item_modifiers_routes,
+        )
+        .nest(
+            "/:restaurant_menu_id/versions",
+            restaurant_menu_versions_routes,
+        )
         .nest(
             "/:restaurant_menu_id/items",
-            restaurant_menu_items_routes.clone(),
+            restaurant_menu_items_routes,
         );
 
     let restaurant_menus_public_routes = Router::new()
         .route("/", get(get_restaurant_menus_pub))
+        .route(
+            "/:restaurant_menu_id/sections",
+            get(get_restaurant_menu_sections),
+        )
+        .route(
+            "/:restaurant_menu_id/items/:restaurant_menu_item_id/modifiers",
+            get(get_restaurant_menu_item_modifiers),
+        )
         .nest(
             "/:restaurant_menu_id/items",
-            restaurant_menu_items_routes.clone(),
+            restaurant_menu_items_public_routes,
         );
 
+    let restaurant_photos_routes = Router::new()
+        .route("/", post(create_restaurant_photo))
+        .route("/order", patch(reorder_restaurant_photos))
+        .route("/:restaurant_photo_id", patch(update_restaurant_photo))
+        .route("/:restaurant_photo_id", delete(delete_restaurant_photo))
+        .route(
+            "/:restaurant_photo_id/cover",
+            patch(set_restaurant_cover_photo),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::RestaurantOwner),
+        ))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ))
+        .route("/", get(get_restaurant_photos));
+
+    let restaurant_delivery_zones_routes = Router::new()
+        .route("/", post(create_restaurant_delivery_zone))
+        .route(
+            "/:restaurant_delivery_zone_id",
+            patch(update_restaurant_delivery_zone),
+        )
+        .route(
+            "/:restaurant_delivery_zone_id",
+            delete(delete_restaurant_delivery_zone),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::RestaurantOwner),
+        ))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ))
+        .route("/", get(get_restaurant_delivery_zones))
+        .route("/check", get(check_restaurant_delivery));
+
+    let restaurant_review_reply_routes = Router::new()
+        .route("/", post(create_review_reply))
+        .route("/", patch(update_review_reply))
+        .route("/", delete(delete_review_reply))
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::RestaurantOwner),
+        ));
+
+    let restaurant_reviews_routes = Router::new()
+        .route("/", post(create_review))
+        .route("/:review_id", patch(update_review))
+        .route("/:review_id", delete(delete_review))
+        .route("/:review_id/report", post(report_review))
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::User),
+        ))
+        .nest("/:review_id/reply", restaurant_review_reply_routes)
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ))
+        .route("/", get(get_restaurant_reviews));
+
     let restaurants_routes = Router::new()
         .route("/", post(create_restaurant))
         .route("/me", get(get_my_restaurants))
+        .route("/:restaurant_id/slug", patch(update_restaurant_slug))
+        .route(
+            "/:restaurant_id/timezone",
+            patch(update_restaurant_timezone),
+        )
+        .route(
+            "/:restaurant_id/currency",
+            patch(update_restaurant_currency),
+        )
+        .route(
+            "/:restaurant_id/description",
+            patch(update_restaurant_description),
+        )
+        .route(
+            "/:restaurant_id/translations",
+            get(get_restaurant_translations),
+        )
+        .route(
+            "/:restaurant_id/translations/:locale",
+            patch(upsert_restaurant_translation),
+        )
+        .route(
+            "/:restaurant_id/translations/:locale",
+            delete(delete_restaurant_translation),
+        )
+        .route(
+            "/:restaurant_id/transfers",
+            post(create_restaurant_transfer),
+        )
+        .route("/:restaurant_id/transfers", get(get_restaurant_transfers))
+        .route(
+            "/:restaurant_id/verification",
+            get(get_restaurant_verification),
+        )
+        .route(
+            "/:restaurant_id/verification",
+            post(send_restaurant_verification),
+        )
+        .route(
+            "/:restaurant_id/verification/confirm",
+            post(confirm_restaurant_verification),
+        )
+        .route("/:restaurant_id/publish", patch(publish_restaurant))
+        .route("/:restaurant_id/unpublish", patch(unpublish_restaurant))
+        .route(
+            "/:restaurant_id/chain",
+            patch(update_restaurant_chain_membership),
+        )
+        .route("/:restaurant_id/menu-export", get(export_restaurant_menus))
+        .route("/:restaurant_id/menu-import", post(import_restaurant_menus))
+        .route("/:restaurant_id/menu-pdf", get(get_restaurant_menu_pdf))
+        .route("/:restaurant_id/qr-code", get(get_restaurant_qr_code))
+        .route(
+            "/:restaurant_id/qr-code/sheet",
+            get(get_restaurant_qr_code_sheet),
+        )
+        .route(
+            "/:restaurant_id/overrides",
+            get(get_restaurant_menu_item_overrides),
+        )
+        .route(
+            "/:restaurant_id/overrides/:restaurant_menu_item_id",
+            patch(upsert_restaurant_menu_item_override),
+        )
+        .route(
+            "/:restaurant_id/overrides/:restaurant_menu_item_id",
+            delete(delete_restaurant_menu_item_override),
+        )
         .route_layer(middleware::from_fn_with_state(
             shared_state.clone(),
             |state, req, next| role_middleware(state, req, next, RolesEnum::RestaurantOwner),
@@ -140,10 +499,22 @@ async fn main() {
             shared_state.clone(),
             auth_middleware,
         ))
+        .route("/top", get(get_top_restaurants))
         .route("/:restaurant_id", get(get_restaurant))
         .nest("/:restaurant_id/menus", restaurant_menus_public_routes)
+        .route("/:restaurant_id/full-menu", get(get_restaurant_full_menu))
         .route("/:restaurant_id/meals", get(get_restaurant_meals))
-        .route("/", get(get_restaurants));
+        .nest("/:restaurant_id/reviews", restaurant_reviews_routes)
+        .nest("/:restaurant_id/photos", restaurant_photos_routes)
+        .nest(
+            "/:restaurant_id/delivery-zones",
+            restaurant_delivery_zones_routes,
+        )
+        .route("/", get(get_restaurants))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            optional_auth_middleware,
+        ));
 
     let users_routes = Router::new()
         .route("/", get(get_users))
@@ -169,6 +540,170 @@ async fn main() {
             auth_middleware,
         ));
 
+    let reviews_routes = Router::new()
+        .route("/moderation", get(get_reviews_moderation_queue))
+        .route("/:review_id/hide", patch(hide_review))
+        .route("/:review_id/restore", patch(restore_review))
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::Admin),
+        ))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ));
+
+    let favorites_routes = Router::new()
+        .route("/restaurants", get(get_favorite_restaurants))
+        .route("/restaurants/:restaurant_id", post(add_favorite_restaurant))
+        .route(
+            "/restaurants/:restaurant_id",
+            delete(remove_favorite_restaurant),
+        )
+        .route("/items", get(get_favorite_menu_items))
+        .route(
+            "/items/:restaurant_menu_item_id",
+            post(add_favorite_menu_item),
+        )
+        .route(
+            "/items/:restaurant_menu_item_id",
+            delete(remove_favorite_menu_item),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::User),
+        ))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ));
+
+    let user_lists_routes = Router::new()
+        .route("/", post(create_user_list))
+        .route("/me", get(get_my_user_lists))
+        .route("/:user_list_id", patch(update_user_list))
+        .route("/:user_list_id", delete(delete_user_list))
+        .route(
+            "/:user_list_id/share",
+            post(regenerate_user_list_share_token),
+        )
+        .route("/:user_list_id/share", delete(revoke_user_list_share_token))
+        .route(
+            "/:user_list_id/restaurants/:restaurant_id",
+            post(add_user_list_restaurant),
+        )
+        .route(
+            "/:user_list_id/restaurants/:restaurant_id",
+            delete(remove_user_list_restaurant),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::User),
+        ))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ))
+        .route("/:user_list_id", get(get_user_list))
+        .route("/shared/:share_token", get(get_shared_user_list))
+        .route("/users/:user_id", get(get_public_user_lists))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            optional_auth_middleware,
+        ));
+
+    let restaurant_transfers_routes = Router::new()
+        .route("/me", get(get_my_restaurant_transfers))
+        .route(
+            "/:restaurant_transfer_id/accept",
+            post(accept_restaurant_transfer),
+        )
+        .route(
+            "/:restaurant_transfer_id/decline",
+            post(decline_restaurant_transfer),
+        )
+        .route(
+            "/:restaurant_transfer_id/cancel",
+            post(cancel_restaurant_transfer),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::RestaurantOwner),
+        ))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ));
+
+    let restaurant_chains_routes = Router::new()
+        .route("/", post(create_restaurant_chain))
+        .route("/me", get(get_my_restaurant_chains))
+        .route("/:restaurant_chain_id", patch(update_restaurant_chain))
+        .route("/:restaurant_chain_id", delete(delete_restaurant_chain))
+        .route(
+            "/:restaurant_chain_id/menus",
+            get(get_restaurant_chain_menus),
+        )
+        .route(
+            "/:restaurant_chain_id/menus",
+            post(create_restaurant_chain_menu),
+        )
+        .route(
+            "/:restaurant_chain_id/menus/:restaurant_menu_id/activate",
+            patch(activate_restaurant_chain_menu),
+        )
+        .route(
+            "/:restaurant_chain_id/menus/:restaurant_menu_id/disactivate",
+            patch(disactivate_restaurant_chain_menu),
+        )
+        .route(
+            "/:restaurant_chain_id/menus/:restaurant_menu_id",
+            delete(delete_restaurant_chain_menu),
+        )
+        .route(
+            "/:restaurant_chain_id/menus/:restaurant_menu_id/items",
+            get(get_restaurant_chain_menu_items),
+        )
+        .route(
+            "/:restaurant_chain_id/menus/:restaurant_menu_id/items",
+            post(create_restaurant_chain_menu_item),
+        )
+        .route(
+            "/:restaurant_chain_id/menus/:restaurant_menu_id/items/:restaurant_menu_item_id",
+            delete(delete_restaurant_chain_menu_item),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::RestaurantOwner),
+        ))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ));
+
+    let trash_routes = Router::new()
+        .route("/", get(get_trash))
+        .route(
+            "/restaurants/:restaurant_id/restore",
+            patch(restore_restaurant),
+        )
+        .route(
+            "/menus/:restaurant_menu_id/restore",
+            patch(restore_restaurant_menu),
+        )
+        .route(
+            "/items/:restaurant_menu_item_id/restore",
+            patch(restore_restaurant_menu_item),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            |state, req, next| role_middleware(state, req, next, RolesEnum::RestaurantOwner),
+        ))
+        .layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            auth_middleware,
+        ));
+
     let auth_routes = Router::new()
         .route("/login", post(login))
         .route("/login/restaurant-owner", post(login_restaurant_owner))
@@ -186,6 +721,12 @@ async fn main() {
     let api_routes = Router::new()
         .nest("/restaurants", restaurants_routes)
         .nest("/users", users_routes)
+        .nest("/reviews", reviews_routes)
+        .nest("/favorites", favorites_routes)
+        .nest("/lists", user_lists_routes)
+        .nest("/chains", restaurant_chains_routes)
+        .nest("/trash", trash_routes)
+        .nest("/transfers", restaurant_transfers_routes)
         .nest("/auth", auth_routes)
         .nest("/files", files_routes)
         .route("/", get(hello));
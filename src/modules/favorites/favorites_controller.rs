@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use crate::{
+    common::money::Money,
+    modules::{
+        restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+        restaurants::{
+            restaurants_dto::RestaurantUser, restaurants_service::is_restaurant_visible,
+        },
+        shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use super::favorites_dto::{FavoriteMenuItem, FavoriteRestaurant};
+
+pub async fn get_favorite_restaurants(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<RestaurantUser>> {
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantUser,
+        r#"SELECT
+        restaurants.restaurant_id,
+        restaurants.name,
+        restaurants.slug,
+        restaurants.user_id,
+        restaurants.location,
+        restaurants.cover_image_uri,
+        restaurants.phone,
+        restaurants.email,
+        users.email AS user_email,
+        restaurants.rating_average,
+        restaurants.rating_count,
+        restaurants.rating_histogram,
+        restaurants.city,
+        restaurants.category,
+        restaurants.latitude,
+        restaurants.longitude,
+        restaurants.score,
+        restaurants.restaurant_chain_id,
+        restaurants.average_price,
+        restaurants.price_level,
+        restaurants.is_verified,
+        restaurants.is_published,
+        restaurants.timezone,
+        restaurants.currency,
+        restaurants.description,
+        restaurants.default_locale,
+        restaurants.created_at,
+        NULL::DOUBLE PRECISION AS distance_km,
+        true AS "is_favorite!"
+        FROM favorite_restaurants
+        JOIN restaurants ON favorite_restaurants.restaurant_id = restaurants.restaurant_id
+        JOIN users ON restaurants.user_id = users.user_id
+        WHERE favorite_restaurants.user_id = $1 AND restaurants.deleted_at IS NULL
+        AND (restaurants.is_published OR restaurants.user_id = $1)
+        ORDER BY favorite_restaurants.created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        current_user.user_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (favorite_restaurants.restaurant_id) FROM favorite_restaurants
+        JOIN restaurants ON favorite_restaurants.restaurant_id = restaurants.restaurant_id
+        where favorite_restaurants.user_id = $1 AND restaurants.deleted_at IS NULL
+        AND (restaurants.is_published OR restaurants.user_id = $1)",
+        current_user.user_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(restaurants) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: restaurants,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn add_favorite_restaurant(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+) -> AppResult<FavoriteRestaurant> {
+    match is_restaurant_visible(&state.db, restaurant_id, Some(&current_user)).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    // Favoriting twice is a no-op that returns the existing favorite.
+    let res = sqlx::query_as!(
+        FavoriteRestaurant,
+        "INSERT INTO favorite_restaurants (user_id,restaurant_id)
+        SELECT $1, restaurant_id FROM restaurants WHERE restaurant_id = $2 AND deleted_at IS NULL
+        ON CONFLICT (user_id, restaurant_id) DO UPDATE SET created_at = favorite_restaurants.created_at
+        RETURNING user_id,restaurant_id,created_at",
+        current_user.user_id,
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(favorite)) => AppResult::Result(StatusCode::CREATED, favorite),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn remove_favorite_restaurant(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+) -> AppResult<FavoriteRestaurant> {
+    let res = sqlx::query_as!(
+        FavoriteRestaurant,
+        "DELETE FROM favorite_restaurants WHERE user_id = $1 AND restaurant_id = $2 RETURNING user_id,restaurant_id,created_at",
+        current_user.user_id,
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(favorite)) => AppResult::Result(StatusCode::OK, favorite),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Favorite not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_favorite_menu_items(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<RestaurantMenuItem>> {
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"SELECT
+        rmi.restaurant_menu_item_id,
+        rmi.name,
+        menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "price!: Money",
+        rmi.description,
+        rmi.restaurant_menu_id,
+        rmi.cover_image_uri,
+        rmi.restaurant_menu_section_id,
+        rmi.position,
+        rmi.allergens,
+        rmi.diet_labels,
+        rmi.spicy_level,
+        rmi.calories,
+        rmi.protein_grams,
+        rmi.carbohydrate_grams,
+        rmi.fat_grams,
+        menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",
+        rmi.sold_out_until,
+        rmi.daily_stock,
+        rmi.remaining_stock,
+        true AS "is_favorite!"
+        FROM favorite_menu_items fmi
+        JOIN restaurant_menu_items rmi ON fmi.restaurant_menu_item_id = rmi.restaurant_menu_item_id
+        JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+        JOIN restaurants r ON rm.restaurant_id = r.restaurant_id
+        WHERE fmi.user_id = $1
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        ORDER BY fmi.created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        current_user.user_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (fmi.restaurant_menu_item_id) FROM favorite_menu_items fmi
+        JOIN restaurant_menu_items rmi ON fmi.restaurant_menu_item_id = rmi.restaurant_menu_item_id
+        JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+        JOIN restaurants r ON rm.restaurant_id = r.restaurant_id
+        WHERE fmi.user_id = $1
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL",
+        current_user.user_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(restaurant_menu_items) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: restaurant_menu_items,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn add_favorite_menu_item(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_menu_item_id): Path<i32>,
+) -> AppResult<FavoriteMenuItem> {
+    let res = sqlx::query_as!(
+        FavoriteMenuItem,
+        "INSERT INTO favorite_menu_items (user_id,restaurant_menu_item_id)
+        SELECT $1, restaurant_menu_item_id FROM restaurant_menu_items WHERE restaurant_menu_item_id = $2 AND deleted_at IS NULL
+        ON CONFLICT (user_id, restaurant_menu_item_id) DO UPDATE SET created_at = favorite_menu_items.created_at
+        RETURNING user_id,restaurant_menu_item_id,created_at",
+        current_user.user_id,
+        restaurant_menu_item_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(favorite)) => AppResult::Result(StatusCode::CREATED, favorite),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn remove_favorite_menu_item(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_menu_item_id): Path<i32>,
+) -> AppResult<FavoriteMenuItem> {
+    let res = sqlx::query_as!(
+        FavoriteMenuItem,
+        "DELETE FROM favorite_menu_items WHERE user_id = $1 AND restaurant_menu_item_id = $2 RETURNING user_id,restaurant_menu_item_id,created_at",
+        current_user.user_id,
+        restaurant_menu_item_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(favorite)) => AppResult::Result(StatusCode::OK, favorite),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Favorite not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+#[derive(Serialize, FromRow)]
+pub struct FavoriteRestaurant {
+    pub user_id: i64,
+    pub restaurant_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct FavoriteMenuItem {
+    pub user_id: i64,
+    pub restaurant_menu_item_id: i64,
+    pub created_at: DateTime<Utc>,
+}
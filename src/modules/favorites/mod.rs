@@ -0,0 +1,2 @@
+pub mod favorites_controller;
+pub mod favorites_dto;
@@ -5,6 +5,7 @@ use axum::{
     http::StatusCode,
 };
 use chrono::Utc;
+use rand::Rng;
 use tokio::{fs::File, io::AsyncWriteExt};
 
 use crate::{modules::shared::shared_dto::AppResult, AppState};
@@ -48,18 +49,94 @@ pub async fn upload(
 }
 
 pub fn delete_file(api_uri: String) {
+    // Only plain uploads are ever removed, whatever uri was stored
+    let Some(file_name) = upload_file_name(&api_uri) else {
+        return;
+    };
+
     // Assuming your project root is the current working directory
     let project_root = std::env::current_dir().expect("Failed to get current directory");
 
-    // Construct the full path by appending the relative path to the project root
-    let full_path = project_root
-        .join("public")
-        .join(api_uri.trim_start_matches("api/files/"));
+    // Construct the full path by appending the file name to the uploads folder
+    let full_path = project_root.join("public").join("uploads").join(file_name);
 
     // Attempt to remove the file
     if let Err(err) = fs::remove_file(&full_path) {
-        eprintln!("Error deleting file: {:?}", err);
+        tracing::error!("Error deleting file: {:?}", err);
     } else {
-        println!("File deleted successfully");
+        tracing::info!("File deleted successfully");
+    }
+}
+
+// The name of a plain upload in public/uploads, None for any other uri.
+pub fn upload_file_name(api_uri: &str) -> Option<&str> {
+    let file_name = api_uri.strip_prefix("api/files/uploads/")?;
+    if file_name.starts_with('.')
+        || !file_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    {
+        return None;
+    }
+
+    Some(file_name)
+}
+
+// Gives a copy its own file so deleting one leaves the other intact. Only
+// plain uploads are duplicated, anything else is shared as is.
+pub fn copy_file(api_uri: &str) -> Option<String> {
+    let file_name = upload_file_name(api_uri)?;
+    let (_, extension) = file_name.rsplit_once('.')?;
+
+    let copy_name = format!(
+        "{}_{}.{}",
+        Utc::now().timestamp(),
+        rand::thread_rng().gen::<u32>(),
+        extension
+    );
+    let uploads_dir = std::env::current_dir().ok()?.join("public").join("uploads");
+
+    match fs::copy(uploads_dir.join(file_name), uploads_dir.join(&copy_name)) {
+        Ok(_) => Some(format!("api/files/uploads/{}", copy_name)),
+        Err(err) => {
+            tracing::error!("Error copying file: {:?}", err);
+            None
+        }
+    }
+}
+
+pub async fn read_file(api_uri: &str) -> Option<Vec<u8>> {
+    let file_name = upload_file_name(api_uri)?;
+    let path = std::env::current_dir()
+        .ok()?
+        .join("public")
+        .join("uploads")
+        .join(file_name);
+
+    tokio::fs::read(path).await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_uploads_are_recognized() {
+        assert_eq!(
+            upload_file_name("api/files/uploads/1715000000_42.jpeg"),
+            Some("1715000000_42.jpeg")
+        );
+    }
+
+    #[test]
+    fn other_uris_are_left_alone() {
+        for api_uri in [
+            "https://cdn.example.com/1715000000.jpeg",
+            "api/files/uploads/../.env",
+            "api/files/uploads/.hidden",
+            "api/files/uploads/nested/photo.jpeg",
+        ] {
+            assert_eq!(upload_file_name(api_uri), None, "{}", api_uri);
+        }
     }
 }
@@ -0,0 +1,24 @@
+use sqlx::PgConnection;
+
+// The same upload may be used in several places, a file is only removed once
+// nothing points at it anymore. Menu versions count too, rolling back brings
+// their item images back.
+pub async fn find_unreferenced_file_uris(
+    conn: &mut PgConnection,
+    file_uris: &[String],
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT uri AS "uri!" FROM UNNEST($1::TEXT[]) uri
+        WHERE NOT EXISTS (SELECT 1 FROM restaurant_menu_items WHERE cover_image_uri = uri)
+        AND NOT EXISTS (SELECT 1 FROM restaurants WHERE cover_image_uri = uri)
+        AND NOT EXISTS (SELECT 1 FROM restaurant_photos WHERE image_uri = uri)
+        AND NOT EXISTS (SELECT 1 FROM reviews WHERE uri = ANY(photo_uris))
+        AND NOT EXISTS (
+            SELECT 1 FROM restaurant_menu_versions, jsonb_array_elements(content->'items') item
+            WHERE item->>'cover_image_uri' = uri
+        )"#,
+        file_uris
+    )
+    .fetch_all(&mut *conn)
+    .await
+}
@@ -1,2 +1,3 @@
 pub mod files_controller;
 pub mod files_dto;
+pub mod files_service;
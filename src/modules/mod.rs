@@ -1,7 +1,25 @@
 pub mod auth;
+pub mod favorites;
 pub mod files;
+pub mod ranking;
+pub mod restaurant_chains;
+pub mod restaurant_delivery_zones;
+pub mod restaurant_menu_imports;
+pub mod restaurant_menu_item_modifiers;
 pub mod restaurant_menu_items;
+pub mod restaurant_menu_pdfs;
+pub mod restaurant_menu_schedules;
+pub mod restaurant_menu_sections;
+pub mod restaurant_menu_versions;
 pub mod restaurant_menus;
+pub mod restaurant_photos;
+pub mod restaurant_qr_codes;
+pub mod restaurant_transfers;
+pub mod restaurant_verifications;
 pub mod restaurants;
+pub mod reviews;
 pub mod shared;
+pub mod translations;
+pub mod trash;
+pub mod user_lists;
 pub mod users;
@@ -0,0 +1 @@
+pub mod ranking_service;
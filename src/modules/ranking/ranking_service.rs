@@ -0,0 +1,197 @@
+use std::{sync::Arc, time::Duration};
+
+use sqlx::{Pool, Postgres};
+
+use crate::AppState;
+
+// How many "average" ratings every restaurant starts with, so a single 5-star
+// review does not put a new restaurant above one with hundreds of 4.8s.
+const PRIOR_WEIGHT: f64 = 10.0;
+const DEFAULT_MEAN_RATING: f64 = 3.0;
+const RECENT_REVIEWS_DAYS: f64 = 30.0;
+
+const RATING_WEIGHT: f64 = 0.55;
+const VOLUME_WEIGHT: f64 = 0.15;
+const RECENCY_WEIGHT: f64 = 0.15;
+const POPULARITY_WEIGHT: f64 = 0.15;
+
+pub struct RankingSignals {
+    pub rating_count: i64,
+    pub rating_sum: i64,
+    pub recent_review_count: i64,
+    pub view_count: i64,
+}
+
+pub fn bayesian_average(rating_sum: i64, rating_count: i64, mean_rating: f64) -> f64 {
+    (PRIOR_WEIGHT * mean_rating + rating_sum as f64) / (PRIOR_WEIGHT + rating_count as f64)
+}
+
+// Every signal is squashed into [0, 1] before weighting, so the final score is
+// a number between 0 and 100.
+pub fn compute_score(signals: &RankingSignals, mean_rating: f64, max_view_count: i64) -> f64 {
+    let rating = bayesian_average(signals.rating_sum, signals.rating_count, mean_rating) / 5.0;
+    let volume = 1.0 - (-(signals.rating_count as f64) / 50.0).exp();
+    let recency = 1.0 - (-(signals.recent_review_count as f64) / 10.0).exp();
+    let popularity = match max_view_count {
+        0 => 0.0,
+        max_view_count => {
+            (1.0 + signals.view_count as f64).ln() / (1.0 + max_view_count as f64).ln()
+        }
+    };
+
+    100.0
+        * (RATING_WEIGHT * rating
+            + VOLUME_WEIGHT * volume
+            + RECENCY_WEIGHT * recency
+            + POPULARITY_WEIGHT * popularity)
+}
+
+pub async fn recompute_scores(db: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT
+        restaurants.restaurant_id,
+        restaurants.rating_count,
+        restaurants.rating_sum,
+        restaurants.view_count,
+        COALESCE(recent.review_count, 0) AS "recent_review_count!"
+        FROM restaurants
+        LEFT JOIN (
+            SELECT restaurant_id, COUNT(review_id) AS review_count
+            FROM reviews
+            WHERE status <> 'Hidden'
+            AND created_at > NOW() - make_interval(days => $1)
+            GROUP BY restaurant_id
+        ) recent ON recent.restaurant_id = restaurants.restaurant_id"#,
+        RECENT_REVIEWS_DAYS as i32
+    )
+    .fetch_all(db)
+    .await?;
+
+    let total_count: i64 = rows.iter().map(|row| row.rating_count as i64).sum();
+    let total_sum: i64 = rows.iter().map(|row| row.rating_sum as i64).sum();
+    let mean_rating = match total_count {
+        0 => DEFAULT_MEAN_RATING,
+        total_count => total_sum as f64 / total_count as f64,
+    };
+    let max_view_count = rows
+        .iter()
+        .map(|row| row.view_count as i64)
+        .max()
+        .unwrap_or(0);
+
+    let (restaurant_ids, scores): (Vec<i32>, Vec<f64>) = rows
+        .iter()
+        .map(|row| {
+            let signals = RankingSignals {
+                rating_count: row.rating_count as i64,
+                rating_sum: row.rating_sum as i64,
+                recent_review_count: row.recent_review_count,
+                view_count: row.view_count as i64,
+            };
+            (
+                row.restaurant_id,
+                compute_score(&signals, mean_rating, max_view_count),
+            )
+        })
+        .unzip();
+
+    let res = sqlx::query!(
+        "UPDATE restaurants SET score = ranked.score, score_updated_at = NOW()
+        FROM UNNEST($1::INTEGER[], $2::DOUBLE PRECISION[]) AS ranked (restaurant_id, score)
+        WHERE restaurants.restaurant_id = ranked.restaurant_id",
+        &restaurant_ids,
+        &scores
+    )
+    .execute(db)
+    .await?;
+
+    Ok(res.rows_affected())
+}
+
+pub async fn run_ranking_task(state: Arc<AppState>) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(state.env.ranking_interval_seconds));
+
+    loop {
+        interval.tick().await;
+
+        match recompute_scores(&state.db).await {
+            Ok(count) => tracing::info!("Ranking recomputed for {} restaurants", count),
+            Err(err) => tracing::error!("Ranking recompute failed: {:?}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signals(rating_count: i64, rating_sum: i64) -> RankingSignals {
+        RankingSignals {
+            rating_count,
+            rating_sum,
+            recent_review_count: 0,
+            view_count: 0,
+        }
+    }
+
+    #[test]
+    fn bayesian_average_is_the_mean_without_reviews() {
+        assert_eq!(bayesian_average(0, 0, 3.7), 3.7);
+        assert_eq!(bayesian_average(0, 0, DEFAULT_MEAN_RATING), 3.0);
+    }
+
+    #[test]
+    fn bayesian_average_moves_from_the_prior_to_the_reviews() {
+        // One 5-star review barely moves a 3.0 prior.
+        let one_review = bayesian_average(5, 1, 3.0);
+        assert!((one_review - 35.0 / 11.0).abs() < 1e-9);
+        // Hundreds of 4.8s outweigh it almost completely.
+        let many_reviews = bayesian_average(4800, 1000, 3.0);
+        assert!(many_reviews > 4.78 && many_reviews < 4.8);
+        assert!(many_reviews > one_review);
+    }
+
+    #[test]
+    fn compute_score_without_reviews_or_views_only_counts_the_prior() {
+        let score = compute_score(&signals(0, 0), 3.0, 0);
+        assert!((score - 100.0 * RATING_WEIGHT * 3.0 / 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compute_score_stays_between_0_and_100() {
+        let best = RankingSignals {
+            rating_count: 100_000,
+            rating_sum: 500_000,
+            recent_review_count: 10_000,
+            view_count: 1_000_000,
+        };
+        let score = compute_score(&best, 5.0, 1_000_000);
+        assert!(score <= 100.0 && score > 99.0, "{}", score);
+        assert!(compute_score(&signals(10, 10), 1.0, 0) >= 0.0);
+    }
+
+    #[test]
+    fn compute_score_orders_by_rating_volume_recency_and_views() {
+        let mean_rating = 4.0;
+        let score = |signals: &RankingSignals| compute_score(signals, mean_rating, 1000);
+
+        // A single 5-star review does not beat hundreds of 4.8s.
+        assert!(score(&signals(300, 1440)) > score(&signals(1, 5)));
+        // Same average, more reviews ranks higher.
+        assert!(score(&signals(40, 180)) > score(&signals(20, 90)));
+        // Better ratings rank higher at the same volume.
+        assert!(score(&signals(20, 90)) > score(&signals(20, 60)));
+
+        let recent = RankingSignals {
+            recent_review_count: 5,
+            ..signals(20, 90)
+        };
+        assert!(score(&recent) > score(&signals(20, 90)));
+        let viewed = RankingSignals {
+            view_count: 500,
+            ..signals(20, 90)
+        };
+        assert!(score(&viewed) > score(&signals(20, 90)));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod restaurant_chains_controller;
+pub mod restaurant_chains_dto;
+pub mod restaurant_chains_service;
@@ -0,0 +1,914 @@
+use std::sync::Arc;
+
+use crate::{
+    common::money::Money,
+    modules::{
+        files::{files_controller::delete_file, files_service::find_unreferenced_file_uris},
+        restaurant_menu_items::restaurant_menu_items_dto::{
+            CreateRestaurantMenuItem, RestaurantMenuItem,
+        },
+        restaurant_menus::restaurant_menus_dto::{CreateRestaurantMenu, RestaurantMenu},
+        restaurants::{
+            restaurants_dto::Restaurant,
+            restaurants_service::{
+                check_restaurant_access, find_menu_currency_exponent,
+                find_restaurant_currency_exponent, is_valid_currency, refresh_menu_price_levels,
+                refresh_restaurant_price_level,
+            },
+        },
+        shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use super::{
+    restaurant_chains_dto::{
+        CreateRestaurantChain, RestaurantChain, RestaurantMenuItemOverride, UpdateRestaurantChain,
+        UpdateRestaurantChainMembership, UpsertRestaurantMenuItemOverride,
+    },
+    restaurant_chains_service::can_manage_restaurant_chain,
+};
+
+async fn check_restaurant_chain_access<T>(
+    state: &AppState,
+    restaurant_chain_id: i32,
+    current_user: &User,
+) -> Result<(), AppResult<T>> {
+    match can_manage_restaurant_chain(&state.db, restaurant_chain_id, current_user).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Chain not found!"),
+        )),
+        Err(_) => Err(AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )),
+    }
+}
+
+pub async fn get_my_restaurant_chains(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<RestaurantChain>> {
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantChain,
+        r#"SELECT
+        rc.restaurant_chain_id,
+        rc.name,
+        rc.user_id,
+        rc.currency,
+        rc.created_at,
+        (
+            SELECT COUNT(*) FROM restaurants r
+            WHERE r.restaurant_chain_id = rc.restaurant_chain_id AND r.deleted_at IS NULL
+        ) AS "restaurant_count!"
+        FROM restaurant_chains rc
+        WHERE rc.user_id = $1
+        ORDER BY rc.created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        current_user.user_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (restaurant_chain_id) FROM restaurant_chains where user_id = $1",
+        current_user.user_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(restaurant_chains) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: restaurant_chains,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_restaurant_chain(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Json(create_restaurant_chain_dto): Json<CreateRestaurantChain>,
+) -> AppResult<RestaurantChain> {
+    let name = create_restaurant_chain_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("Chain name is required"),
+        );
+    }
+
+    let currency = create_restaurant_chain_dto
+        .currency
+        .map(|currency| currency.trim().to_uppercase())
+        .unwrap_or_else(|| String::from("EUR"));
+
+    match is_valid_currency(&state.db, &currency).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("currency must be a supported ISO 4217 code"),
+            )
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantChain,
+        r#"INSERT INTO restaurant_chains (name,user_id,currency) VALUES ($1,$2,$3)
+        RETURNING restaurant_chain_id,name,user_id,currency,created_at,0::BIGINT AS "restaurant_count!""#,
+        name,
+        current_user.user_id,
+        currency
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(restaurant_chain) => AppResult::Result(StatusCode::CREATED, restaurant_chain),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_restaurant_chain(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_chain_id): Path<i32>,
+    Json(update_restaurant_chain_dto): Json<UpdateRestaurantChain>,
+) -> AppResult<RestaurantChain> {
+    let name = update_restaurant_chain_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("Chain name is required"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantChain,
+        r#"UPDATE restaurant_chains SET name = $2
+        WHERE restaurant_chain_id = $1 AND (user_id = $3 OR $4)
+        RETURNING restaurant_chain_id,name,user_id,currency,created_at,
+        (
+            SELECT COUNT(*) FROM restaurants r
+            WHERE r.restaurant_chain_id = restaurant_chains.restaurant_chain_id AND r.deleted_at IS NULL
+        ) AS "restaurant_count!""#,
+        restaurant_chain_id,
+        name,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_chain)) => AppResult::Result(StatusCode::OK, restaurant_chain),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Chain not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Branches are kept and simply leave the chain; the chain's own menus go with it.
+pub async fn delete_restaurant_chain(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_chain_id): Path<i32>,
+) -> AppResult<RestaurantChain> {
+    if let Err(err) =
+        check_restaurant_chain_access(&state, restaurant_chain_id, &current_user).await
+    {
+        return err;
+    }
+
+    let res: Result<(RestaurantChain, Vec<String>, Vec<i32>), sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let restaurant_ids = sqlx::query_scalar!(
+            "SELECT restaurant_id FROM restaurants WHERE restaurant_chain_id = $1",
+            restaurant_chain_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let file_uris = sqlx::query_scalar!(
+            r#"SELECT DISTINCT uri AS "uri!" FROM (
+                SELECT rmi.cover_image_uri AS uri FROM restaurant_menu_items rmi
+                JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+                WHERE rm.restaurant_chain_id = $1
+                UNION ALL
+                SELECT jsonb_array_elements(rmv.content->'items')->>'cover_image_uri' FROM restaurant_menu_versions rmv
+                JOIN restaurant_menus rm ON rmv.restaurant_menu_id = rm.restaurant_menu_id
+                WHERE rm.restaurant_chain_id = $1
+            ) files
+            WHERE uri IS NOT NULL AND uri <> ''"#,
+            restaurant_chain_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let restaurant_chain = sqlx::query_as!(
+            RestaurantChain,
+            r#"DELETE FROM restaurant_chains WHERE restaurant_chain_id = $1
+            RETURNING restaurant_chain_id,name,user_id,currency,created_at,0::BIGINT AS "restaurant_count!""#,
+            restaurant_chain_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let file_uris = find_unreferenced_file_uris(&mut tx, &file_uris).await?;
+
+        tx.commit().await?;
+
+        Ok((restaurant_chain, file_uris, restaurant_ids))
+    }
+    .await;
+
+    match res {
+        Ok((restaurant_chain, file_uris, restaurant_ids)) => {
+            for file_uri in file_uris {
+                delete_file(file_uri);
+            }
+            for restaurant_id in restaurant_ids {
+                refresh_restaurant_price_level(&state.db, restaurant_id).await;
+            }
+            AppResult::Result(StatusCode::OK, restaurant_chain)
+        }
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_chain_menus(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_chain_id): Path<i32>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<RestaurantMenu>> {
+    if let Err(err) =
+        check_restaurant_chain_access(&state, restaurant_chain_id, &current_user).await
+    {
+        return err;
+    }
+
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantMenu,
+        "SELECT restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id
+        FROM restaurant_menus
+        WHERE restaurant_chain_id = $1 AND deleted_at IS NULL
+        ORDER BY restaurant_menu_id asc
+        LIMIT $2 OFFSET $3",
+        restaurant_chain_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (restaurant_menu_id) FROM restaurant_menus where restaurant_chain_id = $1 AND deleted_at IS NULL",
+        restaurant_chain_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(restaurant_menus) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: restaurant_menus,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_restaurant_chain_menu(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_chain_id): Path<i32>,
+    Json(create_restaurant_menu_dto): Json<CreateRestaurantMenu>,
+) -> AppResult<RestaurantMenu> {
+    if let Err(err) =
+        check_restaurant_chain_access(&state, restaurant_chain_id, &current_user).await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenu,
+        "INSERT INTO restaurant_menus (name,is_active,restaurant_chain_id) VALUES ($1,$2,$3)
+        RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
+        create_restaurant_menu_dto.name,
+        create_restaurant_menu_dto.is_active,
+        restaurant_chain_id
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(restaurant_menu) => AppResult::Result(StatusCode::CREATED, restaurant_menu),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+async fn set_restaurant_chain_menu_active(
+    state: &AppState,
+    current_user: &User,
+    restaurant_chain_id: i32,
+    restaurant_menu_id: i32,
+    is_active: bool,
+) -> AppResult<RestaurantMenu> {
+    if let Err(err) = check_restaurant_chain_access(state, restaurant_chain_id, current_user).await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenu,
+        "UPDATE restaurant_menus SET is_active = $3
+        WHERE restaurant_menu_id = $1 AND restaurant_chain_id = $2 AND deleted_at IS NULL
+        RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
+        restaurant_menu_id,
+        restaurant_chain_id,
+        is_active
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu)) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::OK, restaurant_menu)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn activate_restaurant_chain_menu(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_chain_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenu> {
+    set_restaurant_chain_menu_active(
+        &state,
+        &current_user,
+        restaurant_chain_id,
+        restaurant_menu_id,
+        true,
+    )
+    .await
+}
+
+pub async fn disactivate_restaurant_chain_menu(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_chain_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenu> {
+    set_restaurant_chain_menu_active(
+        &state,
+        &current_user,
+        restaurant_chain_id,
+        restaurant_menu_id,
+        false,
+    )
+    .await
+}
+
+// Soft deleted like branch menus, so the trash purge takes care of the files.
+pub async fn delete_restaurant_chain_menu(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_chain_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenu> {
+    if let Err(err) =
+        check_restaurant_chain_access(&state, restaurant_chain_id, &current_user).await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenu,
+        "UPDATE restaurant_menus SET deleted_at = NOW()
+        WHERE restaurant_menu_id = $1 AND restaurant_chain_id = $2 AND deleted_at IS NULL
+        RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
+        restaurant_menu_id,
+        restaurant_chain_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu)) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::OK, restaurant_menu)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_chain_menu_items(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_chain_id, restaurant_menu_id)): Path<(i32, i32)>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<RestaurantMenuItem>> {
+    if let Err(err) =
+        check_restaurant_chain_access(&state, restaurant_chain_id, &current_user).await
+    {
+        return err;
+    }
+
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"SELECT rmi.restaurant_menu_item_id,rmi.name,menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "price!: Money",rmi.description,rmi.restaurant_menu_id,rmi.cover_image_uri,rmi.restaurant_menu_section_id,rmi.position,rmi.allergens,rmi.diet_labels,rmi.spicy_level,rmi.calories,rmi.protein_grams,rmi.carbohydrate_grams,rmi.fat_grams,menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",rmi.sold_out_until,rmi.daily_stock,rmi.remaining_stock,false AS "is_favorite!"
+        FROM restaurant_menu_items rmi
+        JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+        LEFT JOIN restaurant_menu_sections rms ON rms.restaurant_menu_section_id = rmi.restaurant_menu_section_id
+        WHERE rm.restaurant_menu_id = $1 AND rm.restaurant_chain_id = $2
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL
+        ORDER BY rms.position ASC NULLS FIRST, rms.restaurant_menu_section_id ASC NULLS FIRST,
+        rmi.position ASC, rmi.restaurant_menu_item_id ASC
+        LIMIT $3 OFFSET $4"#,
+        restaurant_menu_id,
+        restaurant_chain_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (rmi.restaurant_menu_item_id) FROM restaurant_menu_items rmi
+        JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+        WHERE rm.restaurant_menu_id = $1 AND rm.restaurant_chain_id = $2
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL",
+        restaurant_menu_id,
+        restaurant_chain_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(restaurant_menu_items) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: restaurant_menu_items,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_restaurant_chain_menu_item(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_chain_id, restaurant_menu_id)): Path<(i32, i32)>,
+    Json(create_restaurant_menu_item_dto): Json<CreateRestaurantMenuItem>,
+) -> AppResult<RestaurantMenuItem> {
+    if let Err(err) =
+        check_restaurant_chain_access(&state, restaurant_chain_id, &current_user).await
+    {
+        return err;
+    }
+
+    let exponent = match find_menu_currency_exponent(&state.db, restaurant_menu_id).await {
+        Ok(Some(exponent)) => exponent,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let price_minor = match create_restaurant_menu_item_dto
+        .price
+        .to_minor_units(exponent)
+    {
+        Some(price_minor) if price_minor >= 0 => price_minor,
+        _ => {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("price must not be negative nor have more decimals than its currency"),
+            )
+        }
+    };
+
+    let dietary_info = &create_restaurant_menu_item_dto.dietary_info;
+    if !dietary_info.is_valid() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "spicy_level must be between 0 and 3 and nutrition facts must not be negative",
+            ),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"INSERT INTO restaurant_menu_items (name,description,restaurant_menu_id,cover_image_uri,price_minor,restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams)
+        SELECT $1,$2,$5,$3,$4,$7,COALESCE(MAX(position) + 1, 0),$8,$9,$10,$11,$12,$13,$14 FROM restaurant_menu_items
+        WHERE restaurant_menu_id = $5 AND restaurant_menu_section_id IS NOT DISTINCT FROM $7 AND deleted_at IS NULL
+        HAVING EXISTS (SELECT 1 FROM restaurant_menus WHERE restaurant_menu_id = $5 AND restaurant_chain_id = $6 AND deleted_at IS NULL)
+        AND ($7::INTEGER IS NULL OR EXISTS (SELECT 1 FROM restaurant_menu_sections WHERE restaurant_menu_section_id = $7 AND restaurant_menu_id = $5))
+        RETURNING restaurant_menu_item_id,name,description,restaurant_menu_id,cover_image_uri,menu_money(price_minor, restaurant_menu_id) AS "price!: Money",restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams,menu_item_availability(availability, sold_out_until, remaining_stock) AS "availability!",sold_out_until,daily_stock,remaining_stock,false AS "is_favorite!""#,
+        create_restaurant_menu_item_dto.name,
+        create_restaurant_menu_item_dto.description,
+        create_restaurant_menu_item_dto.cover_image_uri,
+        price_minor,
+        restaurant_menu_id,
+        restaurant_chain_id,
+        create_restaurant_menu_item_dto.restaurant_menu_section_id,
+        &dietary_info.allergen_names(),
+        &dietary_info.diet_label_names(),
+        dietary_info.spicy_level,
+        dietary_info.calories,
+        dietary_info.protein_grams,
+        dietary_info.carbohydrate_grams,
+        dietary_info.fat_grams
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item)) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::CREATED, restaurant_menu_item)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_chain_menu_item(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_chain_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(i32, i32, i32)>,
+) -> AppResult<RestaurantMenuItem> {
+    if let Err(err) =
+        check_restaurant_chain_access(&state, restaurant_chain_id, &current_user).await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"UPDATE restaurant_menu_items SET deleted_at = NOW()
+        WHERE restaurant_menu_item_id = $1 AND restaurant_menu_id = $2 AND deleted_at IS NULL
+        AND restaurant_menu_id IN (SELECT restaurant_menu_id FROM restaurant_menus WHERE restaurant_chain_id = $3)
+        RETURNING restaurant_menu_item_id,name,description,restaurant_menu_id,cover_image_uri,menu_money(price_minor, restaurant_menu_id) AS "price!: Money",restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams,menu_item_availability(availability, sold_out_until, remaining_stock) AS "availability!",sold_out_until,daily_stock,remaining_stock,false AS "is_favorite!""#,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_chain_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item)) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::OK, restaurant_menu_item)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Only chains owned by the restaurant's owner can be joined, also when an
+// admin makes the change.
+pub async fn update_restaurant_chain_membership(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(update_restaurant_chain_membership_dto): Json<UpdateRestaurantChainMembership>,
+) -> AppResult<Restaurant> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    if let Some(restaurant_chain_id) = update_restaurant_chain_membership_dto.restaurant_chain_id {
+        let res = sqlx::query_scalar!(
+            r#"SELECT restaurant_chains.currency = restaurants.currency AS "is_same_currency!"
+            FROM restaurant_chains
+            JOIN restaurants ON restaurants.restaurant_id = $1
+            WHERE restaurant_chains.restaurant_chain_id = $2
+            AND restaurant_chains.user_id = restaurants.user_id"#,
+            restaurant_id,
+            restaurant_chain_id
+        )
+        .fetch_optional(&state.db)
+        .await;
+
+        match res {
+            Ok(Some(true)) => {}
+            Ok(Some(false)) => {
+                return AppResult::Error(
+                    StatusCode::CONFLICT,
+                    String::from("Restaurant currency must match the chain currency!"),
+                )
+            }
+            Ok(None) => {
+                return AppResult::Error(StatusCode::NOT_FOUND, String::from("Chain not found!"))
+            }
+            Err(_) => {
+                return AppResult::Error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    String::from("Something went wrong"),
+                )
+            }
+        }
+    }
+
+    let res = sqlx::query_as!(
+        Restaurant,
+        "UPDATE restaurants SET restaurant_chain_id = $2
+        WHERE restaurant_id = $1
+        AND ($2::INTEGER IS NULL OR EXISTS (
+            SELECT 1 FROM restaurant_chains
+            WHERE restaurant_chain_id = $2 AND restaurant_chains.user_id = restaurants.user_id
+        ))
+        RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
+        restaurant_id,
+        update_restaurant_chain_membership_dto.restaurant_chain_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant)) => {
+            refresh_restaurant_price_level(&state.db, restaurant_id).await;
+            AppResult::Result(StatusCode::OK, restaurant)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Chain not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_menu_item_overrides(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<RestaurantMenuItemOverride>> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantMenuItemOverride,
+        r#"SELECT
+        rmio.restaurant_id,
+        rmio.restaurant_menu_item_id,
+        rmi.name,
+        menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "base_price!: Money",
+        menu_money(rmio.price_minor, rmi.restaurant_menu_id) AS "price: Money",
+        rmio.is_available,
+        rmio.updated_at
+        FROM restaurant_menu_item_overrides rmio
+        JOIN restaurant_menu_items rmi ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id
+        WHERE rmio.restaurant_id = $1 AND rmi.deleted_at IS NULL
+        ORDER BY rmio.restaurant_menu_item_id asc
+        LIMIT $2 OFFSET $3"#,
+        restaurant_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (rmio.restaurant_menu_item_id) FROM restaurant_menu_item_overrides rmio
+        JOIN restaurant_menu_items rmi ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id
+        WHERE rmio.restaurant_id = $1 AND rmi.deleted_at IS NULL",
+        restaurant_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(overrides) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: overrides,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Overrides only make sense for items the branch inherits from its chain.
+pub async fn upsert_restaurant_menu_item_override(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_item_id)): Path<(i32, i32)>,
+    Json(upsert_override_dto): Json<UpsertRestaurantMenuItemOverride>,
+) -> AppResult<RestaurantMenuItemOverride> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    // Branches share their chain currency, so the branch one applies.
+    let exponent = match find_restaurant_currency_exponent(&state.db, restaurant_id).await {
+        Ok(Some(exponent)) => exponent,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let price_minor = match &upsert_override_dto.price {
+        Some(price) => match price.to_minor_units(exponent) {
+            Some(price_minor) if price_minor >= 0 => Some(price_minor),
+            _ => return AppResult::Error(StatusCode::BAD_REQUEST, String::from("Invalid price")),
+        },
+        None => None,
+    };
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItemOverride,
+        r#"WITH upserted AS (
+            INSERT INTO restaurant_menu_item_overrides (restaurant_id,restaurant_menu_item_id,price_minor,is_available)
+            SELECT r.restaurant_id, rmi.restaurant_menu_item_id, $3, $4
+            FROM restaurants r
+            JOIN restaurant_menus rm ON rm.restaurant_chain_id = r.restaurant_chain_id
+            JOIN restaurant_menu_items rmi ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+            WHERE r.restaurant_id = $1 AND rmi.restaurant_menu_item_id = $2
+            AND rm.deleted_at IS NULL AND rmi.deleted_at IS NULL
+            ON CONFLICT (restaurant_id, restaurant_menu_item_id)
+            DO UPDATE SET price_minor = EXCLUDED.price_minor, is_available = EXCLUDED.is_available, updated_at = NOW()
+            RETURNING restaurant_id,restaurant_menu_item_id,price_minor,is_available,updated_at
+        )
+        SELECT
+        upserted.restaurant_id,
+        upserted.restaurant_menu_item_id,
+        rmi.name,
+        menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "base_price!: Money",
+        menu_money(upserted.price_minor, rmi.restaurant_menu_id) AS "price: Money",
+        upserted.is_available,
+        upserted.updated_at
+        FROM upserted
+        JOIN restaurant_menu_items rmi ON upserted.restaurant_menu_item_id = rmi.restaurant_menu_item_id"#,
+        restaurant_id,
+        restaurant_menu_item_id,
+        price_minor,
+        upsert_override_dto.is_available
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item_override)) => {
+            refresh_restaurant_price_level(&state.db, restaurant_id).await;
+            AppResult::Result(StatusCode::OK, restaurant_menu_item_override)
+        }
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Chain menu item not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_menu_item_override(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_item_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenuItemOverride> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItemOverride,
+        r#"WITH deleted AS (
+            DELETE FROM restaurant_menu_item_overrides
+            WHERE restaurant_id = $1 AND restaurant_menu_item_id = $2
+            RETURNING restaurant_id,restaurant_menu_item_id,price_minor,is_available,updated_at
+        )
+        SELECT
+        deleted.restaurant_id,
+        deleted.restaurant_menu_item_id,
+        rmi.name,
+        menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "base_price!: Money",
+        menu_money(deleted.price_minor, rmi.restaurant_menu_id) AS "price: Money",
+        deleted.is_available,
+        deleted.updated_at
+        FROM deleted
+        JOIN restaurant_menu_items rmi ON deleted.restaurant_menu_item_id = rmi.restaurant_menu_item_id"#,
+        restaurant_id,
+        restaurant_menu_item_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item_override)) => {
+            refresh_restaurant_price_level(&state.db, restaurant_id).await;
+            AppResult::Result(StatusCode::OK, restaurant_menu_item_override)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Override not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
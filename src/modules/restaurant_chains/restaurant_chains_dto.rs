@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::common::money::{Money, MoneyInput};
+
+#[derive(Deserialize)]
+pub struct CreateRestaurantChain {
+    pub name: String,
+    // ISO 4217 code the chain menus are priced in, EUR when omitted.
+    pub currency: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantChain {
+    pub name: String,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantChain {
+    pub restaurant_chain_id: i64,
+    pub name: String,
+    pub user_id: i64,
+    pub currency: String,
+    pub created_at: DateTime<Utc>,
+    pub restaurant_count: i64,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantChainMembership {
+    pub restaurant_chain_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct UpsertRestaurantMenuItemOverride {
+    pub price: Option<MoneyInput>,
+    #[serde(default = "default_is_available")]
+    pub is_available: bool,
+}
+
+fn default_is_available() -> bool {
+    true
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantMenuItemOverride {
+    pub restaurant_id: i64,
+    pub restaurant_menu_item_id: i64,
+    pub name: String,
+    pub base_price: Money,
+    pub price: Option<Money>,
+    pub is_available: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_keep_items_available_unless_told_otherwise() {
+        let upsert_override: UpsertRestaurantMenuItemOverride =
+            serde_json::from_str(r#"{"price": null}"#).unwrap();
+        assert!(upsert_override.is_available);
+        assert!(upsert_override.price.is_none());
+
+        let upsert_override: UpsertRestaurantMenuItemOverride =
+            serde_json::from_str(r#"{"is_available": false}"#).unwrap();
+        assert!(!upsert_override.is_available);
+        assert!(upsert_override.price.is_none());
+    }
+
+    #[test]
+    fn membership_takes_a_chain_or_null_to_leave() {
+        let membership: UpdateRestaurantChainMembership =
+            serde_json::from_str(r#"{"restaurant_chain_id": null}"#).unwrap();
+        assert_eq!(membership.restaurant_chain_id, None);
+
+        let membership: UpdateRestaurantChainMembership =
+            serde_json::from_str(r#"{"restaurant_chain_id": 4}"#).unwrap();
+        assert_eq!(membership.restaurant_chain_id, Some(4));
+    }
+}
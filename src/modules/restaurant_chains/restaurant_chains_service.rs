@@ -0,0 +1,23 @@
+use sqlx::{Pool, Postgres};
+
+use crate::modules::users::users_dto::User;
+
+pub async fn can_manage_restaurant_chain(
+    db: &Pool<Postgres>,
+    restaurant_chain_id: i32,
+    user: &User,
+) -> Result<bool, sqlx::Error> {
+    let res = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM restaurant_chains
+            WHERE restaurant_chain_id = $1 AND (user_id = $2 OR $3)
+        ) AS "can_manage!""#,
+        restaurant_chain_id,
+        user.user_id,
+        user.role == "Admin"
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(res)
+}
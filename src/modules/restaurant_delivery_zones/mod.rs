@@ -0,0 +1,3 @@
+pub mod restaurant_delivery_zones_controller;
+pub mod restaurant_delivery_zones_dto;
+pub mod restaurant_delivery_zones_service;
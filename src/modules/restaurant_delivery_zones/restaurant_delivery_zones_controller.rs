@@ -0,0 +1,386 @@
+use std::sync::Arc;
+
+use crate::{
+    common::geo::{
+        is_valid_point, parse_geojson_polygons, polygons_bounding_box, radius_bounding_box, Point,
+    },
+    modules::{
+        restaurants::restaurants_service::{
+            check_restaurant_access, resolve_restaurant, RestaurantLookup,
+        },
+        shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use super::{
+    restaurant_delivery_zones_dto::{
+        CreateRestaurantDeliveryZone, DeliveryCheck, DeliveryCheckInput, RestaurantDeliveryZone,
+        UpdateRestaurantDeliveryZone,
+    },
+    restaurant_delivery_zones_service::find_delivery_zones_for_point,
+};
+
+const MAX_DELIVERY_RADIUS_KM: f64 = 100.0;
+
+fn is_valid_amount(amount: f64) -> bool {
+    amount.is_finite() && amount >= 0.0
+}
+
+pub async fn get_restaurant_delivery_zones(
+    State(state): State<Arc<AppState>>,
+    Path(restaurant_id): Path<String>,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<Vec<RestaurantDeliveryZone>> {
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let res = sqlx::query_as!(
+        RestaurantDeliveryZone,
+        "SELECT restaurant_delivery_zone_id,restaurant_id,name,zone_type,center_latitude,center_longitude,radius_km,polygon,minimum_order,delivery_fee,created_at
+        FROM restaurant_delivery_zones
+        WHERE restaurant_id = $1
+        AND restaurant_id IN (SELECT restaurant_id FROM restaurants WHERE deleted_at IS NULL)
+        ORDER BY delivery_fee ASC, restaurant_delivery_zone_id ASC",
+        restaurant_id
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match res {
+        Ok(zones) => AppResult::Result(StatusCode::OK, zones),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// When several zones cover the point the cheapest one the order qualifies
+// for wins.
+pub async fn check_restaurant_delivery(
+    State(state): State<Arc<AppState>>,
+    Path(restaurant_id): Path<String>,
+    Query(delivery_check_input): Query<DeliveryCheckInput>,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<DeliveryCheck> {
+    if !is_valid_point(delivery_check_input.lat, delivery_check_input.lng) {
+        return AppResult::Error(StatusCode::BAD_REQUEST, String::from("Invalid coordinates"));
+    }
+
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let point = Point {
+        latitude: delivery_check_input.lat,
+        longitude: delivery_check_input.lng,
+    };
+    let zones = match find_delivery_zones_for_point(&state.db, point, Some(restaurant_id)).await {
+        Ok(zones) => zones,
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let order_total = delivery_check_input.order_total;
+    let meets_minimum_order =
+        |zone: &RestaurantDeliveryZone| order_total.is_none_or(|total| total >= zone.minimum_order);
+    let zone = zones
+        .iter()
+        .filter(|zone| meets_minimum_order(zone))
+        .min_by(|a, b| a.delivery_fee.total_cmp(&b.delivery_fee))
+        .or_else(|| {
+            zones
+                .iter()
+                .min_by(|a, b| a.minimum_order.total_cmp(&b.minimum_order))
+        });
+
+    let delivery_check = match zone {
+        Some(zone) => DeliveryCheck {
+            delivers: true,
+            restaurant_delivery_zone_id: Some(zone.restaurant_delivery_zone_id),
+            zone_name: Some(zone.name.clone()),
+            minimum_order: Some(zone.minimum_order),
+            delivery_fee: Some(zone.delivery_fee),
+            meets_minimum_order: order_total.map(|_| meets_minimum_order(zone)),
+        },
+        None => DeliveryCheck {
+            delivers: false,
+            restaurant_delivery_zone_id: None,
+            zone_name: None,
+            minimum_order: None,
+            delivery_fee: None,
+            meets_minimum_order: None,
+        },
+    };
+
+    AppResult::Result(StatusCode::OK, delivery_check)
+}
+
+pub async fn create_restaurant_delivery_zone(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(create_zone_dto): Json<CreateRestaurantDeliveryZone>,
+) -> AppResult<RestaurantDeliveryZone> {
+    let name = create_zone_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("Zone name is required"),
+        );
+    }
+
+    if !is_valid_amount(create_zone_dto.minimum_order)
+        || !is_valid_amount(create_zone_dto.delivery_fee)
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("Minimum order and delivery fee must not be negative"),
+        );
+    }
+
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let (zone_type, center, bounding_box) =
+        match (create_zone_dto.radius_km, &create_zone_dto.polygon) {
+            (Some(radius_km), None) => {
+                if !radius_km.is_finite() || radius_km <= 0.0 || radius_km > MAX_DELIVERY_RADIUS_KM
+                {
+                    return AppResult::Error(
+                        StatusCode::BAD_REQUEST,
+                        format!(
+                            "radius_km must be greater than 0 and at most {}",
+                            MAX_DELIVERY_RADIUS_KM
+                        ),
+                    );
+                }
+
+                let center = match (
+                    create_zone_dto.center_latitude,
+                    create_zone_dto.center_longitude,
+                ) {
+                    (Some(latitude), Some(longitude)) => Some((latitude, longitude)),
+                    (None, None) => match sqlx::query!(
+                        "SELECT latitude, longitude FROM restaurants WHERE restaurant_id = $1",
+                        restaurant_id
+                    )
+                    .fetch_one(&state.db)
+                    .await
+                    {
+                        Ok(restaurant) => restaurant.latitude.zip(restaurant.longitude),
+                        Err(_) => {
+                            return AppResult::Error(
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                                String::from("Something went wrong"),
+                            )
+                        }
+                    },
+                    _ => None,
+                };
+
+                let center = match center {
+                    Some((latitude, longitude)) if is_valid_point(latitude, longitude) => Point {
+                        latitude,
+                        longitude,
+                    },
+                    _ => {
+                        return AppResult::Error(
+                            StatusCode::BAD_REQUEST,
+                            String::from(
+                                "A valid center is required when the restaurant has no coordinates",
+                            ),
+                        )
+                    }
+                };
+
+                (
+                    "Radius",
+                    Some(center),
+                    radius_bounding_box(center, radius_km),
+                )
+            }
+            (None, Some(polygon)) => match parse_geojson_polygons(polygon) {
+                Some(polygons) => ("Polygon", None, polygons_bounding_box(&polygons)),
+                None => {
+                    return AppResult::Error(
+                        StatusCode::BAD_REQUEST,
+                        String::from("polygon must be a valid GeoJSON Polygon or MultiPolygon"),
+                    )
+                }
+            },
+            _ => {
+                return AppResult::Error(
+                    StatusCode::BAD_REQUEST,
+                    String::from("Provide either radius_km or polygon"),
+                )
+            }
+        };
+
+    let res = sqlx::query_as!(
+        RestaurantDeliveryZone,
+        "INSERT INTO restaurant_delivery_zones
+        (restaurant_id,name,zone_type,center_latitude,center_longitude,radius_km,polygon,min_latitude,max_latitude,min_longitude,max_longitude,minimum_order,delivery_fee)
+        VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13)
+        RETURNING restaurant_delivery_zone_id,restaurant_id,name,zone_type,center_latitude,center_longitude,radius_km,polygon,minimum_order,delivery_fee,created_at",
+        restaurant_id,
+        name,
+        zone_type,
+        center.map(|center| center.latitude),
+        center.map(|center| center.longitude),
+        create_zone_dto.radius_km,
+        create_zone_dto.polygon,
+        bounding_box.min_latitude,
+        bounding_box.max_latitude,
+        bounding_box.min_longitude,
+        bounding_box.max_longitude,
+        create_zone_dto.minimum_order,
+        create_zone_dto.delivery_fee
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(zone) => AppResult::Result(StatusCode::CREATED, zone),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_restaurant_delivery_zone(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_delivery_zone_id)): Path<(i32, i32)>,
+    Json(update_zone_dto): Json<UpdateRestaurantDeliveryZone>,
+) -> AppResult<RestaurantDeliveryZone> {
+    if update_zone_dto
+        .name
+        .as_deref()
+        .is_some_and(|name| name.trim().is_empty())
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("Zone name is required"),
+        );
+    }
+
+    if !update_zone_dto.minimum_order.is_none_or(is_valid_amount)
+        || !update_zone_dto.delivery_fee.is_none_or(is_valid_amount)
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("Minimum order and delivery fee must not be negative"),
+        );
+    }
+
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantDeliveryZone,
+        "UPDATE restaurant_delivery_zones SET
+        name = COALESCE($3, name),
+        minimum_order = COALESCE($4, minimum_order),
+        delivery_fee = COALESCE($5, delivery_fee)
+        WHERE restaurant_delivery_zone_id = $1 AND restaurant_id = $2
+        RETURNING restaurant_delivery_zone_id,restaurant_id,name,zone_type,center_latitude,center_longitude,radius_km,polygon,minimum_order,delivery_fee,created_at",
+        restaurant_delivery_zone_id,
+        restaurant_id,
+        update_zone_dto.name.as_deref().map(str::trim),
+        update_zone_dto.minimum_order,
+        update_zone_dto.delivery_fee
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(zone)) => AppResult::Result(StatusCode::OK, zone),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Delivery zone not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_delivery_zone(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_delivery_zone_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantDeliveryZone> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantDeliveryZone,
+        "DELETE FROM restaurant_delivery_zones
+        WHERE restaurant_delivery_zone_id = $1 AND restaurant_id = $2
+        RETURNING restaurant_delivery_zone_id,restaurant_id,name,zone_type,center_latitude,center_longitude,radius_km,polygon,minimum_order,delivery_fee,created_at",
+        restaurant_delivery_zone_id,
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(zone)) => AppResult::Result(StatusCode::OK, zone),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Delivery zone not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
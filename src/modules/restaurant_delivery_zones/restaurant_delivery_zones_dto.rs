@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+// Either radius_km (around the given center, or the restaurant itself) or a
+// GeoJSON polygon.
+#[derive(Deserialize)]
+pub struct CreateRestaurantDeliveryZone {
+    pub name: String,
+    pub radius_km: Option<f64>,
+    pub center_latitude: Option<f64>,
+    pub center_longitude: Option<f64>,
+    pub polygon: Option<Value>,
+    #[serde(default)]
+    pub minimum_order: f64,
+    #[serde(default)]
+    pub delivery_fee: f64,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantDeliveryZone {
+    pub name: Option<String>,
+    pub minimum_order: Option<f64>,
+    pub delivery_fee: Option<f64>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantDeliveryZone {
+    pub restaurant_delivery_zone_id: i64,
+    pub restaurant_id: i64,
+    pub name: String,
+    pub zone_type: String,
+    pub center_latitude: Option<f64>,
+    pub center_longitude: Option<f64>,
+    pub radius_km: Option<f64>,
+    pub polygon: Option<Value>,
+    pub minimum_order: f64,
+    pub delivery_fee: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct DeliveryCheckInput {
+    pub lat: f64,
+    pub lng: f64,
+    pub order_total: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct DeliveryCheck {
+    pub delivers: bool,
+    pub restaurant_delivery_zone_id: Option<i64>,
+    pub zone_name: Option<String>,
+    pub minimum_order: Option<f64>,
+    pub delivery_fee: Option<f64>,
+    pub meets_minimum_order: Option<bool>,
+}
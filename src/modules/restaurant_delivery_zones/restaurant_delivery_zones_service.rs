@@ -0,0 +1,56 @@
+use sqlx::{Pool, Postgres};
+
+use crate::common::geo::{haversine_km, parse_geojson_polygons, polygons_contain, Point};
+
+use super::restaurant_delivery_zones_dto::RestaurantDeliveryZone;
+
+pub fn zone_contains(zone: &RestaurantDeliveryZone, point: Point) -> bool {
+    match (
+        zone.center_latitude,
+        zone.center_longitude,
+        zone.radius_km,
+        &zone.polygon,
+    ) {
+        (Some(latitude), Some(longitude), Some(radius_km), _) => {
+            haversine_km(
+                Point {
+                    latitude,
+                    longitude,
+                },
+                point,
+            ) <= radius_km
+        }
+        (_, _, _, Some(polygon)) => parse_geojson_polygons(polygon)
+            .map(|polygons| polygons_contain(&polygons, point))
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+// The bounding boxes narrow things down in SQL, the exact geometry check
+// happens here.
+pub async fn find_delivery_zones_for_point(
+    db: &Pool<Postgres>,
+    point: Point,
+    restaurant_id: Option<i32>,
+) -> Result<Vec<RestaurantDeliveryZone>, sqlx::Error> {
+    let zones = sqlx::query_as!(
+        RestaurantDeliveryZone,
+        "SELECT restaurant_delivery_zone_id,restaurant_id,name,zone_type,center_latitude,center_longitude,radius_km,polygon,minimum_order,delivery_fee,created_at
+        FROM restaurant_delivery_zones
+        WHERE $1 BETWEEN min_latitude AND max_latitude
+        AND $2 BETWEEN min_longitude AND max_longitude
+        AND ($3::INTEGER IS NULL OR restaurant_id = $3)
+        AND restaurant_id IN (SELECT restaurant_id FROM restaurants WHERE deleted_at IS NULL)",
+        point.latitude,
+        point.longitude,
+        restaurant_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(zones
+        .into_iter()
+        .filter(|zone| zone_contains(zone, point))
+        .collect())
+}
@@ -0,0 +1,3 @@
+pub mod restaurant_menu_imports_controller;
+pub mod restaurant_menu_imports_dto;
+pub mod restaurant_menu_imports_service;
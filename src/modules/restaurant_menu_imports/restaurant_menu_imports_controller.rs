@@ -0,0 +1,198 @@
+use std::sync::Arc;
+
+use crate::{
+    modules::{
+        restaurants::restaurants_service::{
+            check_restaurant_access, find_restaurant_currency_exponent,
+            refresh_restaurant_price_level,
+        },
+        shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Extension,
+};
+
+use super::{
+    restaurant_menu_imports_dto::{
+        ExportRestaurantMenusInput, ImportRestaurantMenusInput, MenuFile, MenuFileFormatEnum,
+        MenuImportReport, MENU_FILE_FORMAT_VERSION,
+    },
+    restaurant_menu_imports_service::{
+        find_restaurant_menu_file, import_menu_rows, menu_file_rows, menu_file_to_csv,
+        parse_menu_csv,
+    },
+};
+
+pub async fn export_restaurant_menus(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    export_input: Query<ExportRestaurantMenusInput>,
+) -> Response {
+    if let Err(err) = check_restaurant_access::<()>(&state.db, restaurant_id, &current_user).await {
+        return err.into_response();
+    }
+
+    let menu_file = match find_restaurant_menu_file(&state.db, restaurant_id).await {
+        Ok(menu_file) => menu_file,
+        Err(_) => {
+            return AppResult::<()>::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+            .into_response()
+        }
+    };
+
+    let (content_type, extension, body) = match export_input.format {
+        MenuFileFormatEnum::Csv => ("text/csv", "csv", menu_file_to_csv(&menu_file)),
+        MenuFileFormatEnum::Json => match serde_json::to_string_pretty(&menu_file) {
+            Ok(body) => ("application/json", "json", body),
+            Err(_) => {
+                return AppResult::<()>::Error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    String::from("Something went wrong"),
+                )
+                .into_response()
+            }
+        },
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (CONTENT_TYPE, String::from(content_type)),
+            (
+                CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"restaurant-{}-menus.{}\"",
+                    restaurant_id, extension
+                ),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+// The body is the raw file. Either every line is applied or none is: a file
+// with an error is reported line by line and nothing is saved.
+pub async fn import_restaurant_menus(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    import_input: Query<ImportRestaurantMenusInput>,
+    body: String,
+) -> AppResult<MenuImportReport> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let exponent = match find_restaurant_currency_exponent(&state.db, restaurant_id).await {
+        Ok(Some(exponent)) => exponent,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let (rows, errors) = match import_input.format {
+        MenuFileFormatEnum::Csv => parse_menu_csv(&body),
+        MenuFileFormatEnum::Json => {
+            let menu_file: MenuFile = match serde_json::from_str(&body) {
+                Ok(menu_file) => menu_file,
+                Err(err) => {
+                    return AppResult::Error(
+                        StatusCode::BAD_REQUEST,
+                        format!("file is not a valid menu file: {}", err),
+                    )
+                }
+            };
+
+            if menu_file.format_version != MENU_FILE_FORMAT_VERSION {
+                return AppResult::Error(
+                    StatusCode::BAD_REQUEST,
+                    format!("format_version must be {}", MENU_FILE_FORMAT_VERSION),
+                );
+            }
+
+            let is_same_currency = match &menu_file.currency {
+                Some(currency) => sqlx::query_scalar!(
+                    r#"SELECT EXISTS (
+                        SELECT 1 FROM restaurants WHERE restaurant_id = $1 AND currency = $2
+                    ) AS "is_same_currency!""#,
+                    restaurant_id,
+                    currency.to_uppercase()
+                )
+                .fetch_one(&state.db)
+                .await
+                .map_err(|_| ()),
+                None => Ok(true),
+            };
+            match is_same_currency {
+                Ok(true) => {}
+                Ok(false) => {
+                    return AppResult::Error(
+                        StatusCode::BAD_REQUEST,
+                        String::from("currency must be the currency of the restaurant"),
+                    )
+                }
+                Err(_) => {
+                    return AppResult::Error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        String::from("Something went wrong"),
+                    )
+                }
+            }
+
+            (menu_file_rows(menu_file), Vec::new())
+        }
+    };
+
+    let res: Result<MenuImportReport, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        let report = import_menu_rows(
+            &mut tx,
+            restaurant_id,
+            exponent,
+            rows,
+            errors,
+            import_input.dry_run,
+        )
+        .await?;
+
+        if report.is_applied {
+            tx.commit().await?;
+        }
+
+        Ok(report)
+    }
+    .await;
+
+    match res {
+        Ok(report) if report.is_applied => {
+            refresh_restaurant_price_level(&state.db, restaurant_id).await;
+            AppResult::Result(StatusCode::OK, report)
+        }
+        Ok(report) if report.dry_run => AppResult::Result(StatusCode::OK, report),
+        Ok(report) => AppResult::Result(StatusCode::BAD_REQUEST, report),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::money::MoneyInput;
+
+// Bumped whenever the JSON layout changes in a way older files can't follow.
+pub const MENU_FILE_FORMAT_VERSION: i32 = 1;
+
+// The CSV header, one row per item with its menu and section by name.
+// allergens and diet_labels are separated with "|".
+pub const MENU_CSV_COLUMNS: [&str; 15] = [
+    "menu",
+    "external_ref",
+    "action",
+    "section",
+    "name",
+    "description",
+    "price",
+    "cover_image_uri",
+    "allergens",
+    "diet_labels",
+    "spicy_level",
+    "calories",
+    "protein_grams",
+    "carbohydrate_grams",
+    "fat_grams",
+];
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuFileFormatEnum {
+    Csv,
+    #[default]
+    Json,
+}
+
+#[derive(Deserialize)]
+pub struct ExportRestaurantMenusInput {
+    #[serde(default)]
+    pub format: MenuFileFormatEnum,
+}
+
+#[derive(Deserialize)]
+pub struct ImportRestaurantMenusInput {
+    #[serde(default)]
+    pub format: MenuFileFormatEnum,
+    // Validates the whole file and reports what would change, without saving.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+// The versioned JSON file, also what a CSV file is read into.
+#[derive(Serialize, Deserialize)]
+pub struct MenuFile {
+    pub format_version: i32,
+    // Prices are in this currency, it must be the restaurant's.
+    pub currency: Option<String>,
+    pub menus: Vec<MenuFileMenu>,
+}
+
+// Menus are matched on their name, unknown ones are created inactive.
+#[derive(Serialize, Deserialize)]
+pub struct MenuFileMenu {
+    pub name: String,
+    pub items: Vec<MenuFileItem>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuImportActionEnum {
+    #[default]
+    Upsert,
+    Delete,
+}
+
+// Items are matched on external_ref within their menu. Exported items that
+// never had one use their id.
+#[derive(Serialize, Deserialize)]
+pub struct MenuFileItem {
+    pub external_ref: String,
+    #[serde(default)]
+    pub action: MenuImportActionEnum,
+    // Sections are matched on their name, unknown ones are created.
+    pub section: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub price: Option<MoneyInput>,
+    #[serde(default)]
+    pub cover_image_uri: String,
+    #[serde(default)]
+    pub allergens: Vec<String>,
+    #[serde(default)]
+    pub diet_labels: Vec<String>,
+    pub spicy_level: Option<i16>,
+    pub calories: Option<i32>,
+    pub protein_grams: Option<f64>,
+    pub carbohydrate_grams: Option<f64>,
+    pub fat_grams: Option<f64>,
+}
+
+// The CSV line the item starts on (the header is line 1), or for JSON files
+// the position of the item in the file counting from 1 across menus.
+pub struct MenuImportRow {
+    pub line: usize,
+    pub menu: String,
+    pub item: MenuFileItem,
+}
+
+#[derive(Serialize)]
+pub struct MenuImportError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct MenuImportReport {
+    pub dry_run: bool,
+    // Nothing is saved when a line has an error.
+    pub is_applied: bool,
+    pub created_menus: Vec<String>,
+    pub created_sections: usize,
+    pub created_items: usize,
+    pub updated_items: usize,
+    pub deleted_items: usize,
+    pub errors: Vec<MenuImportError>,
+}
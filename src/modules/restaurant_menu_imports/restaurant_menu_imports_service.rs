@@ -0,0 +1,687 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{PgConnection, Pool, Postgres};
+
+use crate::{
+    common::{
+        csv::{parse_csv, write_csv_record},
+        money::{format_minor_units, MoneyInput},
+    },
+    modules::restaurant_menu_items::restaurant_menu_items_dto::{
+        AllergenEnum, DietLabelEnum, UpdateRestaurantMenuItemDietaryInfo,
+    },
+};
+
+use super::restaurant_menu_imports_dto::{
+    MenuFile, MenuFileItem, MenuFileMenu, MenuImportActionEnum, MenuImportError, MenuImportReport,
+    MenuImportRow, MENU_CSV_COLUMNS, MENU_FILE_FORMAT_VERSION,
+};
+
+// The restaurant's own menus, chain menus are exported from the chain.
+pub async fn find_restaurant_menu_file(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+) -> Result<MenuFile, sqlx::Error> {
+    let currency = sqlx::query!(
+        "SELECT currencies.code,currencies.exponent FROM restaurants
+        JOIN currencies ON currencies.code = restaurants.currency
+        WHERE restaurants.restaurant_id = $1",
+        restaurant_id
+    )
+    .fetch_one(db)
+    .await?;
+
+    let menus = sqlx::query!(
+        "SELECT restaurant_menu_id,name FROM restaurant_menus
+        WHERE restaurant_id = $1 AND deleted_at IS NULL
+        ORDER BY restaurant_menu_id ASC",
+        restaurant_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let items = sqlx::query!(
+        r#"SELECT rmi.restaurant_menu_id,
+        COALESCE(rmi.external_ref, rmi.restaurant_menu_item_id::TEXT) AS "external_ref!",
+        rms.name AS "section?",rmi.name,rmi.description,rmi.price_minor,rmi.cover_image_uri,
+        rmi.allergens,rmi.diet_labels,rmi.spicy_level,rmi.calories,rmi.protein_grams,
+        rmi.carbohydrate_grams,rmi.fat_grams
+        FROM restaurant_menu_items rmi
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+        LEFT JOIN restaurant_menu_sections rms ON rms.restaurant_menu_section_id = rmi.restaurant_menu_section_id
+        WHERE rm.restaurant_id = $1 AND rm.deleted_at IS NULL AND rmi.deleted_at IS NULL
+        ORDER BY rmi.restaurant_menu_id ASC, rms.position ASC NULLS FIRST, rmi.position ASC, rmi.restaurant_menu_item_id ASC"#,
+        restaurant_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut items_by_menu: HashMap<i32, Vec<MenuFileItem>> = HashMap::new();
+    for item in items {
+        items_by_menu
+            .entry(item.restaurant_menu_id)
+            .or_default()
+            .push(MenuFileItem {
+                external_ref: item.external_ref,
+                action: MenuImportActionEnum::Upsert,
+                section: item.section,
+                name: item.name,
+                description: item.description,
+                price: Some(MoneyInput::Text(format_minor_units(
+                    item.price_minor,
+                    currency.exponent,
+                ))),
+                cover_image_uri: item.cover_image_uri,
+                allergens: item.allergens,
+                diet_labels: item.diet_labels,
+                spicy_level: item.spicy_level,
+                calories: item.calories,
+                protein_grams: item.protein_grams,
+                carbohydrate_grams: item.carbohydrate_grams,
+                fat_grams: item.fat_grams,
+            });
+    }
+
+    Ok(MenuFile {
+        format_version: MENU_FILE_FORMAT_VERSION,
+        currency: Some(currency.code),
+        menus: menus
+            .into_iter()
+            .map(|menu| MenuFileMenu {
+                name: menu.name,
+                items: items_by_menu
+                    .remove(&menu.restaurant_menu_id)
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    })
+}
+
+fn format_optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+// Menus without items have no row, so they are left out of CSV exports.
+pub fn menu_file_to_csv(menu_file: &MenuFile) -> String {
+    let mut csv = String::new();
+    write_csv_record(&mut csv, &MENU_CSV_COLUMNS);
+
+    for menu in &menu_file.menus {
+        for item in &menu.items {
+            let price = match &item.price {
+                Some(MoneyInput::Text(price)) => price.clone(),
+                Some(MoneyInput::Number(price)) => price.to_string(),
+                None => String::new(),
+            };
+
+            write_csv_record(
+                &mut csv,
+                &[
+                    &menu.name,
+                    &item.external_ref,
+                    match item.action {
+                        MenuImportActionEnum::Upsert => "upsert",
+                        MenuImportActionEnum::Delete => "delete",
+                    },
+                    item.section.as_deref().unwrap_or(""),
+                    &item.name,
+                    &item.description,
+                    &price,
+                    &item.cover_image_uri,
+                    &item.allergens.join("|"),
+                    &item.diet_labels.join("|"),
+                    &format_optional(item.spicy_level),
+                    &format_optional(item.calories),
+                    &format_optional(item.protein_grams),
+                    &format_optional(item.carbohydrate_grams),
+                    &format_optional(item.fat_grams),
+                ],
+            );
+        }
+    }
+
+    csv
+}
+
+fn parse_optional<T: std::str::FromStr>(value: &str, column: &str) -> Result<Option<T>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    value
+        .parse()
+        .map(Some)
+        .map_err(|_| format!("{} must be a number", column))
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split('|')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+// Columns can come in any order, only menu and external_ref are required.
+// Lines that can't be read are reported and left out.
+pub fn parse_menu_csv(text: &str) -> (Vec<MenuImportRow>, Vec<MenuImportError>) {
+    let records = match parse_csv(text) {
+        Ok(records) => records,
+        Err(line) => {
+            return (
+                Vec::new(),
+                vec![MenuImportError {
+                    line,
+                    message: String::from("quoted field is never closed"),
+                }],
+            )
+        }
+    };
+
+    let mut records = records.into_iter();
+    let Some((_, header)) = records.next() else {
+        return (
+            Vec::new(),
+            vec![MenuImportError {
+                line: 1,
+                message: String::from("file is empty"),
+            }],
+        );
+    };
+
+    let header: Vec<String> = header
+        .iter()
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+    let unknown_columns: Vec<&str> = header
+        .iter()
+        .map(|column| column.as_str())
+        .filter(|column| !MENU_CSV_COLUMNS.contains(column))
+        .collect();
+    if !unknown_columns.is_empty()
+        || !header.iter().any(|column| column == "menu")
+        || !header.iter().any(|column| column == "external_ref")
+    {
+        return (
+            Vec::new(),
+            vec![MenuImportError {
+                line: 1,
+                message: format!(
+                    "header must have the menu and external_ref columns and only known columns ({})",
+                    MENU_CSV_COLUMNS.join(",")
+                ),
+            }],
+        );
+    }
+
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for (line, record) in records {
+        if record.len() != header.len() {
+            errors.push(MenuImportError {
+                line,
+                message: format!("expected {} fields, got {}", header.len(), record.len()),
+            });
+            continue;
+        }
+
+        let fields: HashMap<&str, &str> = header
+            .iter()
+            .map(|column| column.as_str())
+            .zip(record.iter().map(|field| field.as_str()))
+            .collect();
+        let field = |column: &str| fields.get(column).copied().unwrap_or("");
+
+        let item: Result<MenuFileItem, String> = (|| {
+            Ok(MenuFileItem {
+                external_ref: field("external_ref").trim().to_string(),
+                action: match field("action").trim().to_lowercase().as_str() {
+                    "" | "upsert" => MenuImportActionEnum::Upsert,
+                    "delete" => MenuImportActionEnum::Delete,
+                    _ => return Err(String::from("action must be upsert or delete")),
+                },
+                section: Some(field("section").trim().to_string()),
+                name: field("name").trim().to_string(),
+                description: field("description").to_string(),
+                price: Some(field("price").trim())
+                    .filter(|price| !price.is_empty())
+                    .map(|price| MoneyInput::Text(price.to_string())),
+                cover_image_uri: field("cover_image_uri").trim().to_string(),
+                allergens: split_list(field("allergens")),
+                diet_labels: split_list(field("diet_labels")),
+                spicy_level: parse_optional(field("spicy_level"), "spicy_level")?,
+                calories: parse_optional(field("calories"), "calories")?,
+                protein_grams: parse_optional(field("protein_grams"), "protein_grams")?,
+                carbohydrate_grams: parse_optional(
+                    field("carbohydrate_grams"),
+                    "carbohydrate_grams",
+                )?,
+                fat_grams: parse_optional(field("fat_grams"), "fat_grams")?,
+            })
+        })();
+
+        match item {
+            Ok(item) => rows.push(MenuImportRow {
+                line,
+                menu: field("menu").trim().to_string(),
+                item,
+            }),
+            Err(message) => errors.push(MenuImportError { line, message }),
+        }
+    }
+
+    (rows, errors)
+}
+
+pub fn menu_file_rows(menu_file: MenuFile) -> Vec<MenuImportRow> {
+    menu_file
+        .menus
+        .into_iter()
+        .flat_map(|menu| {
+            let menu_name = menu.name;
+            menu.items
+                .into_iter()
+                .map(move |item| (menu_name.clone(), item))
+        })
+        .enumerate()
+        .map(|(index, (menu, item))| MenuImportRow {
+            line: index + 1,
+            menu: menu.trim().to_string(),
+            item,
+        })
+        .collect()
+}
+
+struct MenuItemUpsert {
+    restaurant_menu_item_id: Option<i32>,
+    menu: String,
+    section: Option<String>,
+    external_ref: String,
+    name: String,
+    description: String,
+    price_minor: i64,
+    cover_image_uri: String,
+    dietary_info: UpdateRestaurantMenuItemDietaryInfo,
+}
+
+enum MenuImportChange {
+    Delete(i32),
+    Upsert(Box<MenuItemUpsert>),
+}
+
+fn validate_row(
+    row: MenuImportRow,
+    exponent: i16,
+    restaurant_menu_id: Option<i32>,
+    item_ids: &HashMap<(i32, String), i32>,
+) -> Result<MenuImportChange, String> {
+    let MenuImportRow { menu, item, .. } = row;
+    let restaurant_menu_item_id = restaurant_menu_id.and_then(|restaurant_menu_id| {
+        item_ids
+            .get(&(restaurant_menu_id, item.external_ref.clone()))
+            .copied()
+    });
+
+    if item.action == MenuImportActionEnum::Delete {
+        return restaurant_menu_item_id
+            .map(MenuImportChange::Delete)
+            .ok_or_else(|| {
+                format!(
+                    "menu \"{}\" has no item with external_ref \"{}\"",
+                    menu, item.external_ref
+                )
+            });
+    }
+
+    if item.name.trim().is_empty() {
+        return Err(String::from("name is required"));
+    }
+
+    let price_minor = match item.price.map(|price| price.to_minor_units(exponent)) {
+        Some(Some(price_minor)) if price_minor >= 0 => price_minor,
+        Some(_) => {
+            return Err(String::from(
+                "price must not be negative nor have more decimals than its currency",
+            ))
+        }
+        None => return Err(String::from("price is required")),
+    };
+
+    let allergens = item
+        .allergens
+        .iter()
+        .map(|name| {
+            AllergenEnum::ALL
+                .iter()
+                .find(|allergen| allergen.as_str().eq_ignore_ascii_case(name))
+                .copied()
+                .ok_or_else(|| format!("unknown allergen \"{}\"", name))
+        })
+        .collect::<Result<Vec<AllergenEnum>, String>>()?;
+
+    let diet_labels = item
+        .diet_labels
+        .iter()
+        .map(|name| {
+            DietLabelEnum::ALL
+                .iter()
+                .find(|diet_label| diet_label.as_str().eq_ignore_ascii_case(name))
+                .copied()
+                .ok_or_else(|| format!("unknown diet label \"{}\"", name))
+        })
+        .collect::<Result<Vec<DietLabelEnum>, String>>()?;
+
+    let dietary_info = UpdateRestaurantMenuItemDietaryInfo {
+        allergens,
+        diet_labels,
+        spicy_level: item.spicy_level,
+        calories: item.calories,
+        protein_grams: item.protein_grams,
+        carbohydrate_grams: item.carbohydrate_grams,
+        fat_grams: item.fat_grams,
+    };
+    if !dietary_info.is_valid() {
+        return Err(String::from(
+            "spicy_level must be between 0 and 3 and nutrition facts must not be negative",
+        ));
+    }
+
+    Ok(MenuImportChange::Upsert(Box::new(MenuItemUpsert {
+        restaurant_menu_item_id,
+        menu,
+        section: item
+            .section
+            .map(|section| section.trim().to_string())
+            .filter(|section| !section.is_empty()),
+        external_ref: item.external_ref,
+        name: item.name.trim().to_string(),
+        description: item.description,
+        price_minor,
+        cover_image_uri: item.cover_image_uri,
+        dietary_info,
+    })))
+}
+
+// Checks every row against the restaurant's menus, then applies them unless
+// it is a dry run or a line has an error. The caller owns the transaction
+// and only commits when the report says it was applied.
+pub async fn import_menu_rows(
+    conn: &mut PgConnection,
+    restaurant_id: i32,
+    exponent: i16,
+    rows: Vec<MenuImportRow>,
+    mut errors: Vec<MenuImportError>,
+    dry_run: bool,
+) -> Result<MenuImportReport, sqlx::Error> {
+    // Menus sharing a name resolve to the oldest one.
+    let mut menu_ids: HashMap<String, i32> = HashMap::new();
+    for menu in sqlx::query!(
+        "SELECT restaurant_menu_id,name FROM restaurant_menus
+        WHERE restaurant_id = $1 AND deleted_at IS NULL
+        ORDER BY restaurant_menu_id DESC",
+        restaurant_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    {
+        menu_ids.insert(menu.name, menu.restaurant_menu_id);
+    }
+
+    let mut section_ids: HashMap<(i32, String), i32> = HashMap::new();
+    for section in sqlx::query!(
+        "SELECT rms.restaurant_menu_section_id,rms.restaurant_menu_id,rms.name
+        FROM restaurant_menu_sections rms
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rms.restaurant_menu_id
+        WHERE rm.restaurant_id = $1 AND rm.deleted_at IS NULL
+        ORDER BY rms.restaurant_menu_section_id DESC",
+        restaurant_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    {
+        section_ids.insert(
+            (section.restaurant_menu_id, section.name),
+            section.restaurant_menu_section_id,
+        );
+    }
+
+    // An explicit external_ref wins over an item id that looks the same.
+    let mut item_ids: HashMap<(i32, String), i32> = HashMap::new();
+    for item in sqlx::query!(
+        r#"SELECT rmi.restaurant_menu_item_id,rmi.restaurant_menu_id,
+        COALESCE(rmi.external_ref, rmi.restaurant_menu_item_id::TEXT) AS "external_ref!"
+        FROM restaurant_menu_items rmi
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+        WHERE rm.restaurant_id = $1 AND rm.deleted_at IS NULL AND rmi.deleted_at IS NULL
+        ORDER BY rmi.external_ref IS NOT NULL ASC, rmi.restaurant_menu_item_id ASC"#,
+        restaurant_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    {
+        item_ids.insert(
+            (item.restaurant_menu_id, item.external_ref),
+            item.restaurant_menu_item_id,
+        );
+    }
+
+    let mut seen_refs: HashMap<(String, String), usize> = HashMap::new();
+    let mut created_menus: Vec<String> = Vec::new();
+    let mut created_sections: HashSet<(String, String)> = HashSet::new();
+    let mut changes = Vec::new();
+    for row in rows {
+        let line = row.line;
+        if row.menu.is_empty() {
+            errors.push(MenuImportError {
+                line,
+                message: String::from("menu is required"),
+            });
+            continue;
+        }
+        if row.item.external_ref.trim().is_empty() {
+            errors.push(MenuImportError {
+                line,
+                message: String::from("external_ref is required"),
+            });
+            continue;
+        }
+
+        let ref_key = (row.menu.clone(), row.item.external_ref.clone());
+        if let Some(first_line) = seen_refs.get(&ref_key) {
+            errors.push(MenuImportError {
+                line,
+                message: format!(
+                    "external_ref \"{}\" is already used on line {} for this menu",
+                    row.item.external_ref, first_line
+                ),
+            });
+            continue;
+        }
+        seen_refs.insert(ref_key, line);
+
+        let restaurant_menu_id = menu_ids.get(&row.menu).copied();
+        match validate_row(row, exponent, restaurant_menu_id, &item_ids) {
+            Ok(change) => {
+                if let MenuImportChange::Upsert(upsert) = &change {
+                    let MenuItemUpsert { menu, section, .. } = upsert.as_ref();
+                    if restaurant_menu_id.is_none() && !created_menus.contains(menu) {
+                        created_menus.push(menu.clone());
+                    }
+                    if let Some(section) = section {
+                        let is_known = restaurant_menu_id.is_some_and(|restaurant_menu_id| {
+                            section_ids.contains_key(&(restaurant_menu_id, section.clone()))
+                        });
+                        if !is_known {
+                            created_sections.insert((menu.clone(), section.clone()));
+                        }
+                    }
+                }
+                changes.push(change);
+            }
+            Err(message) => errors.push(MenuImportError { line, message }),
+        }
+    }
+
+    errors.sort_by_key(|error| error.line);
+
+    let mut report = MenuImportReport {
+        dry_run,
+        is_applied: false,
+        created_menus,
+        created_sections: created_sections.len(),
+        created_items: changes
+            .iter()
+            .filter(|change| {
+                matches!(change, MenuImportChange::Upsert(upsert) if upsert.restaurant_menu_item_id.is_none())
+            })
+            .count(),
+        updated_items: changes
+            .iter()
+            .filter(|change| {
+                matches!(change, MenuImportChange::Upsert(upsert) if upsert.restaurant_menu_item_id.is_some())
+            })
+            .count(),
+        deleted_items: changes
+            .iter()
+            .filter(|change| matches!(change, MenuImportChange::Delete(_)))
+            .count(),
+        errors,
+    };
+
+    if dry_run || !report.errors.is_empty() {
+        return Ok(report);
+    }
+
+    for change in changes {
+        match change {
+            MenuImportChange::Delete(restaurant_menu_item_id) => {
+                sqlx::query!(
+                    "UPDATE restaurant_menu_items SET deleted_at = NOW()
+                    WHERE restaurant_menu_item_id = $1 AND deleted_at IS NULL",
+                    restaurant_menu_item_id
+                )
+                .execute(&mut *conn)
+                .await?;
+            }
+            MenuImportChange::Upsert(upsert) => {
+                let MenuItemUpsert {
+                    restaurant_menu_item_id,
+                    menu,
+                    section,
+                    external_ref,
+                    name,
+                    description,
+                    price_minor,
+                    cover_image_uri,
+                    dietary_info,
+                } = *upsert;
+                let restaurant_menu_id = match menu_ids.get(&menu) {
+                    Some(restaurant_menu_id) => *restaurant_menu_id,
+                    None => {
+                        let restaurant_menu_id = sqlx::query_scalar!(
+                            "INSERT INTO restaurant_menus (name,restaurant_id) VALUES ($1,$2)
+                            RETURNING restaurant_menu_id",
+                            menu,
+                            restaurant_id
+                        )
+                        .fetch_one(&mut *conn)
+                        .await?;
+                        menu_ids.insert(menu, restaurant_menu_id);
+                        restaurant_menu_id
+                    }
+                };
+
+                let restaurant_menu_section_id = match section {
+                    Some(section) => {
+                        match section_ids.get(&(restaurant_menu_id, section.clone())) {
+                            Some(restaurant_menu_section_id) => Some(*restaurant_menu_section_id),
+                            None => {
+                                // New sections go last.
+                                let restaurant_menu_section_id = sqlx::query_scalar!(
+                                "INSERT INTO restaurant_menu_sections (restaurant_menu_id,name,position)
+                                SELECT $1, $2, COALESCE(MAX(position) + 1, 0) FROM restaurant_menu_sections WHERE restaurant_menu_id = $1
+                                RETURNING restaurant_menu_section_id",
+                                restaurant_menu_id,
+                                section
+                            )
+                            .fetch_one(&mut *conn)
+                            .await?;
+                                section_ids.insert(
+                                    (restaurant_menu_id, section),
+                                    restaurant_menu_section_id,
+                                );
+                                Some(restaurant_menu_section_id)
+                            }
+                        }
+                    }
+                    None => None,
+                };
+
+                // Items moved to another section go last in it, the others
+                // keep their place.
+                match restaurant_menu_item_id {
+                    Some(restaurant_menu_item_id) => {
+                        sqlx::query!(
+                            "UPDATE restaurant_menu_items
+                            SET position = CASE
+                                WHEN restaurant_menu_section_id IS NOT DISTINCT FROM $6 THEN position
+                                ELSE (
+                                    SELECT COALESCE(MAX(position) + 1, 0) FROM restaurant_menu_items
+                                    WHERE restaurant_menu_id = $14 AND restaurant_menu_section_id IS NOT DISTINCT FROM $6 AND deleted_at IS NULL
+                                )
+                            END,
+                            external_ref = $1, name = $2, description = $3, price_minor = $4, cover_image_uri = $5,
+                            restaurant_menu_section_id = $6, allergens = $7, diet_labels = $8, spicy_level = $9,
+                            calories = $10, protein_grams = $11, carbohydrate_grams = $12, fat_grams = $13
+                            WHERE restaurant_menu_item_id = $15",
+                            external_ref,
+                            name,
+                            description,
+                            price_minor,
+                            cover_image_uri,
+                            restaurant_menu_section_id,
+                            &dietary_info.allergen_names(),
+                            &dietary_info.diet_label_names(),
+                            dietary_info.spicy_level,
+                            dietary_info.calories,
+                            dietary_info.protein_grams,
+                            dietary_info.carbohydrate_grams,
+                            dietary_info.fat_grams,
+                            restaurant_menu_id,
+                            restaurant_menu_item_id
+                        )
+                        .execute(&mut *conn)
+                        .await?;
+                    }
+                    None => {
+                        sqlx::query!(
+                            "INSERT INTO restaurant_menu_items (external_ref,name,description,price_minor,cover_image_uri,restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams,restaurant_menu_id)
+                            SELECT $1,$2,$3,$4,$5,$6,COALESCE(MAX(position) + 1, 0),$7,$8,$9,$10,$11,$12,$13,$14 FROM restaurant_menu_items
+                            WHERE restaurant_menu_id = $14 AND restaurant_menu_section_id IS NOT DISTINCT FROM $6 AND deleted_at IS NULL",
+                            external_ref,
+                            name,
+                            description,
+                            price_minor,
+                            cover_image_uri,
+                            restaurant_menu_section_id,
+                            &dietary_info.allergen_names(),
+                            &dietary_info.diet_label_names(),
+                            dietary_info.spicy_level,
+                            dietary_info.calories,
+                            dietary_info.protein_grams,
+                            dietary_info.carbohydrate_grams,
+                            dietary_info.fat_grams,
+                            restaurant_menu_id
+                        )
+                        .execute(&mut *conn)
+                        .await?;
+                    }
+                }
+            }
+        }
+    }
+
+    report.is_applied = true;
+    Ok(report)
+}
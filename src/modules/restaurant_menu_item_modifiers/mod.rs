@@ -0,0 +1,3 @@
+pub mod restaurant_menu_item_modifiers_controller;
+pub mod restaurant_menu_item_modifiers_dto;
+pub mod restaurant_menu_item_modifiers_service;
@@ -0,0 +1,627 @@
+use std::sync::Arc;
+
+use crate::{
+    common::money::{Money, MoneyInput},
+    modules::{
+        restaurants::restaurants_service::{resolve_restaurant, RestaurantLookup},
+        shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use super::{
+    restaurant_menu_item_modifiers_dto::{
+        CreateRestaurantMenuItemModifierGroup, CreateRestaurantMenuItemModifierOption,
+        RestaurantMenuItemModifierGroup, RestaurantMenuItemModifierGroupRow,
+        RestaurantMenuItemModifierOption, UpdateRestaurantMenuItemModifierGroup,
+        UpdateRestaurantMenuItemModifierOption,
+    },
+    restaurant_menu_item_modifiers_service::find_modifier_groups,
+};
+
+// Returns the minor unit digits of the menu currency, price deltas are
+// entered in it.
+async fn check_restaurant_menu_item_access<T>(
+    state: &AppState,
+    restaurant_id: i32,
+    restaurant_menu_id: i32,
+    restaurant_menu_item_id: i32,
+    current_user: &User,
+) -> Result<i16, AppResult<T>> {
+    let res = sqlx::query_scalar!(
+        r#"SELECT currencies.exponent
+        FROM restaurant_menu_items rmi
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+        JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        JOIN currencies ON currencies.code = r.currency
+        WHERE rmi.restaurant_menu_item_id = $1 AND rm.restaurant_menu_id = $2 AND rm.restaurant_id = $3
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND (r.user_id = $4 OR $5)"#,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(exponent)) => Ok(exponent),
+        Ok(None) => Err(AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Menu item not found!"),
+        )),
+        Err(_) => Err(AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )),
+    }
+}
+
+// Resolves an option price delta to minor units, None when it is invalid.
+// Deltas may be negative for cheaper variants.
+fn option_price_delta_minor(price_delta: Option<&MoneyInput>, exponent: i16) -> Option<i64> {
+    match price_delta {
+        Some(price_delta) => price_delta.to_minor_units(exponent),
+        None => Some(0),
+    }
+}
+
+pub async fn get_restaurant_menu_item_modifiers(
+    State(state): State<Arc<AppState>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(String, i32, i32)>,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<Vec<RestaurantMenuItemModifierGroup>> {
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let res = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM restaurant_menu_items rmi
+            JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+            JOIN restaurants r ON rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id
+            WHERE rmi.restaurant_menu_item_id = $1 AND rm.restaurant_menu_id = $2 AND r.restaurant_id = $3
+            AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        ) AS "exists!""#,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    match find_modifier_groups(&state.db, &[restaurant_menu_item_id]).await {
+        Ok(mut groups_by_item) => AppResult::Result(
+            StatusCode::OK,
+            groups_by_item
+                .remove(&restaurant_menu_item_id)
+                .unwrap_or_default(),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_restaurant_menu_item_modifier_group(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(i32, i32, i32)>,
+    Json(create_restaurant_menu_item_modifier_group_dto): Json<
+        CreateRestaurantMenuItemModifierGroup,
+    >,
+) -> AppResult<RestaurantMenuItemModifierGroup> {
+    let exponent = match check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        Ok(exponent) => exponent,
+        Err(err) => return err,
+    };
+
+    let CreateRestaurantMenuItemModifierGroup {
+        name,
+        selection_type,
+        is_required,
+        min_selections,
+        max_selections,
+        options,
+    } = create_restaurant_menu_item_modifier_group_dto;
+
+    let Some((min_selections, max_selections)) =
+        selection_type.selection_bounds(is_required, min_selections, max_selections)
+    else {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("min_selections must be at least 1 exactly when the group is required, max_selections must not be below min_selections and Single groups allow one selection"),
+        );
+    };
+
+    let price_deltas: Option<Vec<i64>> = options
+        .iter()
+        .map(|option| option_price_delta_minor(option.price_delta.as_ref(), exponent))
+        .collect();
+
+    let Some(price_deltas) = price_deltas.filter(|_| {
+        !name.trim().is_empty() && options.iter().all(|option| !option.name.trim().is_empty())
+    }) else {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty and price_delta must not have more decimals than the currency"),
+        );
+    };
+
+    let res: Result<RestaurantMenuItemModifierGroup, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let group = sqlx::query_as!(
+            RestaurantMenuItemModifierGroupRow,
+            "INSERT INTO restaurant_menu_item_modifier_groups (restaurant_menu_item_id,name,selection_type,is_required,min_selections,max_selections,position)
+            SELECT $1,$2,$3,$4,$5,$6,COALESCE(MAX(position) + 1, 0) FROM restaurant_menu_item_modifier_groups
+            WHERE restaurant_menu_item_id = $1
+            RETURNING restaurant_menu_item_modifier_group_id,restaurant_menu_item_id,name,selection_type,is_required,min_selections,max_selections,position",
+            restaurant_menu_item_id,
+            name.trim(),
+            selection_type.as_str(),
+            is_required,
+            min_selections,
+            max_selections
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let names: Vec<String> = options
+            .iter()
+            .map(|option| option.name.trim().to_string())
+            .collect();
+
+        let options = sqlx::query_as!(
+            RestaurantMenuItemModifierOption,
+            r#"INSERT INTO restaurant_menu_item_modifier_options (restaurant_menu_item_modifier_group_id,name,price_delta_minor,position)
+            SELECT $1,options.name,options.price_delta_minor,(options.position - 1)::INTEGER
+            FROM UNNEST($2::TEXT[],$3::BIGINT[]) WITH ORDINALITY AS options(name,price_delta_minor,position)
+            RETURNING restaurant_menu_item_modifier_option_id,restaurant_menu_item_modifier_group_id,name,menu_money(price_delta_minor, $4) AS "price_delta!: Money",position"#,
+            group.restaurant_menu_item_modifier_group_id as i32,
+            &names,
+            &price_deltas,
+            restaurant_menu_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(RestaurantMenuItemModifierGroup { group, options })
+    }
+    .await;
+
+    match res {
+        Ok(group) => AppResult::Result(StatusCode::CREATED, group),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_restaurant_menu_item_modifier_group(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        restaurant_menu_item_modifier_group_id,
+    )): Path<(i32, i32, i32, i32)>,
+    Json(update_restaurant_menu_item_modifier_group_dto): Json<
+        UpdateRestaurantMenuItemModifierGroup,
+    >,
+) -> AppResult<RestaurantMenuItemModifierGroupRow> {
+    if let Err(err) = check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        return err;
+    }
+
+    let UpdateRestaurantMenuItemModifierGroup {
+        name,
+        selection_type,
+        is_required,
+        min_selections,
+        max_selections,
+    } = update_restaurant_menu_item_modifier_group_dto;
+
+    let Some((min_selections, max_selections)) =
+        selection_type.selection_bounds(is_required, min_selections, max_selections)
+    else {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("min_selections must be at least 1 exactly when the group is required, max_selections must not be below min_selections and Single groups allow one selection"),
+        );
+    };
+
+    if name.trim().is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItemModifierGroupRow,
+        "UPDATE restaurant_menu_item_modifier_groups
+        SET name = $1, selection_type = $2, is_required = $3, min_selections = $4, max_selections = $5
+        WHERE restaurant_menu_item_modifier_group_id = $6 AND restaurant_menu_item_id = $7
+        RETURNING restaurant_menu_item_modifier_group_id,restaurant_menu_item_id,name,selection_type,is_required,min_selections,max_selections,position",
+        name.trim(),
+        selection_type.as_str(),
+        is_required,
+        min_selections,
+        max_selections,
+        restaurant_menu_item_modifier_group_id,
+        restaurant_menu_item_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(group)) => AppResult::Result(StatusCode::OK, group),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Modifier group not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_menu_item_modifier_group(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        restaurant_menu_item_modifier_group_id,
+    )): Path<(i32, i32, i32, i32)>,
+) -> AppResult<RestaurantMenuItemModifierGroupRow> {
+    if let Err(err) = check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItemModifierGroupRow,
+        "DELETE FROM restaurant_menu_item_modifier_groups
+        WHERE restaurant_menu_item_modifier_group_id = $1 AND restaurant_menu_item_id = $2
+        RETURNING restaurant_menu_item_modifier_group_id,restaurant_menu_item_id,name,selection_type,is_required,min_selections,max_selections,position",
+        restaurant_menu_item_modifier_group_id,
+        restaurant_menu_item_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(group)) => AppResult::Result(StatusCode::OK, group),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Modifier group not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_restaurant_menu_item_modifier_option(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        restaurant_menu_item_modifier_group_id,
+    )): Path<(i32, i32, i32, i32)>,
+    Json(create_restaurant_menu_item_modifier_option_dto): Json<
+        CreateRestaurantMenuItemModifierOption,
+    >,
+) -> AppResult<RestaurantMenuItemModifierOption> {
+    let exponent = match check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        Ok(exponent) => exponent,
+        Err(err) => return err,
+    };
+
+    let price_delta_minor = option_price_delta_minor(
+        create_restaurant_menu_item_modifier_option_dto
+            .price_delta
+            .as_ref(),
+        exponent,
+    );
+
+    let Some(price_delta_minor) = price_delta_minor.filter(|_| {
+        !create_restaurant_menu_item_modifier_option_dto
+            .name
+            .trim()
+            .is_empty()
+    }) else {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty and price_delta must not have more decimals than the currency"),
+        );
+    };
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItemModifierOption,
+        r#"INSERT INTO restaurant_menu_item_modifier_options (restaurant_menu_item_modifier_group_id,name,price_delta_minor,position)
+        SELECT $1,$2,$3,COALESCE(MAX(position) + 1, 0) FROM restaurant_menu_item_modifier_options
+        WHERE restaurant_menu_item_modifier_group_id = $1
+        HAVING EXISTS (
+            SELECT 1 FROM restaurant_menu_item_modifier_groups
+            WHERE restaurant_menu_item_modifier_group_id = $1 AND restaurant_menu_item_id = $4
+        )
+        RETURNING restaurant_menu_item_modifier_option_id,restaurant_menu_item_modifier_group_id,name,menu_money(price_delta_minor, $5) AS "price_delta!: Money",position"#,
+        restaurant_menu_item_modifier_group_id,
+        create_restaurant_menu_item_modifier_option_dto.name.trim(),
+        price_delta_minor,
+        restaurant_menu_item_id,
+        restaurant_menu_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(option)) => AppResult::Result(StatusCode::CREATED, option),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Modifier group not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_restaurant_menu_item_modifier_option(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        restaurant_menu_item_modifier_group_id,
+        restaurant_menu_item_modifier_option_id,
+    )): Path<(i32, i32, i32, i32, i32)>,
+    Json(update_restaurant_menu_item_modifier_option_dto): Json<
+        UpdateRestaurantMenuItemModifierOption,
+    >,
+) -> AppResult<RestaurantMenuItemModifierOption> {
+    let exponent = match check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        Ok(exponent) => exponent,
+        Err(err) => return err,
+    };
+
+    let name = update_restaurant_menu_item_modifier_option_dto
+        .name
+        .map(|name| name.trim().to_string());
+
+    let price_delta_minor = match &update_restaurant_menu_item_modifier_option_dto.price_delta {
+        Some(price_delta) => price_delta.to_minor_units(exponent),
+        None => None,
+    };
+
+    if name.as_deref().is_some_and(|name| name.is_empty())
+        || (update_restaurant_menu_item_modifier_option_dto
+            .price_delta
+            .is_some()
+            && price_delta_minor.is_none())
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty and price_delta must not have more decimals than the currency"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItemModifierOption,
+        r#"UPDATE restaurant_menu_item_modifier_options rmimo
+        SET name = COALESCE($1, rmimo.name), price_delta_minor = COALESCE($2, rmimo.price_delta_minor)
+        FROM restaurant_menu_item_modifier_groups rmimg
+        WHERE rmimo.restaurant_menu_item_modifier_option_id = $3
+        AND rmimo.restaurant_menu_item_modifier_group_id = $4
+        AND rmimg.restaurant_menu_item_modifier_group_id = rmimo.restaurant_menu_item_modifier_group_id
+        AND rmimg.restaurant_menu_item_id = $5
+        RETURNING rmimo.restaurant_menu_item_modifier_option_id,rmimo.restaurant_menu_item_modifier_group_id,rmimo.name,menu_money(rmimo.price_delta_minor, $6) AS "price_delta!: Money",rmimo.position"#,
+        name,
+        price_delta_minor,
+        restaurant_menu_item_modifier_option_id,
+        restaurant_menu_item_modifier_group_id,
+        restaurant_menu_item_id,
+        restaurant_menu_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(option)) => AppResult::Result(StatusCode::OK, option),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Modifier option not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_menu_item_modifier_option(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        restaurant_menu_item_modifier_group_id,
+        restaurant_menu_item_modifier_option_id,
+    )): Path<(i32, i32, i32, i32, i32)>,
+) -> AppResult<RestaurantMenuItemModifierOption> {
+    if let Err(err) = check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItemModifierOption,
+        r#"DELETE FROM restaurant_menu_item_modifier_options rmimo
+        USING restaurant_menu_item_modifier_groups rmimg
+        WHERE rmimo.restaurant_menu_item_modifier_option_id = $1
+        AND rmimo.restaurant_menu_item_modifier_group_id = $2
+        AND rmimg.restaurant_menu_item_modifier_group_id = rmimo.restaurant_menu_item_modifier_group_id
+        AND rmimg.restaurant_menu_item_id = $3
+        RETURNING rmimo.restaurant_menu_item_modifier_option_id,rmimo.restaurant_menu_item_modifier_group_id,rmimo.name,menu_money(rmimo.price_delta_minor, $4) AS "price_delta!: Money",rmimo.position"#,
+        restaurant_menu_item_modifier_option_id,
+        restaurant_menu_item_modifier_group_id,
+        restaurant_menu_item_id,
+        restaurant_menu_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(option)) => AppResult::Result(StatusCode::OK, option),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Modifier option not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price_delta(amount: &str) -> MoneyInput {
+        MoneyInput::Text(String::from(amount))
+    }
+
+    #[test]
+    fn price_deltas_may_lower_or_raise_the_price() {
+        assert_eq!(
+            option_price_delta_minor(Some(&price_delta("1.5")), 2),
+            Some(150)
+        );
+        assert_eq!(
+            option_price_delta_minor(Some(&price_delta("-2")), 2),
+            Some(-200)
+        );
+        assert_eq!(
+            option_price_delta_minor(Some(&price_delta("-0.250")), 3),
+            Some(-250)
+        );
+        assert_eq!(
+            option_price_delta_minor(Some(&price_delta("300")), 0),
+            Some(300)
+        );
+    }
+
+    #[test]
+    fn missing_price_deltas_cost_nothing() {
+        assert_eq!(option_price_delta_minor(None, 2), Some(0));
+    }
+
+    #[test]
+    fn price_deltas_follow_the_currency_precision() {
+        assert_eq!(option_price_delta_minor(Some(&price_delta("0.5")), 0), None);
+        assert_eq!(
+            option_price_delta_minor(Some(&price_delta("1.255")), 2),
+            None
+        );
+        assert_eq!(option_price_delta_minor(Some(&price_delta("abc")), 2), None);
+    }
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{
+    common::money::{Money, MoneyInput},
+    modules::restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+};
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+pub enum ModifierSelectionEnum {
+    Single,
+    Multiple,
+}
+
+impl ModifierSelectionEnum {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModifierSelectionEnum::Single => "Single",
+            ModifierSelectionEnum::Multiple => "Multiple",
+        }
+    }
+
+    // Resolves the (min, max) selections of a group, None when they
+    // contradict the selection type or is_required.
+    pub fn selection_bounds(
+        &self,
+        is_required: bool,
+        min_selections: Option<i32>,
+        max_selections: Option<i32>,
+    ) -> Option<(i32, Option<i32>)> {
+        let min_selections = min_selections.unwrap_or(if is_required { 1 } else { 0 });
+        let max_selections = match self {
+            ModifierSelectionEnum::Single => Some(max_selections.unwrap_or(1)),
+            ModifierSelectionEnum::Multiple => max_selections,
+        };
+
+        let is_valid = min_selections >= 0
+            && is_required == (min_selections >= 1)
+            && max_selections.is_none_or(|max_selections| max_selections >= min_selections.max(1))
+            && (*self == ModifierSelectionEnum::Multiple || max_selections == Some(1));
+
+        is_valid.then_some((min_selections, max_selections))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CreateRestaurantMenuItemModifierOption {
+    pub name: String,
+    // Added to the item price when picked, negative for cheaper variants.
+    // Defaults to 0.
+    pub price_delta: Option<MoneyInput>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantMenuItemModifierOption {
+    pub name: Option<String>,
+    pub price_delta: Option<MoneyInput>,
+}
+
+// min_selections defaults to 1 for required groups and 0 otherwise, Single
+// groups always allow one selection at most.
+#[derive(Deserialize)]
+pub struct CreateRestaurantMenuItemModifierGroup {
+    pub name: String,
+    pub selection_type: ModifierSelectionEnum,
+    #[serde(default)]
+    pub is_required: bool,
+    pub min_selections: Option<i32>,
+    pub max_selections: Option<i32>,
+    #[serde(default)]
+    pub options: Vec<CreateRestaurantMenuItemModifierOption>,
+}
+
+// Replaces the group settings, its options are left untouched.
+#[derive(Deserialize)]
+pub struct UpdateRestaurantMenuItemModifierGroup {
+    pub name: String,
+    pub selection_type: ModifierSelectionEnum,
+    #[serde(default)]
+    pub is_required: bool,
+    pub min_selections: Option<i32>,
+    pub max_selections: Option<i32>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantMenuItemModifierOption {
+    pub restaurant_menu_item_modifier_option_id: i64,
+    pub restaurant_menu_item_modifier_group_id: i32,
+    pub name: String,
+    pub price_delta: Money,
+    pub position: i32,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantMenuItemModifierGroupRow {
+    pub restaurant_menu_item_modifier_group_id: i64,
+    pub restaurant_menu_item_id: i32,
+    pub name: String,
+    pub selection_type: String,
+    pub is_required: bool,
+    pub min_selections: i32,
+    pub max_selections: Option<i32>,
+    pub position: i32,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantMenuItemModifierGroup {
+    #[serde(flatten)]
+    pub group: RestaurantMenuItemModifierGroupRow,
+    pub options: Vec<RestaurantMenuItemModifierOption>,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantMenuItemWithModifiers {
+    #[serde(flatten)]
+    pub item: RestaurantMenuItem,
+    pub modifier_groups: Vec<RestaurantMenuItemModifierGroup>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_bounds_default_from_is_required() {
+        use ModifierSelectionEnum::*;
+
+        assert_eq!(
+            Single.selection_bounds(true, None, None),
+            Some((1, Some(1)))
+        );
+        assert_eq!(
+            Single.selection_bounds(false, None, None),
+            Some((0, Some(1)))
+        );
+        assert_eq!(Multiple.selection_bounds(true, None, None), Some((1, None)));
+        assert_eq!(
+            Multiple.selection_bounds(false, None, Some(3)),
+            Some((0, Some(3)))
+        );
+        assert_eq!(
+            Multiple.selection_bounds(true, Some(2), Some(2)),
+            Some((2, Some(2)))
+        );
+    }
+
+    #[test]
+    fn contradicting_selection_bounds_are_rejected() {
+        use ModifierSelectionEnum::*;
+
+        for (selection_type, is_required, min_selections, max_selections) in [
+            (Single, false, None, Some(2)),
+            (Single, true, Some(2), None),
+            (Multiple, true, Some(0), None),
+            (Multiple, false, Some(1), None),
+            (Multiple, true, Some(3), Some(2)),
+            (Multiple, false, None, Some(0)),
+            (Multiple, false, Some(-1), None),
+        ] {
+            assert_eq!(
+                selection_type.selection_bounds(is_required, min_selections, max_selections),
+                None,
+                "{} {} {:?} {:?}",
+                selection_type.as_str(),
+                is_required,
+                min_selections,
+                max_selections
+            );
+        }
+    }
+
+    #[test]
+    fn options_cost_nothing_extra_by_default() {
+        let option: CreateRestaurantMenuItemModifierOption =
+            serde_json::from_str(r#"{"name": "No onions"}"#).unwrap();
+        assert!(option.price_delta.is_none());
+
+        let option: CreateRestaurantMenuItemModifierOption =
+            serde_json::from_str(r#"{"name": "Small", "price_delta": -1.5}"#).unwrap();
+        assert_eq!(
+            option
+                .price_delta
+                .and_then(|price_delta| price_delta.to_minor_units(2)),
+            Some(-150)
+        );
+    }
+}
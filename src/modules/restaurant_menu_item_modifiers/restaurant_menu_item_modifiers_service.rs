@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    common::money::Money,
+    modules::restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+};
+
+use super::restaurant_menu_item_modifiers_dto::{
+    RestaurantMenuItemModifierGroup, RestaurantMenuItemModifierGroupRow,
+    RestaurantMenuItemModifierOption, RestaurantMenuItemWithModifiers,
+};
+
+// Two queries whatever the number of items, keyed by item id.
+pub async fn find_modifier_groups(
+    db: &Pool<Postgres>,
+    restaurant_menu_item_ids: &[i32],
+) -> Result<HashMap<i32, Vec<RestaurantMenuItemModifierGroup>>, sqlx::Error> {
+    let groups = sqlx::query_as!(
+        RestaurantMenuItemModifierGroupRow,
+        "SELECT restaurant_menu_item_modifier_group_id,restaurant_menu_item_id,name,selection_type,is_required,min_selections,max_selections,position
+        FROM restaurant_menu_item_modifier_groups
+        WHERE restaurant_menu_item_id = ANY($1)
+        ORDER BY position ASC, restaurant_menu_item_modifier_group_id ASC",
+        restaurant_menu_item_ids
+    )
+    .fetch_all(db)
+    .await?;
+
+    let restaurant_menu_item_modifier_group_ids: Vec<i32> = groups
+        .iter()
+        .map(|group| group.restaurant_menu_item_modifier_group_id as i32)
+        .collect();
+
+    let options = sqlx::query_as!(
+        RestaurantMenuItemModifierOption,
+        r#"SELECT rmimo.restaurant_menu_item_modifier_option_id,rmimo.restaurant_menu_item_modifier_group_id,rmimo.name,
+        menu_money(rmimo.price_delta_minor, rmi.restaurant_menu_id) AS "price_delta!: Money",rmimo.position
+        FROM restaurant_menu_item_modifier_options rmimo
+        JOIN restaurant_menu_item_modifier_groups rmimg
+        ON rmimg.restaurant_menu_item_modifier_group_id = rmimo.restaurant_menu_item_modifier_group_id
+        JOIN restaurant_menu_items rmi ON rmi.restaurant_menu_item_id = rmimg.restaurant_menu_item_id
+        WHERE rmimo.restaurant_menu_item_modifier_group_id = ANY($1)
+        ORDER BY rmimo.position ASC, rmimo.restaurant_menu_item_modifier_option_id ASC"#,
+        &restaurant_menu_item_modifier_group_ids
+    )
+    .fetch_all(db)
+    .await?;
+
+    let mut options_by_group: HashMap<i32, Vec<RestaurantMenuItemModifierOption>> = HashMap::new();
+    for option in options {
+        options_by_group
+            .entry(option.restaurant_menu_item_modifier_group_id)
+            .or_default()
+            .push(option);
+    }
+
+    let mut groups_by_item: HashMap<i32, Vec<RestaurantMenuItemModifierGroup>> = HashMap::new();
+    for group in groups {
+        let options = options_by_group
+            .remove(&(group.restaurant_menu_item_modifier_group_id as i32))
+            .unwrap_or_default();
+        groups_by_item
+            .entry(group.restaurant_menu_item_id)
+            .or_default()
+            .push(RestaurantMenuItemModifierGroup { group, options });
+    }
+
+    Ok(groups_by_item)
+}
+
+pub async fn with_modifiers(
+    db: &Pool<Postgres>,
+    items: Vec<RestaurantMenuItem>,
+) -> Result<Vec<RestaurantMenuItemWithModifiers>, sqlx::Error> {
+    let restaurant_menu_item_ids: Vec<i32> = items
+        .iter()
+        .map(|item| item.restaurant_menu_item_id as i32)
+        .collect();
+    let mut groups_by_item = find_modifier_groups(db, &restaurant_menu_item_ids).await?;
+
+    Ok(items
+        .into_iter()
+        .map(|item| RestaurantMenuItemWithModifiers {
+            modifier_groups: groups_by_item
+                .remove(&(item.restaurant_menu_item_id as i32))
+                .unwrap_or_default(),
+            item,
+        })
+        .collect())
+}
@@ -1,2 +1,3 @@
 pub mod restaurant_menu_items_controller;
 pub mod restaurant_menu_items_dto;
+pub mod restaurant_menu_items_service;
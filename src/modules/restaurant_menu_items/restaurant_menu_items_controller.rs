@@ -1,11 +1,27 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
+    common::{locale::RequestLocales, money::Money},
     modules::{
-        files::files_controller::delete_file,
-        restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+        restaurant_menu_item_modifiers::{
+            restaurant_menu_item_modifiers_dto::RestaurantMenuItemWithModifiers,
+            restaurant_menu_item_modifiers_service::with_modifiers,
+        },
+        restaurant_menu_items::{
+            restaurant_menu_items_dto::RestaurantMenuItem,
+            restaurant_menu_items_service::copy_menu_items,
+        },
         restaurant_menus,
+        restaurants::{
+            restaurants_dto::PriceFilters,
+            restaurants_service::{
+                check_restaurant_menu_access, find_menu_currency_exponent,
+                find_sibling_restaurant_currency_match, refresh_menu_price_levels,
+                resolve_restaurant, RestaurantLookup,
+            },
+        },
         shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
+        translations::translations_service::translate_menu_items,
         users::users_dto::User,
     },
     AppState,
@@ -16,41 +32,144 @@ use axum::{
     Extension,
 };
 
-use super::restaurant_menu_items_dto::CreateRestaurantMenuItem;
+use super::restaurant_menu_items_dto::{
+    CopyRestaurantMenuItems, CreateRestaurantMenuItem, DecrementRestaurantMenuItemStock,
+    DietaryFilters, UpdateRestaurantMenuItemAvailability, UpdateRestaurantMenuItemDietaryInfo,
+    UpdateRestaurantMenuItemStock,
+};
 
 pub async fn get_restaurant_meals(
     State(state): State<Arc<AppState>>,
     pagination_input: Query<PaginationInput>,
-    Path((restaurant_id)): Path<(i32)>,
-) -> AppResult<PaginatedList<RestaurantMenuItem>> {
+    Path(restaurant_id): Path<String>,
+    price_filters: Query<PriceFilters>,
+    dietary_filters: Query<DietaryFilters>,
+    locales: RequestLocales,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<PaginatedList<RestaurantMenuItemWithModifiers>> {
+    if !price_filters.is_valid() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "price_level must be between 1 and 3 and max_price must not be negative and needs a currency",
+            ),
+        );
+    }
+
+    let Some((excluded_allergens, diet_labels)) = dietary_filters.parse() else {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "exclude_allergens and diet must be comma separated lists of known values",
+            ),
+        );
+    };
+
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+    let current_user_id = current_user.map(|Extension(user)| user.user_id);
     let PaginationInput { page, page_size } = pagination_input.0;
     let offset = (page.saturating_sub(1)) * page_size;
     let res = sqlx::query_as!(
         RestaurantMenuItem,
-        "SELECT 
-        restaurant_menu_item_id, 
+        r#"SELECT 
+        rmi.restaurant_menu_item_id, 
         rmi.name, 
         rmi.description,
         rmi.cover_image_uri,
+        rmi.restaurant_menu_section_id,
+        rmi.position,
+        rmi.allergens,
+        rmi.diet_labels,
+        rmi.spicy_level,
+        rmi.calories,
+        rmi.protein_grams,
+        rmi.carbohydrate_grams,
+        rmi.fat_grams,
+        menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",
+        rmi.sold_out_until,
+        rmi.daily_stock,
+        rmi.remaining_stock,
         rmi.restaurant_menu_id,       
-        rmi.price       
+        menu_money(COALESCE(rmio.price_minor, rmi.price_minor), rmi.restaurant_menu_id) AS "price!: Money",
+        EXISTS (
+            SELECT 1 FROM favorite_menu_items fmi
+            WHERE fmi.restaurant_menu_item_id = rmi.restaurant_menu_item_id
+            AND fmi.user_id = $4
+        ) AS "is_favorite!"
         FROM restaurant_menu_items rmi
         JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
-        WHERE rm.restaurant_id = $1       
-        LIMIT $2 OFFSET $3",
+        JOIN restaurants r ON r.restaurant_id = $1
+        JOIN currencies ON currencies.code = r.currency
+        LEFT JOIN restaurant_menu_item_overrides rmio
+        ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id AND rmio.restaurant_id = r.restaurant_id
+        WHERE (rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id)
+        AND COALESCE(rmio.is_available, true)
+        AND menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) <> 'hidden'
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND ($5::SMALLINT IS NULL OR price_level_for(COALESCE(rmio.price_minor, rmi.price_minor) / 10 ^ currencies.exponent, currencies.code) = $5)
+        AND ($6::FLOAT IS NULL OR (currencies.code = $9 AND COALESCE(rmio.price_minor, rmi.price_minor) / 10 ^ currencies.exponent <= $6))
+        AND NOT rmi.allergens && $7 AND rmi.diet_labels @> $8
+        LIMIT $2 OFFSET $3"#,
         restaurant_id,
         page_size,
-        offset
+        offset,
+        current_user_id,
+        price_filters.price_level,
+        price_filters.max_price,
+        &excluded_allergens,
+        &diet_labels,
+        price_filters.currency()
     )
     .fetch_all(&state.db)
     .await;
+    let res = match res {
+        Ok(mut items) => {
+            match translate_menu_items(&state.db, &locales, restaurant_id, &mut items).await {
+                Ok(()) => with_modifiers(&state.db, items).await,
+                Err(err) => Err(err),
+            }
+        }
+        Err(err) => Err(err),
+    };
 
     let count: i64 = match sqlx::query_scalar!(
-        "SELECT COUNT (restaurant_id) 
+        "SELECT COUNT (rmi.restaurant_menu_item_id)
         FROM restaurant_menu_items rmi
         JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
-        WHERE rm.restaurant_id = $1 ",
-        restaurant_id
+        JOIN restaurants r ON r.restaurant_id = $1
+        JOIN currencies ON currencies.code = r.currency
+        LEFT JOIN restaurant_menu_item_overrides rmio
+        ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id AND rmio.restaurant_id = r.restaurant_id
+        WHERE (rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id)
+        AND COALESCE(rmio.is_available, true)
+        AND menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) <> 'hidden'
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND ($2::SMALLINT IS NULL OR price_level_for(COALESCE(rmio.price_minor, rmi.price_minor) / 10 ^ currencies.exponent, currencies.code) = $2)
+        AND ($3::FLOAT IS NULL OR (currencies.code = $6 AND COALESCE(rmio.price_minor, rmi.price_minor) / 10 ^ currencies.exponent <= $3))
+        AND NOT rmi.allergens && $4 AND rmi.diet_labels @> $5",
+        restaurant_id,
+        price_filters.price_level,
+        price_filters.max_price,
+        &excluded_allergens,
+        &diet_labels,
+        price_filters.currency()
     )
     .fetch_one(&state.db)
     .await
@@ -76,32 +195,127 @@ pub async fn get_restaurant_meals(
 pub async fn get_restaurant_menu_items(
     State(state): State<Arc<AppState>>,
     pagination_input: Query<PaginationInput>,
-    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
-) -> AppResult<PaginatedList<RestaurantMenuItem>> {
+    Path((restaurant_id, restaurant_menu_id)): Path<(String, i32)>,
+    dietary_filters: Query<DietaryFilters>,
+    locales: RequestLocales,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<PaginatedList<RestaurantMenuItemWithModifiers>> {
+    let Some((excluded_allergens, diet_labels)) = dietary_filters.parse() else {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "exclude_allergens and diet must be comma separated lists of known values",
+            ),
+        );
+    };
+
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+    let current_user_id = current_user.map(|Extension(user)| user.user_id);
     let PaginationInput { page, page_size } = pagination_input.0;
     let offset = (page.saturating_sub(1)) * page_size;
     let res = sqlx::query_as!(
         RestaurantMenuItem,
-        "SELECT 
-        restaurant_menu_item_id, 
-        name,
-        price, 
-        description,
-        cover_image_uri,
-        restaurant_menu_id       
-        FROM restaurant_menu_items
-        where restaurant_menu_id = $1        
-        LIMIT $2 OFFSET $3",
+        r#"SELECT 
+        rmi.restaurant_menu_item_id, 
+        rmi.name,
+        menu_money(COALESCE(rmio.price_minor, rmi.price_minor), rmi.restaurant_menu_id) AS "price!: Money", 
+        rmi.description,
+        rmi.cover_image_uri,
+        rmi.restaurant_menu_section_id,
+        rmi.position,
+        rmi.allergens,
+        rmi.diet_labels,
+        rmi.spicy_level,
+        rmi.calories,
+        rmi.protein_grams,
+        rmi.carbohydrate_grams,
+        rmi.fat_grams,
+        menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",
+        rmi.sold_out_until,
+        rmi.daily_stock,
+        rmi.remaining_stock,
+        rmi.restaurant_menu_id,
+        EXISTS (
+            SELECT 1 FROM favorite_menu_items fmi
+            WHERE fmi.restaurant_menu_item_id = rmi.restaurant_menu_item_id
+            AND fmi.user_id = $4
+        ) AS "is_favorite!"
+        FROM restaurant_menu_items rmi
+        LEFT JOIN restaurant_menu_sections rms ON rms.restaurant_menu_section_id = rmi.restaurant_menu_section_id
+        LEFT JOIN restaurant_menu_item_overrides rmio
+        ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id AND rmio.restaurant_id = $5
+        where rmi.restaurant_menu_id = $1
+        AND rmi.deleted_at IS NULL
+        AND COALESCE(rmio.is_available, true)
+        AND menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) <> 'hidden'
+        AND rmi.restaurant_menu_id IN (
+            SELECT restaurant_menu_id FROM restaurant_menus
+            JOIN restaurants ON restaurant_menus.restaurant_id = restaurants.restaurant_id
+            OR restaurant_menus.restaurant_chain_id = restaurants.restaurant_chain_id
+            WHERE restaurants.restaurant_id = $5
+            AND restaurant_menus.deleted_at IS NULL AND restaurants.deleted_at IS NULL
+        )
+        AND NOT rmi.allergens && $6 AND rmi.diet_labels @> $7
+        ORDER BY rms.position ASC NULLS FIRST, rms.restaurant_menu_section_id ASC NULLS FIRST,
+        rmi.position ASC, rmi.restaurant_menu_item_id ASC
+        LIMIT $2 OFFSET $3"#,
         restaurant_menu_id,
         page_size,
-        offset
+        offset,
+        current_user_id,
+        restaurant_id,
+        &excluded_allergens,
+        &diet_labels
     )
     .fetch_all(&state.db)
     .await;
+    let res = match res {
+        Ok(mut items) => {
+            match translate_menu_items(&state.db, &locales, restaurant_id, &mut items).await {
+                Ok(()) => with_modifiers(&state.db, items).await,
+                Err(err) => Err(err),
+            }
+        }
+        Err(err) => Err(err),
+    };
 
     let count: i64 = match sqlx::query_scalar!(
-        "SELECT COUNT (restaurant_menu_item_id) FROM restaurant_menu_items where restaurant_menu_id = $1 ",
-        restaurant_menu_id
+        "SELECT COUNT (rmi.restaurant_menu_item_id) FROM restaurant_menu_items rmi
+        LEFT JOIN restaurant_menu_item_overrides rmio
+        ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id AND rmio.restaurant_id = $2
+        where rmi.restaurant_menu_id = $1
+        AND rmi.deleted_at IS NULL
+        AND COALESCE(rmio.is_available, true)
+        AND menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) <> 'hidden'
+        AND rmi.restaurant_menu_id IN (
+            SELECT restaurant_menu_id FROM restaurant_menus
+            JOIN restaurants ON restaurant_menus.restaurant_id = restaurants.restaurant_id
+            OR restaurant_menus.restaurant_chain_id = restaurants.restaurant_chain_id
+            WHERE restaurants.restaurant_id = $2
+            AND restaurant_menus.deleted_at IS NULL AND restaurants.deleted_at IS NULL
+        )
+        AND NOT rmi.allergens && $3 AND rmi.diet_labels @> $4",
+        restaurant_menu_id,
+        restaurant_id,
+        &excluded_allergens,
+        &diet_labels
     )
     .fetch_one(&state.db)
     .await
@@ -127,23 +341,86 @@ pub async fn get_restaurant_menu_items(
 
 pub async fn create_restaurant_menu_item(
     State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
     Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
     Json(create_restaurant_menu_item_dto): Json<CreateRestaurantMenuItem>,
 ) -> AppResult<RestaurantMenuItem> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let exponent = match find_menu_currency_exponent(&state.db, restaurant_menu_id).await {
+        Ok(Some(exponent)) => exponent,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let price_minor = match create_restaurant_menu_item_dto
+        .price
+        .to_minor_units(exponent)
+    {
+        Some(price_minor) if price_minor >= 0 => price_minor,
+        _ => {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("price must not be negative nor have more decimals than its currency"),
+            )
+        }
+    };
+
+    let dietary_info = &create_restaurant_menu_item_dto.dietary_info;
+    if !dietary_info.is_valid() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "spicy_level must be between 0 and 3 and nutrition facts must not be negative",
+            ),
+        );
+    }
+
     let res = sqlx::query_as!(
         RestaurantMenuItem,
-        "INSERT INTO restaurant_menu_items (name,description,restaurant_menu_id,cover_image_uri,price) VALUES ($1,$2,$3,$4,$5) RETURNING restaurant_menu_item_id,name,description,restaurant_menu_id,cover_image_uri,price",
+        r#"INSERT INTO restaurant_menu_items (name,description,restaurant_menu_id,cover_image_uri,price_minor,restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams)
+        SELECT $1,$2,$3,$4,$5,$6,COALESCE(MAX(position) + 1, 0),$7,$8,$9,$10,$11,$12,$13 FROM restaurant_menu_items
+        WHERE restaurant_menu_id = $3 AND restaurant_menu_section_id IS NOT DISTINCT FROM $6 AND deleted_at IS NULL
+        HAVING $6::INTEGER IS NULL OR EXISTS (SELECT 1 FROM restaurant_menu_sections WHERE restaurant_menu_section_id = $6 AND restaurant_menu_id = $3)
+        RETURNING restaurant_menu_item_id,name,description,restaurant_menu_id,cover_image_uri,menu_money(price_minor, restaurant_menu_id) AS "price!: Money",restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams,menu_item_availability(availability, sold_out_until, remaining_stock) AS "availability!",sold_out_until,daily_stock,remaining_stock,false AS "is_favorite!""#,
         create_restaurant_menu_item_dto.name,
         create_restaurant_menu_item_dto.description,
         restaurant_menu_id,
         create_restaurant_menu_item_dto.cover_image_uri,
-        create_restaurant_menu_item_dto.price,
+        price_minor,
+        create_restaurant_menu_item_dto.restaurant_menu_section_id,
+        &dietary_info.allergen_names(),
+        &dietary_info.diet_label_names(),
+        dietary_info.spicy_level,
+        dietary_info.calories,
+        dietary_info.protein_grams,
+        dietary_info.carbohydrate_grams,
+        dietary_info.fat_grams
     )
-    .fetch_one(&state.db)
+    .fetch_optional(&state.db)
     .await;
 
     return match res {
-        Ok(restaurant) => AppResult::Result(StatusCode::CREATED, restaurant),
+        Ok(Some(restaurant)) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::CREATED, restaurant)
+        }
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Menu section not found!"),
+        ),
         Err(_) => AppResult::Error(
             StatusCode::INTERNAL_SERVER_ERROR,
             String::from("Something went wrong"),
@@ -151,39 +428,394 @@ pub async fn create_restaurant_menu_item(
     };
 }
 
-pub async fn delete_restaurant_menu_item(
+pub async fn update_restaurant_menu_item_dietary_info(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(i32, i32, i32)>,
+    Json(update_dietary_info_dto): Json<UpdateRestaurantMenuItemDietaryInfo>,
+) -> AppResult<RestaurantMenuItem> {
+    if !update_dietary_info_dto.is_valid() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "spicy_level must be between 0 and 3 and nutrition facts must not be negative",
+            ),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"UPDATE restaurant_menu_items rmi
+        SET allergens = $1, diet_labels = $2, spicy_level = $3, calories = $4,
+        protein_grams = $5, carbohydrate_grams = $6, fat_grams = $7
+        FROM restaurant_menus rm
+        JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        WHERE rmi.restaurant_menu_item_id = $8 AND rmi.restaurant_menu_id = $9
+        AND rm.restaurant_menu_id = rmi.restaurant_menu_id AND rm.restaurant_id = $10
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND (r.user_id = $11 OR $12)
+        RETURNING rmi.restaurant_menu_item_id,rmi.name,rmi.description,rmi.restaurant_menu_id,rmi.cover_image_uri,menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "price!: Money",rmi.restaurant_menu_section_id,rmi.position,rmi.allergens,rmi.diet_labels,rmi.spicy_level,rmi.calories,rmi.protein_grams,rmi.carbohydrate_grams,rmi.fat_grams,menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",rmi.sold_out_until,rmi.daily_stock,rmi.remaining_stock,false AS "is_favorite!""#,
+        &update_dietary_info_dto.allergen_names(),
+        &update_dietary_info_dto.diet_label_names(),
+        update_dietary_info_dto.spicy_level,
+        update_dietary_info_dto.calories,
+        update_dietary_info_dto.protein_grams,
+        update_dietary_info_dto.carbohydrate_grams,
+        update_dietary_info_dto.fat_grams,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item)) => AppResult::Result(StatusCode::OK, restaurant_menu_item),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Quick switch for the kitchen during service, no other field is touched.
+pub async fn update_restaurant_menu_item_availability(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(i32, i32, i32)>,
+    Json(update_availability_dto): Json<UpdateRestaurantMenuItemAvailability>,
+) -> AppResult<RestaurantMenuItem> {
+    if !update_availability_dto.is_valid() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("sold_out_until must be in the future and only set on sold out items"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"UPDATE restaurant_menu_items rmi
+        SET availability = $1, sold_out_until = $2
+        FROM restaurant_menus rm
+        JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        WHERE rmi.restaurant_menu_item_id = $3 AND rmi.restaurant_menu_id = $4
+        AND rm.restaurant_menu_id = rmi.restaurant_menu_id AND rm.restaurant_id = $5
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND (r.user_id = $6 OR $7)
+        RETURNING rmi.restaurant_menu_item_id,rmi.name,rmi.description,rmi.restaurant_menu_id,rmi.cover_image_uri,menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "price!: Money",rmi.restaurant_menu_section_id,rmi.position,rmi.allergens,rmi.diet_labels,rmi.spicy_level,rmi.calories,rmi.protein_grams,rmi.carbohydrate_grams,rmi.fat_grams,menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",rmi.sold_out_until,rmi.daily_stock,rmi.remaining_stock,false AS "is_favorite!""#,
+        update_availability_dto.availability.as_str(),
+        update_availability_dto.sold_out_until,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item)) => AppResult::Result(StatusCode::OK, restaurant_menu_item),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_restaurant_menu_item_stock(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(i32, i32, i32)>,
+    Json(update_stock_dto): Json<UpdateRestaurantMenuItemStock>,
+) -> AppResult<RestaurantMenuItem> {
+    if !update_stock_dto.is_valid() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("stock must not be negative and remaining_stock needs a daily_stock"),
+        );
+    }
+
+    // Counts as today's refill, the reset task picks it up again tomorrow.
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"UPDATE restaurant_menu_items rmi
+        SET daily_stock = $1, remaining_stock = COALESCE($2, $1::INTEGER),
+        stock_reset_on = (NOW() AT TIME ZONE r.timezone)::DATE
+        FROM restaurant_menus rm
+        JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        WHERE rmi.restaurant_menu_item_id = $3 AND rmi.restaurant_menu_id = $4
+        AND rm.restaurant_menu_id = rmi.restaurant_menu_id AND rm.restaurant_id = $5
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND (r.user_id = $6 OR $7)
+        RETURNING rmi.restaurant_menu_item_id,rmi.name,rmi.description,rmi.restaurant_menu_id,rmi.cover_image_uri,menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "price!: Money",rmi.restaurant_menu_section_id,rmi.position,rmi.allergens,rmi.diet_labels,rmi.spicy_level,rmi.calories,rmi.protein_grams,rmi.carbohydrate_grams,rmi.fat_grams,menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",rmi.sold_out_until,rmi.daily_stock,rmi.remaining_stock,false AS "is_favorite!""#,
+        update_stock_dto.daily_stock,
+        update_stock_dto.remaining_stock,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item)) => AppResult::Result(StatusCode::OK, restaurant_menu_item),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Called when portions are sold. Items that are not counted only need to be
+// available.
+pub async fn decrement_restaurant_menu_item_stock(
     State(state): State<Arc<AppState>>,
     Extension(current_user): Extension<Arc<User>>,
     Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(i32, i32, i32)>,
+    Json(decrement_stock_dto): Json<DecrementRestaurantMenuItemStock>,
 ) -> AppResult<RestaurantMenuItem> {
-    let res = match current_user.role.as_str() {
-        "Admin" =>
-            sqlx::query_as!(
-                RestaurantMenuItem,
-                "DELETE FROM restaurant_menu_items Where restaurant_menu_item_id = $1 RETURNING restaurant_menu_item_id,name,description,restaurant_menu_id,cover_image_uri,price",
-                restaurant_menu_item_id
+    if decrement_stock_dto.quantity < 1 {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("quantity must be at least 1"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"UPDATE restaurant_menu_items rmi
+        SET remaining_stock = rmi.remaining_stock - $1
+        FROM restaurant_menus rm
+        JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        WHERE rmi.restaurant_menu_item_id = $2 AND rmi.restaurant_menu_id = $3
+        AND rm.restaurant_menu_id = rmi.restaurant_menu_id AND rm.restaurant_id = $4
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND (r.user_id = $5 OR $6)
+        AND menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) = 'available'
+        AND (rmi.remaining_stock IS NULL OR rmi.remaining_stock >= $1)
+        RETURNING rmi.restaurant_menu_item_id,rmi.name,rmi.description,rmi.restaurant_menu_id,rmi.cover_image_uri,menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "price!: Money",rmi.restaurant_menu_section_id,rmi.position,rmi.allergens,rmi.diet_labels,rmi.spicy_level,rmi.calories,rmi.protein_grams,rmi.carbohydrate_grams,rmi.fat_grams,menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",rmi.sold_out_until,rmi.daily_stock,rmi.remaining_stock,false AS "is_favorite!""#,
+        decrement_stock_dto.quantity,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item)) => {
+            return AppResult::Result(StatusCode::OK, restaurant_menu_item)
+        }
+        Ok(None) => {}
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
             )
-            .fetch_one(&state.db)
-            .await,
+        }
+    };
+
+    // Nothing was updated, find out why.
+    let availability = sqlx::query_scalar!(
+        r#"SELECT menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!"
+        FROM restaurant_menu_items rmi
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+        JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        WHERE rmi.restaurant_menu_item_id = $1 AND rmi.restaurant_menu_id = $2 AND rm.restaurant_id = $3
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND (r.user_id = $4 OR $5)"#,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
 
-        _ => sqlx::query_as!(
+    match availability {
+        Ok(Some(availability)) if availability == "available" => {
+            AppResult::Error(StatusCode::CONFLICT, String::from("Not enough stock left!"))
+        }
+        Ok(Some(_)) => AppResult::Error(
+            StatusCode::CONFLICT,
+            String::from("Menu item is not available!"),
+        ),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn copy_restaurant_menu_items(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+    Json(copy_items_dto): Json<CopyRestaurantMenuItems>,
+) -> AppResult<Vec<RestaurantMenuItem>> {
+    if copy_items_dto.restaurant_menu_item_ids.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("restaurant_menu_item_ids must not be empty"),
+        );
+    }
+
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let target_restaurant_id = copy_items_dto.target_restaurant_id.unwrap_or(restaurant_id);
+    match find_sibling_restaurant_currency_match(&state.db, restaurant_id, target_restaurant_id)
+        .await
+    {
+        Ok(Some(true)) => {}
+        Ok(Some(false)) => {
+            return AppResult::Error(
+                StatusCode::CONFLICT,
+                String::from("Target restaurant uses a different currency!"),
+            )
+        }
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let checks = sqlx::query!(
+        r#"SELECT
+        (
+            SELECT COUNT(*) FROM restaurant_menu_items rmi
+            JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+            WHERE rmi.restaurant_menu_item_id = ANY($1) AND rmi.restaurant_menu_id = $2
+            AND rm.restaurant_id = $3 AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL
+        ) = CARDINALITY(ARRAY(SELECT DISTINCT UNNEST($1::INTEGER[]))) AS "are_menu_items!",
+        EXISTS (
+            SELECT 1 FROM restaurant_menus
+            WHERE restaurant_menu_id = $4 AND restaurant_id = $5 AND deleted_at IS NULL
+        ) AS "is_target_menu!""#,
+        &copy_items_dto.restaurant_menu_item_ids,
+        restaurant_menu_id,
+        restaurant_id,
+        copy_items_dto.target_restaurant_menu_id,
+        target_restaurant_id
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match checks {
+        Ok(checks) if !checks.are_menu_items => {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("restaurant_menu_item_ids must be items of this menu"),
+            )
+        }
+        Ok(checks) if !checks.is_target_menu => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!"))
+        }
+        Ok(_) => {}
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let res: Result<Vec<RestaurantMenuItem>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let copied_item_ids = copy_menu_items(
+            &mut tx,
+            &copy_items_dto.restaurant_menu_item_ids,
+            copy_items_dto.target_restaurant_menu_id,
+            &HashMap::new(),
+            copy_items_dto.copy_images,
+        )
+        .await?;
+
+        let items = sqlx::query_as!(
             RestaurantMenuItem,
-            "DELETE FROM restaurant_menu_items Where restaurant_menu_item_id = $1 RETURNING restaurant_menu_item_id,name,description,restaurant_menu_id,cover_image_uri,price",
-            restaurant_menu_item_id,
+            r#"SELECT restaurant_menu_item_id,name,description,restaurant_menu_id,cover_image_uri,menu_money(price_minor, restaurant_menu_id) AS "price!: Money",restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams,menu_item_availability(availability, sold_out_until, remaining_stock) AS "availability!",sold_out_until,daily_stock,remaining_stock,false AS "is_favorite!"
+            FROM restaurant_menu_items
+            WHERE restaurant_menu_item_id = ANY($1)
+            ORDER BY position ASC, restaurant_menu_item_id ASC"#,
+            &copied_item_ids
         )
-        .fetch_one(&state.db)
-        .await,
-    };
+        .fetch_all(&mut *tx)
+        .await?;
 
-    return match res {
-        Ok(restaurant_menu_item) => {
-            delete_file(restaurant_menu_item.cover_image_uri.clone());
+        tx.commit().await?;
+
+        Ok(items)
+    }
+    .await;
+
+    match res {
+        Ok(items) => {
+            refresh_menu_price_levels(&state.db, copy_items_dto.target_restaurant_menu_id).await;
+            AppResult::Result(StatusCode::CREATED, items)
+        }
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
 
+pub async fn delete_restaurant_menu_item(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(i32, i32, i32)>,
+) -> AppResult<RestaurantMenuItem> {
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"UPDATE restaurant_menu_items rmi SET deleted_at = NOW()
+        FROM restaurant_menus rm
+        JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        WHERE rmi.restaurant_menu_item_id = $1 AND rmi.restaurant_menu_id = $2
+        AND rm.restaurant_menu_id = rmi.restaurant_menu_id AND rm.restaurant_id = $3
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND (r.user_id = $4 OR $5)
+        RETURNING rmi.restaurant_menu_item_id,rmi.name,rmi.description,rmi.restaurant_menu_id,rmi.cover_image_uri,menu_money(rmi.price_minor, rmi.restaurant_menu_id) AS "price!: Money",rmi.restaurant_menu_section_id,rmi.position,rmi.allergens,rmi.diet_labels,rmi.spicy_level,rmi.calories,rmi.protein_grams,rmi.carbohydrate_grams,rmi.fat_grams,menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",rmi.sold_out_until,rmi.daily_stock,rmi.remaining_stock,false AS "is_favorite!""#,
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu_item)) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_item.restaurant_menu_id as i32)
+                .await;
             AppResult::Result(StatusCode::OK, restaurant_menu_item)
         }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!")),
         Err(_) => AppResult::Error(
             StatusCode::INTERNAL_SERVER_ERROR,
             String::from("Something went wrong"),
         ),
-    };
+    }
 }
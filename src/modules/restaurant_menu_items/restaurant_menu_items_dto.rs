@@ -1,20 +1,393 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::common::money::{Money, MoneyInput};
+
 #[derive(Deserialize)]
 pub struct CreateRestaurantMenuItem {
     pub name: String,
     pub description: String,
-    pub price: f64,
+    // In the currency of the menu's restaurant or chain.
+    pub price: MoneyInput,
     pub cover_image_uri: String,
+    pub restaurant_menu_section_id: Option<i32>,
+    #[serde(flatten)]
+    pub dietary_info: UpdateRestaurantMenuItemDietaryInfo,
 }
 
 #[derive(Serialize, FromRow)]
 pub struct RestaurantMenuItem {
     pub restaurant_menu_item_id: i64,
     pub name: String,
-    pub price: f64,
+    pub price: Money,
     pub description: String,
     pub restaurant_menu_id: i64,
     pub cover_image_uri: String,
+    pub restaurant_menu_section_id: Option<i32>,
+    // Order inside the section, or among the items without one.
+    pub position: i32,
+    pub allergens: Vec<String>,
+    pub diet_labels: Vec<String>,
+    // 0 (not spicy) to 3.
+    pub spicy_level: Option<i16>,
+    // Per serving.
+    pub calories: Option<i32>,
+    pub protein_grams: Option<f64>,
+    pub carbohydrate_grams: Option<f64>,
+    pub fat_grams: Option<f64>,
+    // available, sold_out or hidden, as customers currently see it.
+    pub availability: String,
+    pub sold_out_until: Option<DateTime<Utc>>,
+    // Unset when the kitchen does not count portions.
+    pub daily_stock: Option<i32>,
+    pub remaining_stock: Option<i32>,
+    pub is_favorite: bool,
+}
+
+// The 14 allergens EU law requires restaurants to declare.
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AllergenEnum {
+    Celery,
+    Crustaceans,
+    Eggs,
+    Fish,
+    Gluten,
+    Lupin,
+    Milk,
+    Molluscs,
+    Mustard,
+    Nuts,
+    Peanuts,
+    Sesame,
+    Soybeans,
+    Sulphites,
+}
+
+impl AllergenEnum {
+    pub const ALL: [AllergenEnum; 14] = [
+        AllergenEnum::Celery,
+        AllergenEnum::Crustaceans,
+        AllergenEnum::Eggs,
+        AllergenEnum::Fish,
+        AllergenEnum::Gluten,
+        AllergenEnum::Lupin,
+        AllergenEnum::Milk,
+        AllergenEnum::Molluscs,
+        AllergenEnum::Mustard,
+        AllergenEnum::Nuts,
+        AllergenEnum::Peanuts,
+        AllergenEnum::Sesame,
+        AllergenEnum::Soybeans,
+        AllergenEnum::Sulphites,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AllergenEnum::Celery => "celery",
+            AllergenEnum::Crustaceans => "crustaceans",
+            AllergenEnum::Eggs => "eggs",
+            AllergenEnum::Fish => "fish",
+            AllergenEnum::Gluten => "gluten",
+            AllergenEnum::Lupin => "lupin",
+            AllergenEnum::Milk => "milk",
+            AllergenEnum::Molluscs => "molluscs",
+            AllergenEnum::Mustard => "mustard",
+            AllergenEnum::Nuts => "nuts",
+            AllergenEnum::Peanuts => "peanuts",
+            AllergenEnum::Sesame => "sesame",
+            AllergenEnum::Soybeans => "soybeans",
+            AllergenEnum::Sulphites => "sulphites",
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DietLabelEnum {
+    GlutenFree,
+    Halal,
+    Vegan,
+    Vegetarian,
+}
+
+impl DietLabelEnum {
+    pub const ALL: [DietLabelEnum; 4] = [
+        DietLabelEnum::GlutenFree,
+        DietLabelEnum::Halal,
+        DietLabelEnum::Vegan,
+        DietLabelEnum::Vegetarian,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DietLabelEnum::GlutenFree => "gluten_free",
+            DietLabelEnum::Halal => "halal",
+            DietLabelEnum::Vegan => "vegan",
+            DietLabelEnum::Vegetarian => "vegetarian",
+        }
+    }
+}
+
+// Replaces the whole dietary info of an item, omitted fields are cleared.
+#[derive(Deserialize, Default)]
+pub struct UpdateRestaurantMenuItemDietaryInfo {
+    #[serde(default)]
+    pub allergens: Vec<AllergenEnum>,
+    #[serde(default)]
+    pub diet_labels: Vec<DietLabelEnum>,
+    pub spicy_level: Option<i16>,
+    pub calories: Option<i32>,
+    pub protein_grams: Option<f64>,
+    pub carbohydrate_grams: Option<f64>,
+    pub fat_grams: Option<f64>,
+}
+
+impl UpdateRestaurantMenuItemDietaryInfo {
+    pub fn is_valid(&self) -> bool {
+        self.spicy_level
+            .is_none_or(|spicy_level| (0..=3).contains(&spicy_level))
+            && self.calories.is_none_or(|calories| calories >= 0)
+            && [self.protein_grams, self.carbohydrate_grams, self.fat_grams]
+                .iter()
+                .all(|grams| grams.is_none_or(|grams| grams.is_finite() && grams >= 0.0))
+    }
+
+    // Stored sorted and without duplicates.
+    pub fn allergen_names(&self) -> Vec<String> {
+        AllergenEnum::ALL
+            .iter()
+            .filter(|allergen| self.allergens.contains(allergen))
+            .map(|allergen| allergen.as_str().to_string())
+            .collect()
+    }
+
+    pub fn diet_label_names(&self) -> Vec<String> {
+        DietLabelEnum::ALL
+            .iter()
+            .filter(|diet_label| self.diet_labels.contains(diet_label))
+            .map(|diet_label| diet_label.as_str().to_string())
+            .collect()
+    }
+}
+
+// Comma separated lists, e.g. ?exclude_allergens=milk,eggs&diet=vegan. Items
+// must carry every requested diet label.
+#[derive(Deserialize)]
+pub struct DietaryFilters {
+    pub exclude_allergens: Option<String>,
+    pub diet: Option<String>,
+}
+
+impl DietaryFilters {
+    // Returns (allergens, diet labels), None when a value is unknown.
+    pub fn parse(&self) -> Option<(Vec<String>, Vec<String>)> {
+        let allergens = split_filter(self.exclude_allergens.as_deref())
+            .map(|name| {
+                AllergenEnum::ALL
+                    .iter()
+                    .find(|allergen| allergen.as_str().eq_ignore_ascii_case(name))
+                    .map(|allergen| allergen.as_str().to_string())
+            })
+            .collect::<Option<Vec<String>>>()?;
+
+        let diet_labels = split_filter(self.diet.as_deref())
+            .map(|name| {
+                DietLabelEnum::ALL
+                    .iter()
+                    .find(|diet_label| diet_label.as_str().eq_ignore_ascii_case(name))
+                    .map(|diet_label| diet_label.as_str().to_string())
+            })
+            .collect::<Option<Vec<String>>>()?;
+
+        Some((allergens, diet_labels))
+    }
+}
+
+fn split_filter(value: Option<&str>) -> impl Iterator<Item = &str> {
+    value
+        .unwrap_or("")
+        .split(',')
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AvailabilityEnum {
+    Available,
+    SoldOut,
+    Hidden,
+}
+
+impl AvailabilityEnum {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AvailabilityEnum::Available => "available",
+            AvailabilityEnum::SoldOut => "sold_out",
+            AvailabilityEnum::Hidden => "hidden",
+        }
+    }
+}
+
+// Leaving out sold_out_until keeps a sold out item off until it is set back
+// to available. Marking an item available does not refill its stock.
+#[derive(Deserialize)]
+pub struct UpdateRestaurantMenuItemAvailability {
+    pub availability: AvailabilityEnum,
+    pub sold_out_until: Option<DateTime<Utc>>,
+}
+
+impl UpdateRestaurantMenuItemAvailability {
+    pub fn is_valid(&self) -> bool {
+        self.sold_out_until.is_none_or(|sold_out_until| {
+            self.availability == AvailabilityEnum::SoldOut && sold_out_until > Utc::now()
+        })
+    }
+}
+
+// remaining_stock defaults to daily_stock, an unset daily_stock stops counting.
+#[derive(Deserialize)]
+pub struct UpdateRestaurantMenuItemStock {
+    pub daily_stock: Option<i32>,
+    pub remaining_stock: Option<i32>,
+}
+
+impl UpdateRestaurantMenuItemStock {
+    pub fn is_valid(&self) -> bool {
+        match (self.daily_stock, self.remaining_stock) {
+            (Some(daily_stock), remaining_stock) => {
+                daily_stock >= 0 && remaining_stock.is_none_or(|remaining| remaining >= 0)
+            }
+            (None, remaining_stock) => remaining_stock.is_none(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DecrementRestaurantMenuItemStock {
+    pub quantity: i32,
+}
+
+// Copies land after the items without a section of the target menu.
+#[derive(Deserialize)]
+pub struct CopyRestaurantMenuItems {
+    pub restaurant_menu_item_ids: Vec<i32>,
+    pub target_restaurant_menu_id: i32,
+    // Another restaurant of the same owner, in the same currency.
+    pub target_restaurant_id: Option<i32>,
+    #[serde(default)]
+    pub copy_images: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dietary_filters(exclude_allergens: Option<&str>, diet: Option<&str>) -> DietaryFilters {
+        DietaryFilters {
+            exclude_allergens: exclude_allergens.map(String::from),
+            diet: diet.map(String::from),
+        }
+    }
+
+    #[test]
+    fn dietary_filters_are_parsed_case_insensitively() {
+        assert_eq!(
+            dietary_filters(Some("Milk, eggs,,"), Some("VEGAN,gluten_free")).parse(),
+            Some((
+                vec![String::from("milk"), String::from("eggs")],
+                vec![String::from("vegan"), String::from("gluten_free")]
+            ))
+        );
+        assert_eq!(
+            dietary_filters(None, Some("")).parse(),
+            Some((vec![], vec![]))
+        );
+    }
+
+    #[test]
+    fn unknown_dietary_filters_are_rejected() {
+        assert_eq!(dietary_filters(Some("milk,chocolate"), None).parse(), None);
+        assert_eq!(dietary_filters(None, Some("keto")).parse(), None);
+    }
+
+    #[test]
+    fn dietary_info_is_stored_sorted_without_duplicates() {
+        let dietary_info: UpdateRestaurantMenuItemDietaryInfo = serde_json::from_str(
+            r#"{"allergens": ["sesame", "gluten", "sesame"], "diet_labels": ["vegetarian", "halal"]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(dietary_info.allergen_names(), vec!["gluten", "sesame"]);
+        assert_eq!(dietary_info.diet_label_names(), vec!["halal", "vegetarian"]);
+    }
+
+    #[test]
+    fn nutrition_facts_must_be_in_range() {
+        assert!(UpdateRestaurantMenuItemDietaryInfo::default().is_valid());
+
+        for dietary_info in [
+            r#"{"spicy_level": 4}"#,
+            r#"{"spicy_level": -1}"#,
+            r#"{"calories": -10}"#,
+            r#"{"fat_grams": -0.5}"#,
+        ] {
+            let parsed: UpdateRestaurantMenuItemDietaryInfo =
+                serde_json::from_str(dietary_info).unwrap();
+            assert!(!parsed.is_valid(), "{}", dietary_info);
+        }
+
+        let dietary_info: UpdateRestaurantMenuItemDietaryInfo = serde_json::from_str(
+            r#"{"spicy_level": 3, "calories": 650, "protein_grams": 32.5, "carbohydrate_grams": 0, "fat_grams": 12}"#,
+        )
+        .unwrap();
+        assert!(dietary_info.is_valid());
+    }
+
+    #[test]
+    fn only_sold_out_items_take_a_future_sold_out_until() {
+        let later = Some(Utc::now() + chrono::Duration::hours(2));
+        let earlier = Some(Utc::now() - chrono::Duration::hours(2));
+
+        for (availability, sold_out_until, is_valid) in [
+            (AvailabilityEnum::SoldOut, later, true),
+            (AvailabilityEnum::SoldOut, None, true),
+            (AvailabilityEnum::SoldOut, earlier, false),
+            (AvailabilityEnum::Available, later, false),
+            (AvailabilityEnum::Hidden, None, true),
+        ] {
+            let update = UpdateRestaurantMenuItemAvailability {
+                availability,
+                sold_out_until,
+            };
+            assert_eq!(update.is_valid(), is_valid, "{}", availability.as_str());
+        }
+    }
+
+    #[test]
+    fn remaining_stock_needs_a_daily_stock() {
+        for (daily_stock, remaining_stock, is_valid) in [
+            (Some(20), None, true),
+            (Some(20), Some(3), true),
+            (Some(0), Some(0), true),
+            (None, None, true),
+            (None, Some(3), false),
+            (Some(-1), None, false),
+            (Some(20), Some(-1), false),
+        ] {
+            let update = UpdateRestaurantMenuItemStock {
+                daily_stock,
+                remaining_stock,
+            };
+            assert_eq!(
+                update.is_valid(),
+                is_valid,
+                "{:?} {:?}",
+                daily_stock,
+                remaining_stock
+            );
+        }
+    }
 }
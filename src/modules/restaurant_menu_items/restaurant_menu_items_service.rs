@@ -0,0 +1,289 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use sqlx::{PgConnection, Pool, Postgres};
+
+use crate::{modules::files::files_controller::copy_file, AppState};
+
+// Refills counted items once a new day has started in the restaurant
+// timezone, chain menus follow UTC. Expired sold out marks are cleared too.
+pub async fn reset_daily_stock(db: &Pool<Postgres>) -> Result<u64, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    let res = sqlx::query!(
+        "UPDATE restaurant_menu_items rmi
+        SET remaining_stock = rmi.daily_stock, stock_reset_on = local.today
+        FROM restaurant_menus rm
+        LEFT JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        CROSS JOIN LATERAL (
+            SELECT (NOW() AT TIME ZONE COALESCE(r.timezone, 'UTC'))::DATE AS today
+        ) local
+        WHERE rm.restaurant_menu_id = rmi.restaurant_menu_id
+        AND rmi.daily_stock IS NOT NULL AND rmi.deleted_at IS NULL
+        AND stock_reset_due(rmi.stock_reset_on, COALESCE(r.timezone, 'UTC'), NOW())"
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE restaurant_menu_items
+        SET availability = 'available', sold_out_until = NULL
+        WHERE availability = 'sold_out' AND sold_out_until <= NOW()"
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(res.rows_affected())
+}
+
+pub async fn run_stock_reset_task(state: Arc<AppState>) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(state.env.stock_reset_interval_seconds));
+
+    loop {
+        interval.tick().await;
+
+        match reset_daily_stock(&state.db).await {
+            Ok(count) => tracing::info!("Daily stock reset for {} menu items", count),
+            Err(err) => tracing::error!("Daily stock reset failed: {:?}", err),
+        }
+    }
+}
+
+// The (section, position) of each copy, given the (section, position) of the
+// originals in order. Items whose section has no counterpart are appended
+// after the items without a section, starting at next_position.
+fn place_copied_items(
+    items: impl Iterator<Item = (Option<i32>, i32)>,
+    section_ids: &HashMap<i32, i32>,
+    mut next_position: i32,
+) -> Vec<(Option<i32>, i32)> {
+    items
+        .map(|(section_id, position)| {
+            match section_id.and_then(|section_id| section_ids.get(&section_id).copied()) {
+                Some(section_id) => (Some(section_id), position),
+                None => {
+                    next_position += 1;
+                    (None, next_position - 1)
+                }
+            }
+        })
+        .collect()
+}
+
+// Copies items and their modifiers into a menu, in their current order.
+// Items keep their position inside a section found in section_ids, the
+// others go after the items without a section. Availability and stock start
+// over on the copies.
+pub async fn copy_menu_items(
+    conn: &mut PgConnection,
+    restaurant_menu_item_ids: &[i32],
+    target_restaurant_menu_id: i32,
+    section_ids: &HashMap<i32, i32>,
+    copy_images: bool,
+) -> Result<Vec<i32>, sqlx::Error> {
+    let items = sqlx::query!(
+        "SELECT restaurant_menu_item_id,cover_image_uri,restaurant_menu_section_id,position
+        FROM restaurant_menu_items
+        WHERE restaurant_menu_item_id = ANY($1) AND deleted_at IS NULL
+        ORDER BY position ASC, restaurant_menu_item_id ASC",
+        restaurant_menu_item_ids
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let next_position = sqlx::query_scalar!(
+        r#"SELECT COALESCE(MAX(position)+1,0) AS "position!" FROM restaurant_menu_items
+        WHERE restaurant_menu_id = $1 AND restaurant_menu_section_id IS NULL AND deleted_at IS NULL"#,
+        target_restaurant_menu_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let placements = place_copied_items(
+        items
+            .iter()
+            .map(|item| (item.restaurant_menu_section_id, item.position)),
+        section_ids,
+        next_position,
+    );
+
+    let mut copied_item_ids = Vec::with_capacity(items.len());
+    for (item, (section_id, position)) in items.into_iter().zip(placements) {
+        let cover_image_uri = if copy_images && !item.cover_image_uri.is_empty() {
+            copy_file(&item.cover_image_uri).unwrap_or(item.cover_image_uri)
+        } else {
+            item.cover_image_uri
+        };
+
+        let copied_item_id = sqlx::query_scalar!(
+            "INSERT INTO restaurant_menu_items (name,description,restaurant_menu_id,cover_image_uri,price_minor,restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams)
+            SELECT name,description,$2,$3,price_minor,$4,$5,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams
+            FROM restaurant_menu_items WHERE restaurant_menu_item_id = $1
+            RETURNING restaurant_menu_item_id",
+            item.restaurant_menu_item_id,
+            target_restaurant_menu_id,
+            cover_image_uri,
+            section_id,
+            position
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let group_ids = sqlx::query_scalar!(
+            "SELECT restaurant_menu_item_modifier_group_id FROM restaurant_menu_item_modifier_groups
+            WHERE restaurant_menu_item_id = $1",
+            item.restaurant_menu_item_id
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        for group_id in group_ids {
+            sqlx::query!(
+                "WITH copied_group AS (
+                    INSERT INTO restaurant_menu_item_modifier_groups (restaurant_menu_item_id,name,selection_type,is_required,min_selections,max_selections,position)
+                    SELECT $2,name,selection_type,is_required,min_selections,max_selections,position
+                    FROM restaurant_menu_item_modifier_groups WHERE restaurant_menu_item_modifier_group_id = $1
+                    RETURNING restaurant_menu_item_modifier_group_id
+                )
+                INSERT INTO restaurant_menu_item_modifier_options (restaurant_menu_item_modifier_group_id,name,price_delta_minor,position)
+                SELECT copied_group.restaurant_menu_item_modifier_group_id,o.name,o.price_delta_minor,o.position
+                FROM restaurant_menu_item_modifier_options o, copied_group
+                WHERE o.restaurant_menu_item_modifier_group_id = $1",
+                group_id,
+                copied_item_id
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        copied_item_ids.push(copied_item_id);
+    }
+
+    Ok(copied_item_ids)
+}
+
+// The refill and availability rules live in SQL, those tests run them against
+// the database the queries are checked with.
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+    use super::*;
+
+    async fn pool() -> Pool<Postgres> {
+        dotenv::dotenv().ok();
+        let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        Pool::connect(&database_url).await.unwrap()
+    }
+
+    async fn is_reset_due(
+        db: &Pool<Postgres>,
+        stock_reset_on: Option<NaiveDate>,
+        timezone: &str,
+        at: &str,
+    ) -> bool {
+        let at: DateTime<Utc> = at.parse().unwrap();
+        sqlx::query_scalar!(
+            r#"SELECT stock_reset_due($1, $2, $3) AS "is_due!""#,
+            stock_reset_on,
+            timezone,
+            at
+        )
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    async fn availability(
+        db: &Pool<Postgres>,
+        availability: &str,
+        sold_out_until: Option<DateTime<Utc>>,
+        remaining_stock: Option<i32>,
+    ) -> String {
+        sqlx::query_scalar!(
+            r#"SELECT menu_item_availability($1, $2, $3) AS "availability!""#,
+            availability,
+            sold_out_until,
+            remaining_stock
+        )
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    #[test]
+    fn copies_without_a_target_section_are_appended() {
+        let items = [(None, 0), (Some(4), 1), (None, 1), (Some(5), 0)];
+
+        assert_eq!(
+            place_copied_items(items.into_iter(), &HashMap::new(), 3),
+            vec![(None, 3), (None, 4), (None, 5), (None, 6)]
+        );
+    }
+
+    #[test]
+    fn copies_keep_their_position_in_a_copied_section() {
+        let items = [(Some(4), 2), (Some(5), 0), (None, 7)];
+        let section_ids = HashMap::from([(4, 40)]);
+
+        assert_eq!(
+            place_copied_items(items.into_iter(), &section_ids, 0),
+            vec![(Some(40), 2), (None, 0), (None, 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn stock_is_refilled_once_per_local_day() {
+        let db = pool().await;
+        let june_14 = NaiveDate::from_ymd_opt(2024, 6, 14);
+
+        assert!(is_reset_due(&db, None, "UTC", "2024-06-14T12:00:00Z").await);
+        assert!(!is_reset_due(&db, june_14, "UTC", "2024-06-14T23:59:00Z").await);
+        assert!(is_reset_due(&db, june_14, "UTC", "2024-06-15T00:00:00Z").await);
+    }
+
+    #[tokio::test]
+    async fn stock_days_start_at_local_midnight() {
+        let db = pool().await;
+        let june_14 = NaiveDate::from_ymd_opt(2024, 6, 14);
+
+        // 16:00 UTC is already the 15th in Tokyo.
+        assert!(is_reset_due(&db, june_14, "Asia/Tokyo", "2024-06-14T16:00:00Z").await);
+        assert!(!is_reset_due(&db, june_14, "Asia/Tokyo", "2024-06-14T14:00:00Z").await);
+        // 03:00 UTC on the 15th is still the 14th in Los Angeles.
+        assert!(!is_reset_due(&db, june_14, "America/Los_Angeles", "2024-06-15T03:00:00Z").await);
+        assert!(is_reset_due(&db, june_14, "America/Los_Angeles", "2024-06-15T07:00:00Z").await);
+    }
+
+    #[tokio::test]
+    async fn sold_out_items_come_back_on_their_own() {
+        let db = pool().await;
+        let later = Some(Utc::now() + Duration::hours(1));
+        let earlier = Some(Utc::now() - Duration::hours(1));
+
+        assert_eq!(
+            availability(&db, "available", None, None).await,
+            "available"
+        );
+        assert_eq!(availability(&db, "hidden", None, Some(0)).await, "hidden");
+        assert_eq!(
+            availability(&db, "sold_out", None, Some(5)).await,
+            "sold_out"
+        );
+        assert_eq!(availability(&db, "sold_out", later, None).await, "sold_out");
+        assert_eq!(
+            availability(&db, "sold_out", earlier, None).await,
+            "available"
+        );
+        assert_eq!(
+            availability(&db, "available", None, Some(0)).await,
+            "sold_out"
+        );
+        assert_eq!(
+            availability(&db, "sold_out", earlier, Some(0)).await,
+            "sold_out"
+        );
+    }
+}
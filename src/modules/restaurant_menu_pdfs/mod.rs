@@ -0,0 +1,3 @@
+pub mod restaurant_menu_pdfs_controller;
+pub mod restaurant_menu_pdfs_dto;
+pub mod restaurant_menu_pdfs_service;
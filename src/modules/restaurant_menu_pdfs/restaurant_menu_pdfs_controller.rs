@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use crate::{
+    common::{
+        etag::{etag_for, is_not_modified},
+        locale::RequestLocales,
+    },
+    modules::{
+        restaurants::restaurants_service::can_manage_restaurant, shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_DISPOSITION, CONTENT_TYPE, ETAG},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Extension,
+};
+
+use super::{
+    restaurant_menu_pdfs_dto::{MenuPdfInput, MENU_PDF_LAYOUT_VERSION},
+    restaurant_menu_pdfs_service::{
+        find_menu_pdf_content, find_menu_pdf_locale, load_menu_pdf_images, render_menu_pdf,
+    },
+};
+
+// Drawn again only when something printed on it changed, otherwise the PDF
+// stored for the template and locale is sent back.
+pub async fn get_restaurant_menu_pdf(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    menu_pdf_input: Query<MenuPdfInput>,
+    headers: HeaderMap,
+    locales: RequestLocales,
+) -> Response {
+    let internal_error = || {
+        AppResult::<()>::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )
+        .into_response()
+    };
+    let not_found = || {
+        AppResult::<()>::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+            .into_response()
+    };
+
+    match can_manage_restaurant(&state.db, restaurant_id, &current_user).await {
+        Ok(true) => {}
+        Ok(false) => return not_found(),
+        Err(_) => return internal_error(),
+    }
+
+    let content = match find_menu_pdf_content(&state.db, restaurant_id, &locales).await {
+        Ok(Some(content)) => content,
+        Ok(None) => return not_found(),
+        Err(_) => return internal_error(),
+    };
+
+    let template = menu_pdf_input.template;
+    let fingerprint =
+        match serde_json::to_vec(&(MENU_PDF_LAYOUT_VERSION, template.as_str(), &content)) {
+            Ok(body) => etag_for(&body),
+            Err(_) => return internal_error(),
+        };
+    let cache_headers = [
+        (ETAG, fingerprint.clone()),
+        (CACHE_CONTROL, String::from("private, no-cache")),
+    ];
+
+    if is_not_modified(&headers, &fingerprint) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    let locale = match find_menu_pdf_locale(&state.db, restaurant_id, &locales).await {
+        Ok(locale) => locale,
+        Err(_) => return internal_error(),
+    };
+    let cached_pdf = sqlx::query_scalar!(
+        "SELECT pdf FROM restaurant_menu_pdfs
+        WHERE restaurant_id = $1 AND template = $2 AND locale = $3 AND fingerprint = $4",
+        restaurant_id,
+        template.as_str(),
+        locale,
+        fingerprint
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    let pdf = match cached_pdf {
+        Ok(Some(pdf)) => pdf,
+        Ok(None) => {
+            let images = load_menu_pdf_images(&content, template).await;
+            // Drawing is CPU bound, keep it off the async workers.
+            let pdf = match tokio::task::spawn_blocking(move || {
+                render_menu_pdf(&content, template, images)
+            })
+            .await
+            {
+                Ok(pdf) => pdf,
+                Err(_) => return internal_error(),
+            };
+
+            let saved = sqlx::query!(
+                "INSERT INTO restaurant_menu_pdfs (restaurant_id,template,locale,fingerprint,pdf)
+                VALUES ($1,$2,$3,$4,$5)
+                ON CONFLICT (restaurant_id,template,locale)
+                DO UPDATE SET fingerprint = $4, pdf = $5, created_at = NOW()",
+                restaurant_id,
+                template.as_str(),
+                locale,
+                fingerprint,
+                pdf
+            )
+            .execute(&state.db)
+            .await;
+            if saved.is_err() {
+                return internal_error();
+            }
+            pdf
+        }
+        Err(_) => return internal_error(),
+    };
+
+    (
+        StatusCode::OK,
+        cache_headers,
+        [
+            (CONTENT_TYPE, String::from("application/pdf")),
+            (
+                CONTENT_DISPOSITION,
+                format!(
+                    "inline; filename=\"restaurant-{}-menu-{}.pdf\"",
+                    restaurant_id,
+                    template.as_str()
+                ),
+            ),
+        ],
+        pdf,
+    )
+        .into_response()
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::modules::restaurant_menus::restaurant_menus_dto::RestaurantMenuWithItems;
+
+// Bumped whenever the rendering changes, so cached PDFs are drawn again.
+pub const MENU_PDF_LAYOUT_VERSION: i32 = 1;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuPdfTemplateEnum {
+    // Serif, centered headings and dotted leaders up to the prices.
+    #[default]
+    Classic,
+    // Sans serif under a colored band, with larger pictures.
+    Modern,
+    // Two columns of small text without pictures, to fit on fewer pages.
+    Compact,
+}
+
+impl MenuPdfTemplateEnum {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MenuPdfTemplateEnum::Classic => "classic",
+            MenuPdfTemplateEnum::Modern => "modern",
+            MenuPdfTemplateEnum::Compact => "compact",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MenuPdfInput {
+    #[serde(default)]
+    pub template: MenuPdfTemplateEnum,
+}
+
+// Everything printed on the PDF, hashed to tell when it has to be drawn
+// again.
+#[derive(Serialize)]
+pub struct MenuPdfContent {
+    pub restaurant_name: String,
+    pub description: String,
+    pub location: String,
+    pub phone: String,
+    pub menus: Vec<RestaurantMenuWithItems>,
+}
@@ -0,0 +1,655 @@
+use std::collections::HashMap;
+
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    common::{
+        locale::RequestLocales,
+        money::{format_minor_units, Money},
+        pdf::{
+            wrap_text, PdfColor, PdfDocument, PdfFont, PdfImage, PdfLineStyle, PdfTextStyle,
+            A4_HEIGHT, A4_WIDTH,
+        },
+    },
+    modules::{
+        files::files_controller::read_file,
+        restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+        restaurant_menu_sections::restaurant_menu_sections_service::{
+            find_restaurant_menu_sections, group_menu_items,
+        },
+        restaurant_menus::restaurant_menus_dto::{RestaurantMenu, RestaurantMenuWithItems},
+        translations::translations_service::{
+            find_restaurant_default_locale, preferred_locales, translate_menu_items,
+            translate_menus, translate_texts, TranslatableText, TranslationTarget,
+        },
+    },
+};
+
+use super::restaurant_menu_pdfs_dto::{MenuPdfContent, MenuPdfTemplateEnum};
+
+const MARGIN: f32 = 48.0;
+const COLUMN_GAP: f32 = 24.0;
+const FOOTER_HEIGHT: f32 = 24.0;
+const LINE_HEIGHT: f32 = 1.3;
+const ITEM_GAP: f32 = 10.0;
+
+// Every active menu whatever its schedule, a printed menu outlives the
+// hour it is printed at. Stock and sold out states are left out for the
+// same reason, hidden items are not printed.
+pub async fn find_menu_pdf_content(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+    locales: &RequestLocales,
+) -> Result<Option<MenuPdfContent>, sqlx::Error> {
+    let Some(mut restaurant) = sqlx::query!(
+        "SELECT restaurant_id,name,description,location,phone,default_locale FROM restaurants
+        WHERE restaurant_id = $1 AND deleted_at IS NULL",
+        restaurant_id
+    )
+    .fetch_optional(db)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let text = TranslatableText {
+        id: restaurant.restaurant_id as i64,
+        default_locale: &restaurant.default_locale,
+        name: &mut restaurant.name,
+        description: Some(&mut restaurant.description),
+    };
+    translate_texts(db, TranslationTarget::Restaurant, locales, vec![text]).await?;
+
+    let mut menus = sqlx::query_as!(
+        RestaurantMenu,
+        "SELECT rm.restaurant_menu_id,rm.name,rm.is_active,rm.restaurant_id,rm.restaurant_chain_id
+        FROM restaurant_menus rm
+        JOIN restaurants r ON rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id
+        WHERE r.restaurant_id = $1 AND rm.is_active = true AND rm.deleted_at IS NULL
+        ORDER BY rm.restaurant_menu_id ASC",
+        restaurant_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let restaurant_menu_ids: Vec<i32> = menus
+        .iter()
+        .map(|menu| menu.restaurant_menu_id as i32)
+        .collect();
+
+    let mut items = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"SELECT
+        rmi.restaurant_menu_item_id,
+        rmi.name,
+        menu_money(COALESCE(rmio.price_minor, rmi.price_minor), rmi.restaurant_menu_id) AS "price!: Money",
+        rmi.description,
+        rmi.cover_image_uri,
+        rmi.restaurant_menu_section_id,
+        rmi.position,
+        rmi.allergens,
+        rmi.diet_labels,
+        rmi.spicy_level,
+        rmi.calories,
+        rmi.protein_grams,
+        rmi.carbohydrate_grams,
+        rmi.fat_grams,
+        'available' AS "availability!",
+        NULL::TIMESTAMPTZ AS sold_out_until,
+        NULL::INTEGER AS daily_stock,
+        NULL::INTEGER AS remaining_stock,
+        rmi.restaurant_menu_id,
+        false AS "is_favorite!"
+        FROM restaurant_menu_items rmi
+        LEFT JOIN restaurant_menu_item_overrides rmio
+        ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id AND rmio.restaurant_id = $2
+        WHERE rmi.restaurant_menu_id = ANY($1)
+        AND rmi.deleted_at IS NULL
+        AND COALESCE(rmio.is_available, true)
+        AND rmi.availability <> 'hidden'
+        ORDER BY rmi.restaurant_menu_id ASC, rmi.position ASC, rmi.restaurant_menu_item_id ASC"#,
+        &restaurant_menu_ids,
+        restaurant_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    translate_menus(db, locales, restaurant_id, &mut menus).await?;
+    translate_menu_items(db, locales, restaurant_id, &mut items).await?;
+
+    let sections = find_restaurant_menu_sections(db, &restaurant_menu_ids).await?;
+
+    let mut items_by_menu: HashMap<i64, Vec<RestaurantMenuItem>> = HashMap::new();
+    for item in items {
+        items_by_menu
+            .entry(item.restaurant_menu_id)
+            .or_default()
+            .push(item);
+    }
+
+    let mut sections_by_menu: HashMap<i32, Vec<_>> = HashMap::new();
+    for section in sections {
+        sections_by_menu
+            .entry(section.restaurant_menu_id)
+            .or_default()
+            .push(section);
+    }
+
+    let menus = menus
+        .into_iter()
+        .map(|menu| {
+            let (items, sections) = group_menu_items(
+                items_by_menu
+                    .remove(&menu.restaurant_menu_id)
+                    .unwrap_or_default(),
+                sections_by_menu
+                    .remove(&(menu.restaurant_menu_id as i32))
+                    .unwrap_or_default(),
+            );
+            RestaurantMenuWithItems {
+                menu,
+                items,
+                sections,
+            }
+        })
+        // An empty menu would only print its title.
+        .filter(|menu| {
+            !menu.items.is_empty()
+                || menu
+                    .sections
+                    .iter()
+                    .any(|section| !section.items.is_empty())
+        })
+        .collect();
+
+    Ok(Some(MenuPdfContent {
+        restaurant_name: restaurant.name,
+        description: restaurant.description,
+        location: restaurant.location,
+        phone: restaurant.phone,
+        menus,
+    }))
+}
+
+// The locale a PDF is stored under: the first requested one the restaurant
+// has translated anything printed into, its default locale otherwise.
+pub async fn find_menu_pdf_locale(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+    locales: &RequestLocales,
+) -> Result<String, sqlx::Error> {
+    let default_locale = find_restaurant_default_locale(db, restaurant_id).await?;
+    let locales = preferred_locales(&locales.0, &default_locale);
+    if locales.is_empty() {
+        return Ok(default_locale);
+    }
+
+    let translated_locales = sqlx::query_scalar!(
+        r#"SELECT locale AS "locale!" FROM restaurant_translations
+        WHERE restaurant_id = $1 AND locale = ANY($2)
+        UNION
+        SELECT rmt.locale FROM restaurant_menu_translations rmt
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmt.restaurant_menu_id
+        JOIN restaurants r ON rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id
+        WHERE r.restaurant_id = $1 AND rmt.locale = ANY($2)
+        UNION
+        SELECT rmit.locale FROM restaurant_menu_item_translations rmit
+        JOIN restaurant_menu_items rmi ON rmi.restaurant_menu_item_id = rmit.restaurant_menu_item_id
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+        JOIN restaurants r ON rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id
+        WHERE r.restaurant_id = $1 AND rmit.locale = ANY($2)"#,
+        restaurant_id,
+        locales
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(locales
+        .iter()
+        .find(|locale| translated_locales.contains(locale))
+        .cloned()
+        .unwrap_or(default_locale))
+}
+
+fn menu_pdf_items(content: &MenuPdfContent) -> Vec<&RestaurantMenuItem> {
+    content
+        .menus
+        .iter()
+        .flat_map(|menu| {
+            menu.items
+                .iter()
+                .chain(menu.sections.iter().flat_map(|section| &section.items))
+        })
+        .collect()
+}
+
+// Item pictures that can be embedded, by cover_image_uri. Missing files and
+// unsupported formats are printed without a picture.
+pub async fn load_menu_pdf_images(
+    content: &MenuPdfContent,
+    template: MenuPdfTemplateEnum,
+) -> HashMap<String, PdfImage> {
+    let mut images = HashMap::new();
+    if menu_pdf_style(template).image_size <= 0.0 {
+        return images;
+    }
+
+    let mut uris: Vec<String> = menu_pdf_items(content)
+        .into_iter()
+        .map(|item| item.cover_image_uri.clone())
+        .filter(|uri| !uri.is_empty())
+        .collect();
+    uris.sort();
+    uris.dedup();
+
+    for uri in uris {
+        if let Some(image) = read_file(&uri)
+            .await
+            .and_then(|bytes| PdfImage::from_bytes(&bytes))
+        {
+            images.insert(uri, image);
+        }
+    }
+    images
+}
+
+struct MenuPdfStyle {
+    title: PdfTextStyle,
+    subtitle: PdfTextStyle,
+    menu_title: PdfTextStyle,
+    section_title: PdfTextStyle,
+    item_name: PdfTextStyle,
+    item_price: PdfTextStyle,
+    item_description: PdfTextStyle,
+    item_details: PdfTextStyle,
+    footer: PdfTextStyle,
+    rule: PdfLineStyle,
+    leader: Option<PdfLineStyle>,
+    // Colored band behind the restaurant name, its title is then white.
+    header_band: Option<PdfColor>,
+    is_centered: bool,
+    columns: usize,
+    // Width and height of item pictures, 0 to leave them out.
+    image_size: f32,
+}
+
+fn menu_pdf_style(template: MenuPdfTemplateEnum) -> MenuPdfStyle {
+    let ink = PdfColor(0x22, 0x22, 0x22);
+    let muted = PdfColor(0x6B, 0x6B, 0x6B);
+    let text = |font: PdfFont, size: f32, color: PdfColor| PdfTextStyle { font, size, color };
+
+    match template {
+        MenuPdfTemplateEnum::Classic => {
+            let accent = PdfColor(0x7A, 0x1F, 0x1F);
+            MenuPdfStyle {
+                title: text(PdfFont::TimesBold, 28.0, ink),
+                subtitle: text(PdfFont::TimesItalic, 11.0, muted),
+                menu_title: text(PdfFont::TimesBold, 20.0, accent),
+                section_title: text(PdfFont::TimesBold, 14.0, ink),
+                item_name: text(PdfFont::TimesBold, 12.0, ink),
+                item_price: text(PdfFont::TimesBold, 12.0, ink),
+                item_description: text(PdfFont::TimesItalic, 10.0, muted),
+                item_details: text(PdfFont::TimesRoman, 8.5, muted),
+                footer: text(PdfFont::TimesItalic, 8.0, muted),
+                rule: PdfLineStyle {
+                    width: 0.6,
+                    dash: 0.0,
+                    color: accent,
+                },
+                leader: Some(PdfLineStyle {
+                    width: 0.8,
+                    dash: 1.5,
+                    color: muted,
+                }),
+                header_band: None,
+                is_centered: true,
+                columns: 1,
+                image_size: 48.0,
+            }
+        }
+        MenuPdfTemplateEnum::Modern => {
+            let accent = PdfColor(0x1F, 0x6F, 0x5C);
+            MenuPdfStyle {
+                title: text(PdfFont::HelveticaBold, 26.0, PdfColor(0xFF, 0xFF, 0xFF)),
+                subtitle: text(PdfFont::Helvetica, 10.0, muted),
+                menu_title: text(PdfFont::HelveticaBold, 18.0, accent),
+                section_title: text(PdfFont::HelveticaBold, 12.0, accent),
+                item_name: text(PdfFont::HelveticaBold, 11.0, ink),
+                item_price: text(PdfFont::HelveticaBold, 11.0, accent),
+                item_description: text(PdfFont::Helvetica, 9.5, muted),
+                item_details: text(PdfFont::HelveticaOblique, 8.0, muted),
+                footer: text(PdfFont::Helvetica, 8.0, muted),
+                rule: PdfLineStyle {
+                    width: 1.5,
+                    dash: 0.0,
+                    color: accent,
+                },
+                leader: None,
+                header_band: Some(accent),
+                is_centered: false,
+                columns: 1,
+                image_size: 64.0,
+            }
+        }
+        MenuPdfTemplateEnum::Compact => MenuPdfStyle {
+            title: text(PdfFont::HelveticaBold, 18.0, ink),
+            subtitle: text(PdfFont::Helvetica, 8.5, muted),
+            menu_title: text(PdfFont::HelveticaBold, 13.0, ink),
+            section_title: text(PdfFont::HelveticaBold, 10.0, muted),
+            item_name: text(PdfFont::HelveticaBold, 9.0, ink),
+            item_price: text(PdfFont::HelveticaBold, 9.0, ink),
+            item_description: text(PdfFont::Helvetica, 8.0, muted),
+            item_details: text(PdfFont::HelveticaOblique, 7.0, muted),
+            footer: text(PdfFont::Helvetica, 7.0, muted),
+            rule: PdfLineStyle {
+                width: 0.5,
+                dash: 0.0,
+                color: muted,
+            },
+            leader: None,
+            header_band: None,
+            is_centered: false,
+            columns: 2,
+            image_size: 0.0,
+        },
+    }
+}
+
+fn line_height(style: &PdfTextStyle) -> f32 {
+    style.size * LINE_HEIGHT
+}
+
+fn format_price(price: &Money) -> String {
+    format!(
+        "{} {}",
+        format_minor_units(price.amount_minor, price.exponent),
+        price.currency
+    )
+}
+
+// "Allergens: gluten, milk · vegan · spicy 2/3"
+fn item_details(item: &RestaurantMenuItem) -> String {
+    let mut details = Vec::new();
+    if !item.allergens.is_empty() {
+        details.push(format!("Allergens: {}", item.allergens.join(", ")));
+    }
+    for diet_label in &item.diet_labels {
+        details.push(diet_label.replace('_', " "));
+    }
+    if let Some(spicy_level) = item.spicy_level.filter(|&spicy_level| spicy_level > 0) {
+        details.push(format!("spicy {}/3", spicy_level));
+    }
+    details.join(" \u{b7} ")
+}
+
+// Document image id and pixel size of a picture.
+type PlacedImage = (usize, (u32, u32));
+
+// Lays the menu out top to bottom, column after column. y is measured
+// from the top of the page, the PDF one from the bottom.
+struct MenuPdfLayout<'a> {
+    doc: PdfDocument,
+    style: &'a MenuPdfStyle,
+    images: HashMap<String, PlacedImage>,
+    column: usize,
+    column_top: f32,
+    y: f32,
+}
+
+impl<'a> MenuPdfLayout<'a> {
+    fn column_width(&self) -> f32 {
+        let columns = self.style.columns as f32;
+        (A4_WIDTH - 2.0 * MARGIN - (columns - 1.0) * COLUMN_GAP) / columns
+    }
+
+    fn column_x(&self) -> f32 {
+        MARGIN + self.column as f32 * (self.column_width() + COLUMN_GAP)
+    }
+
+    fn new_page(&mut self) {
+        self.doc.add_page();
+        self.column = 0;
+        self.column_top = MARGIN;
+        self.y = MARGIN;
+    }
+
+    // Moves to the next column or page unless height still fits.
+    fn ensure_space(&mut self, height: f32) {
+        if self.y + height <= A4_HEIGHT - MARGIN - FOOTER_HEIGHT || self.y <= self.column_top {
+            return;
+        }
+        if self.column + 1 < self.style.columns {
+            self.column += 1;
+            self.y = self.column_top;
+        } else {
+            self.new_page();
+        }
+    }
+
+    // Writes a line whose top is at the current y and moves below it.
+    fn text_line(&mut self, x: f32, style: &PdfTextStyle, text: &str) {
+        let baseline = A4_HEIGHT - self.y - style.size;
+        self.doc.text(x, baseline, style, text);
+        self.y += line_height(style);
+    }
+
+    fn aligned_line(&mut self, style: &PdfTextStyle, text: &str) {
+        let x = if self.style.is_centered {
+            self.column_x() + (self.column_width() - style.text_width(text)) / 2.0
+        } else {
+            self.column_x()
+        };
+        self.text_line(x, style, text);
+    }
+
+    fn rule(&mut self) {
+        let y = A4_HEIGHT - self.y;
+        let x = self.column_x();
+        let width = self.column_width();
+        let (from, to) = if self.style.is_centered {
+            ((x + width * 0.3, y), (x + width * 0.7, y))
+        } else {
+            ((x, y), (x + width, y))
+        };
+        self.doc.line(from, to, &self.style.rule);
+    }
+
+    fn header(&mut self, content: &MenuPdfContent) {
+        let style = self.style;
+        let width = A4_WIDTH - 2.0 * MARGIN;
+        let title_lines = wrap_text(&content.restaurant_name, &style.title, width);
+
+        if let Some(band) = style.header_band {
+            let height = MARGIN + title_lines.len() as f32 * line_height(&style.title) + 16.0;
+            self.doc
+                .rect(0.0, A4_HEIGHT - height, A4_WIDTH, height, band);
+            for line in &title_lines {
+                self.text_line(MARGIN, &style.title, line);
+            }
+            self.y = height + 14.0;
+        } else {
+            for line in &title_lines {
+                self.aligned_line(&style.title, line);
+            }
+            self.y += 4.0;
+        }
+
+        let contact: Vec<&str> = [content.location.as_str(), content.phone.as_str()]
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .collect();
+        let contact = contact.join(" \u{b7} ");
+        for text in [&content.description, &contact] {
+            for line in wrap_text(text, &style.subtitle, width) {
+                self.aligned_line(&style.subtitle, &line);
+            }
+        }
+
+        self.y += 10.0;
+        self.column_top = self.y;
+    }
+
+    fn menu_title(&mut self, name: &str) {
+        let style = self.style;
+        // Keeps the title with at least its first item.
+        self.ensure_space(line_height(&style.menu_title) + 60.0);
+        if self.y > self.column_top {
+            self.y += 12.0;
+        }
+        for line in wrap_text(name, &style.menu_title, self.column_width()) {
+            self.aligned_line(&style.menu_title, &line);
+        }
+        self.y += 2.0;
+        self.rule();
+        self.y += 10.0;
+    }
+
+    fn section_title(&mut self, name: &str) {
+        let style = self.style;
+        self.ensure_space(line_height(&style.section_title) + 40.0);
+        self.y += 4.0;
+        let name = if style.is_centered {
+            name.to_uppercase()
+        } else {
+            name.to_string()
+        };
+        for line in wrap_text(&name, &style.section_title, self.column_width()) {
+            self.aligned_line(&style.section_title, &line);
+        }
+        self.y += 4.0;
+    }
+
+    fn item(&mut self, item: &RestaurantMenuItem) {
+        let style = self.style;
+        let image = self.images.get(item.cover_image_uri.as_str()).copied();
+        let text_offset = if image.is_some() {
+            style.image_size + 10.0
+        } else {
+            0.0
+        };
+        let text_width = self.column_width() - text_offset;
+
+        let price = format_price(&item.price);
+        let price_width = style.item_price.text_width(&price);
+        let name_lines = wrap_text(
+            &item.name,
+            &style.item_name,
+            text_width - price_width - 12.0,
+        );
+        let description_lines = wrap_text(&item.description, &style.item_description, text_width);
+        let details_lines = wrap_text(&item_details(item), &style.item_details, text_width);
+
+        let text_height = name_lines.len() as f32 * line_height(&style.item_name)
+            + description_lines.len() as f32 * line_height(&style.item_description)
+            + details_lines.len() as f32 * line_height(&style.item_details);
+        let height = if image.is_some() {
+            text_height.max(style.image_size)
+        } else {
+            text_height
+        };
+        self.ensure_space(height);
+
+        let top = self.y;
+        let x = self.column_x();
+        let text_x = x + text_offset;
+
+        if let Some(image) = image {
+            self.draw_picture(image, x, top);
+        }
+
+        let price_x = x + self.column_width() - price_width;
+        let first_baseline = A4_HEIGHT - top - style.item_name.size;
+        self.doc
+            .text(price_x, first_baseline, &style.item_price, &price);
+        if let (Some(leader), [name]) = (&style.leader, name_lines.as_slice()) {
+            let from = text_x + style.item_name.text_width(name) + 4.0;
+            let to = price_x - 4.0;
+            if to - from > 8.0 {
+                self.doc
+                    .line((from, first_baseline), (to, first_baseline), leader);
+            }
+        }
+
+        for line in &name_lines {
+            self.text_line(text_x, &style.item_name, line);
+        }
+        for line in &description_lines {
+            self.text_line(text_x, &style.item_description, line);
+        }
+        for line in &details_lines {
+            self.text_line(text_x, &style.item_details, line);
+        }
+
+        self.y = top + height + ITEM_GAP;
+    }
+
+    // Fits the picture in its square, keeping its proportions.
+    fn draw_picture(&mut self, (image_id, (width, height)): PlacedImage, x: f32, top: f32) {
+        let size = self.style.image_size;
+        if width == 0 || height == 0 {
+            return;
+        }
+        let scale = size / width.max(height) as f32;
+        let (width, height) = (width as f32 * scale, height as f32 * scale);
+        self.doc.image(
+            image_id,
+            x + (size - width) / 2.0,
+            A4_HEIGHT - top - (size + height) / 2.0,
+            width,
+            height,
+        );
+    }
+
+    fn footers(&mut self, restaurant_name: &str) {
+        let style = &self.style.footer;
+        let pages = self.doc.page_count();
+        for page in 0..pages {
+            let text = format!("{} \u{b7} {}/{}", restaurant_name, page + 1, pages);
+            let x = (A4_WIDTH - style.text_width(&text)) / 2.0;
+            self.doc.text_on(page, x, MARGIN - style.size, style, &text);
+        }
+    }
+}
+
+pub fn render_menu_pdf(
+    content: &MenuPdfContent,
+    template: MenuPdfTemplateEnum,
+    images: HashMap<String, PdfImage>,
+) -> Vec<u8> {
+    let style = menu_pdf_style(template);
+    let mut doc = PdfDocument::new(A4_WIDTH, A4_HEIGHT, &content.restaurant_name);
+    let mut image_ids = HashMap::new();
+    for (uri, image) in images {
+        let size = (image.width, image.height);
+        image_ids.insert(uri, (doc.add_image(image), size));
+    }
+    let mut layout = MenuPdfLayout {
+        doc,
+        style: &style,
+        images: image_ids,
+        column: 0,
+        column_top: MARGIN,
+        y: MARGIN,
+    };
+    layout.new_page();
+    layout.header(content);
+
+    for menu in &content.menus {
+        layout.menu_title(&menu.menu.name);
+        for item in &menu.items {
+            layout.item(item);
+        }
+        for section in &menu.sections {
+            if section.items.is_empty() {
+                continue;
+            }
+            layout.section_title(&section.section.name);
+            for item in &section.items {
+                layout.item(item);
+            }
+        }
+    }
+
+    layout.footers(&content.restaurant_name);
+    layout.doc.to_bytes()
+}
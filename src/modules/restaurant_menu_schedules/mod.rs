@@ -0,0 +1,2 @@
+pub mod restaurant_menu_schedules_controller;
+pub mod restaurant_menu_schedules_dto;
@@ -0,0 +1,239 @@
+use std::sync::Arc;
+
+use crate::{
+    modules::{
+        restaurants::restaurants_service::check_restaurant_menu_access,
+        shared::shared_dto::AppResult, users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use super::restaurant_menu_schedules_dto::{CreateRestaurantMenuSchedule, RestaurantMenuSchedule};
+
+pub async fn get_restaurant_menu_schedules(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<Vec<RestaurantMenuSchedule>> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuSchedule,
+        "SELECT restaurant_menu_schedule_id,restaurant_menu_id,days_of_week,start_time,end_time,start_date,end_date,created_at
+        FROM restaurant_menu_schedules
+        WHERE restaurant_menu_id = $1
+        ORDER BY restaurant_menu_schedule_id ASC",
+        restaurant_menu_id
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match res {
+        Ok(schedules) => AppResult::Result(StatusCode::OK, schedules),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// A menu with several schedules is available whenever any of them matches.
+pub async fn create_restaurant_menu_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+    Json(create_restaurant_menu_schedule_dto): Json<CreateRestaurantMenuSchedule>,
+) -> AppResult<RestaurantMenuSchedule> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    if !create_restaurant_menu_schedule_dto.is_valid() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("days_of_week must be between 1 and 7, start_time and end_time must be given together and differ, start_date must not be after end_date"),
+        );
+    }
+
+    let mut days_of_week = create_restaurant_menu_schedule_dto.days_of_week;
+    days_of_week.sort_unstable();
+    days_of_week.dedup();
+
+    let res = sqlx::query_as!(
+        RestaurantMenuSchedule,
+        "INSERT INTO restaurant_menu_schedules (restaurant_menu_id,days_of_week,start_time,end_time,start_date,end_date)
+        VALUES ($1,$2,$3,$4,$5,$6)
+        RETURNING restaurant_menu_schedule_id,restaurant_menu_id,days_of_week,start_time,end_time,start_date,end_date,created_at",
+        restaurant_menu_id,
+        &days_of_week,
+        create_restaurant_menu_schedule_dto.start_time,
+        create_restaurant_menu_schedule_dto.end_time,
+        create_restaurant_menu_schedule_dto.start_date,
+        create_restaurant_menu_schedule_dto.end_date
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(schedule) => AppResult::Result(StatusCode::CREATED, schedule),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_menu_schedule(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_schedule_id)): Path<(i32, i32, i32)>,
+) -> AppResult<RestaurantMenuSchedule> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuSchedule,
+        "DELETE FROM restaurant_menu_schedules
+        WHERE restaurant_menu_schedule_id = $1 AND restaurant_menu_id = $2
+        RETURNING restaurant_menu_schedule_id,restaurant_menu_id,days_of_week,start_time,end_time,start_date,end_date,created_at",
+        restaurant_menu_schedule_id,
+        restaurant_menu_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(schedule)) => AppResult::Result(StatusCode::OK, schedule),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Menu schedule not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Schedules are matched in SQL, these run menu_schedule_matches against the
+// database the queries are checked with.
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+    use sqlx::{Pool, Postgres};
+
+    struct Schedule {
+        days_of_week: Vec<i16>,
+        start_time: Option<NaiveTime>,
+        end_time: Option<NaiveTime>,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    }
+
+    fn hours(start_hour: u32, end_hour: u32) -> Schedule {
+        Schedule {
+            days_of_week: (1..=7).collect(),
+            start_time: NaiveTime::from_hms_opt(start_hour, 0, 0),
+            end_time: NaiveTime::from_hms_opt(end_hour, 0, 0),
+            start_date: None,
+            end_date: None,
+        }
+    }
+
+    async fn pool() -> Pool<Postgres> {
+        dotenv::dotenv().ok();
+        let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+        Pool::connect(&database_url).await.unwrap()
+    }
+
+    async fn matches(db: &Pool<Postgres>, schedule: &Schedule, at: &str, timezone: &str) -> bool {
+        let at: DateTime<Utc> = at.parse().unwrap();
+        sqlx::query_scalar!(
+            r#"SELECT menu_schedule_matches($1,$2,$3,$4,$5,$6 AT TIME ZONE $7) AS "matches!""#,
+            &schedule.days_of_week,
+            schedule.start_time,
+            schedule.end_time,
+            schedule.start_date,
+            schedule.end_date,
+            at,
+            timezone
+        )
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn times_are_read_in_the_restaurant_timezone() {
+        let db = pool().await;
+        let lunch = hours(12, 15);
+
+        // 11:30 UTC is 13:30 in Paris summer time and 07:30 in New York.
+        assert!(matches(&db, &lunch, "2024-06-12T11:30:00Z", "Europe/Paris").await);
+        assert!(!matches(&db, &lunch, "2024-06-12T11:30:00Z", "America/New_York").await);
+        assert!(!matches(&db, &lunch, "2024-06-12T11:30:00Z", "UTC").await);
+        // Paris is only one hour ahead in winter.
+        assert!(!matches(&db, &lunch, "2024-01-10T10:30:00Z", "Europe/Paris").await);
+        assert!(matches(&db, &lunch, "2024-01-10T11:30:00Z", "Europe/Paris").await);
+    }
+
+    #[tokio::test]
+    async fn days_and_dates_follow_the_local_calendar() {
+        let db = pool().await;
+        let weekends = Schedule {
+            days_of_week: vec![6, 7],
+            start_time: None,
+            end_time: None,
+            start_date: None,
+            end_date: NaiveDate::from_ymd_opt(2024, 6, 30),
+        };
+
+        // Friday 23:30 UTC is already Saturday in Tokyo.
+        assert!(!matches(&db, &weekends, "2024-06-14T23:30:00Z", "UTC").await);
+        assert!(matches(&db, &weekends, "2024-06-14T23:30:00Z", "Asia/Tokyo").await);
+        // Sunday 30 June late in Los Angeles is the 1st of July in UTC.
+        assert!(
+            matches(
+                &db,
+                &weekends,
+                "2024-07-01T05:00:00Z",
+                "America/Los_Angeles"
+            )
+            .await
+        );
+        assert!(!matches(&db, &weekends, "2024-07-06T12:00:00Z", "UTC").await);
+    }
+
+    #[tokio::test]
+    async fn overnight_ranges_belong_to_the_day_they_start_on() {
+        let db = pool().await;
+        let friday_nights = Schedule {
+            days_of_week: vec![5],
+            ..hours(22, 2)
+        };
+
+        // Friday 14 June 2024, then the small hours of Saturday.
+        assert!(matches(&db, &friday_nights, "2024-06-14T23:00:00Z", "UTC").await);
+        assert!(matches(&db, &friday_nights, "2024-06-15T01:00:00Z", "UTC").await);
+        assert!(!matches(&db, &friday_nights, "2024-06-15T03:00:00Z", "UTC").await);
+        assert!(!matches(&db, &friday_nights, "2024-06-14T01:00:00Z", "UTC").await);
+        assert!(!matches(&db, &friday_nights, "2024-06-15T23:00:00Z", "UTC").await);
+    }
+}
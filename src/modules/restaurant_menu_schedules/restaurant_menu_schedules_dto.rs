@@ -0,0 +1,50 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// Times and dates are local to the restaurant timezone. Leaving out the times
+// makes the menu available all day, leaving out the dates every week.
+#[derive(Deserialize)]
+pub struct CreateRestaurantMenuSchedule {
+    #[serde(default = "every_day")]
+    pub days_of_week: Vec<i16>,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+fn every_day() -> Vec<i16> {
+    (1..=7).collect()
+}
+
+impl CreateRestaurantMenuSchedule {
+    pub fn is_valid(&self) -> bool {
+        let has_valid_days = !self.days_of_week.is_empty()
+            && self.days_of_week.iter().all(|day| (1..=7).contains(day));
+        let has_valid_times = match (self.start_time, self.end_time) {
+            (Some(start_time), Some(end_time)) => start_time != end_time,
+            (None, None) => true,
+            _ => false,
+        };
+        let has_valid_dates = match (self.start_date, self.end_date) {
+            (Some(start_date), Some(end_date)) => start_date <= end_date,
+            _ => true,
+        };
+
+        has_valid_days && has_valid_times && has_valid_dates
+    }
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantMenuSchedule {
+    pub restaurant_menu_schedule_id: i64,
+    pub restaurant_menu_id: i32,
+    // ISO days, 1 = Monday to 7 = Sunday.
+    pub days_of_week: Vec<i16>,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+}
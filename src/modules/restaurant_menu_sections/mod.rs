@@ -0,0 +1,3 @@
+pub mod restaurant_menu_sections_controller;
+pub mod restaurant_menu_sections_dto;
+pub mod restaurant_menu_sections_service;
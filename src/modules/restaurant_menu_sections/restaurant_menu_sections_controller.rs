@@ -0,0 +1,405 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crate::{
+    modules::{
+        restaurants::restaurants_service::{
+            check_restaurant_menu_access, resolve_restaurant, RestaurantLookup,
+        },
+        shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use super::{
+    restaurant_menu_sections_dto::{
+        CreateRestaurantMenuSection, ReorderRestaurantMenu, RestaurantMenuLayout,
+        RestaurantMenuSection, UpdateRestaurantMenuSection,
+    },
+    restaurant_menu_sections_service::find_restaurant_menu_layout,
+};
+
+pub async fn get_restaurant_menu_sections(
+    State(state): State<Arc<AppState>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(String, i32)>,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<Vec<RestaurantMenuSection>> {
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let res = sqlx::query_as!(
+        RestaurantMenuSection,
+        "SELECT rms.restaurant_menu_section_id,rms.restaurant_menu_id,rms.name,rms.position,rms.created_at
+        FROM restaurant_menu_sections rms
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rms.restaurant_menu_id
+        JOIN restaurants r ON rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id
+        WHERE rms.restaurant_menu_id = $1 AND r.restaurant_id = $2
+        AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        ORDER BY rms.position ASC, rms.restaurant_menu_section_id ASC",
+        restaurant_menu_id,
+        restaurant_id
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match res {
+        Ok(sections) => AppResult::Result(StatusCode::OK, sections),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_restaurant_menu_section(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+    Json(create_restaurant_menu_section_dto): Json<CreateRestaurantMenuSection>,
+) -> AppResult<RestaurantMenuSection> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let name = create_restaurant_menu_section_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty"),
+        );
+    }
+
+    // New sections go last.
+    let res = sqlx::query_as!(
+        RestaurantMenuSection,
+        "INSERT INTO restaurant_menu_sections (restaurant_menu_id,name,position)
+        SELECT $1, $2, COALESCE(MAX(position) + 1, 0) FROM restaurant_menu_sections WHERE restaurant_menu_id = $1
+        RETURNING restaurant_menu_section_id,restaurant_menu_id,name,position,created_at",
+        restaurant_menu_id,
+        name
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(section) => AppResult::Result(StatusCode::CREATED, section),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_restaurant_menu_section(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_section_id)): Path<(i32, i32, i32)>,
+    Json(update_restaurant_menu_section_dto): Json<UpdateRestaurantMenuSection>,
+) -> AppResult<RestaurantMenuSection> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let name = update_restaurant_menu_section_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuSection,
+        "UPDATE restaurant_menu_sections SET name = $3
+        WHERE restaurant_menu_section_id = $1 AND restaurant_menu_id = $2
+        RETURNING restaurant_menu_section_id,restaurant_menu_id,name,position,created_at",
+        restaurant_menu_section_id,
+        restaurant_menu_id,
+        name
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(section)) => AppResult::Result(StatusCode::OK, section),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Menu section not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// The items of the section are kept and moved after the items without a
+// section.
+pub async fn delete_restaurant_menu_section(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_section_id)): Path<(i32, i32, i32)>,
+) -> AppResult<RestaurantMenuSection> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res: Result<Option<RestaurantMenuSection>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let moved_item_ids = sqlx::query_scalar!(
+            "SELECT restaurant_menu_item_id FROM restaurant_menu_items WHERE restaurant_menu_section_id = $1 AND restaurant_menu_id = $2 FOR UPDATE",
+            restaurant_menu_section_id,
+            restaurant_menu_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let section = sqlx::query_as!(
+            RestaurantMenuSection,
+            "DELETE FROM restaurant_menu_sections
+            WHERE restaurant_menu_section_id = $1 AND restaurant_menu_id = $2
+            RETURNING restaurant_menu_section_id,restaurant_menu_id,name,position,created_at",
+            restaurant_menu_section_id,
+            restaurant_menu_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(section) = section else {
+            return Ok(None);
+        };
+
+        // The foreign key already cleared their section, keep their relative
+        // order after the items that had none.
+        sqlx::query!(
+            "UPDATE restaurant_menu_items SET position = ordered.position
+            FROM (
+                SELECT restaurant_menu_item_id,
+                ROW_NUMBER() OVER (ORDER BY restaurant_menu_item_id = ANY($2) ASC, position ASC, restaurant_menu_item_id ASC)::INTEGER - 1 AS position
+                FROM restaurant_menu_items
+                WHERE restaurant_menu_id = $1 AND restaurant_menu_section_id IS NULL
+            ) ordered
+            WHERE restaurant_menu_items.restaurant_menu_item_id = ordered.restaurant_menu_item_id",
+            restaurant_menu_id,
+            &moved_item_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(section))
+    }
+    .await;
+
+    match res {
+        Ok(Some(section)) => AppResult::Result(StatusCode::OK, section),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Menu section not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Item ids with their section and position, 0 standing for "no section".
+fn flatten_menu_layout(
+    reorder_restaurant_menu_dto: &ReorderRestaurantMenu,
+) -> (Vec<i32>, Vec<i32>, Vec<i32>) {
+    let mut restaurant_menu_item_ids: Vec<i32> = Vec::new();
+    let mut item_section_ids: Vec<i32> = Vec::new();
+    let mut item_positions: Vec<i32> = Vec::new();
+    let groups = std::iter::once((0, &reorder_restaurant_menu_dto.restaurant_menu_item_ids)).chain(
+        reorder_restaurant_menu_dto.sections.iter().map(|section| {
+            (
+                section.restaurant_menu_section_id,
+                &section.restaurant_menu_item_ids,
+            )
+        }),
+    );
+    for (restaurant_menu_section_id, item_ids) in groups {
+        for (position, restaurant_menu_item_id) in item_ids.iter().enumerate() {
+            restaurant_menu_item_ids.push(*restaurant_menu_item_id);
+            item_section_ids.push(restaurant_menu_section_id);
+            item_positions.push(position as i32);
+        }
+    }
+
+    (restaurant_menu_item_ids, item_section_ids, item_positions)
+}
+
+pub async fn reorder_restaurant_menu(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+    Json(reorder_restaurant_menu_dto): Json<ReorderRestaurantMenu>,
+) -> AppResult<RestaurantMenuLayout> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let restaurant_menu_section_ids: Vec<i32> = reorder_restaurant_menu_dto
+        .sections
+        .iter()
+        .map(|section| section.restaurant_menu_section_id)
+        .collect();
+
+    let (restaurant_menu_item_ids, item_section_ids, item_positions) =
+        flatten_menu_layout(&reorder_restaurant_menu_dto);
+
+    let res: Result<Option<RestaurantMenuLayout>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let current_section_ids: HashSet<i32> = sqlx::query_scalar!(
+            "SELECT restaurant_menu_section_id FROM restaurant_menu_sections WHERE restaurant_menu_id = $1 FOR UPDATE",
+            restaurant_menu_id
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let current_item_ids: HashSet<i32> = sqlx::query_scalar!(
+            "SELECT restaurant_menu_item_id FROM restaurant_menu_items WHERE restaurant_menu_id = $1 AND deleted_at IS NULL FOR UPDATE",
+            restaurant_menu_id
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let requested_section_ids: HashSet<i32> =
+            restaurant_menu_section_ids.iter().copied().collect();
+        let requested_item_ids: HashSet<i32> =
+            restaurant_menu_item_ids.iter().copied().collect();
+
+        if requested_section_ids.len() != restaurant_menu_section_ids.len()
+            || requested_section_ids != current_section_ids
+            || requested_item_ids.len() != restaurant_menu_item_ids.len()
+            || requested_item_ids != current_item_ids
+        {
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            "UPDATE restaurant_menu_sections SET position = ordered.position::INTEGER - 1
+            FROM UNNEST($2::INTEGER[]) WITH ORDINALITY AS ordered(restaurant_menu_section_id, position)
+            WHERE restaurant_menu_sections.restaurant_menu_section_id = ordered.restaurant_menu_section_id
+            AND restaurant_menu_sections.restaurant_menu_id = $1",
+            restaurant_menu_id,
+            &restaurant_menu_section_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE restaurant_menu_items
+            SET restaurant_menu_section_id = NULLIF(ordered.restaurant_menu_section_id, 0), position = ordered.position
+            FROM UNNEST($2::INTEGER[], $3::INTEGER[], $4::INTEGER[]) AS ordered(restaurant_menu_item_id, restaurant_menu_section_id, position)
+            WHERE restaurant_menu_items.restaurant_menu_item_id = ordered.restaurant_menu_item_id
+            AND restaurant_menu_items.restaurant_menu_id = $1",
+            restaurant_menu_id,
+            &restaurant_menu_item_ids,
+            &item_section_ids,
+            &item_positions
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(
+            find_restaurant_menu_layout(&state.db, restaurant_menu_id).await?,
+        ))
+    }
+    .await;
+
+    match res {
+        Ok(Some(layout)) => AppResult::Result(StatusCode::OK, layout),
+        Ok(None) => AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("sections and restaurant_menu_item_ids must list every section and item of the menu exactly once"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_is_flattened_with_positions_per_section() {
+        let reorder_restaurant_menu_dto: ReorderRestaurantMenu = serde_json::from_str(
+            r#"{
+                "restaurant_menu_item_ids": [7, 3],
+                "sections": [
+                    {"restaurant_menu_section_id": 2, "restaurant_menu_item_ids": [5]},
+                    {"restaurant_menu_section_id": 1, "restaurant_menu_item_ids": []},
+                    {"restaurant_menu_section_id": 4, "restaurant_menu_item_ids": [1, 9, 8]}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            flatten_menu_layout(&reorder_restaurant_menu_dto),
+            (
+                vec![7, 3, 5, 1, 9, 8],
+                vec![0, 0, 2, 4, 4, 4],
+                vec![0, 1, 0, 0, 1, 2]
+            )
+        );
+    }
+
+    #[test]
+    fn items_without_a_section_are_optional() {
+        let reorder_restaurant_menu_dto: ReorderRestaurantMenu =
+            serde_json::from_str(r#"{"sections": []}"#).unwrap();
+
+        assert_eq!(
+            flatten_menu_layout(&reorder_restaurant_menu_dto),
+            (vec![], vec![], vec![])
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::modules::restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem;
+
+#[derive(Deserialize)]
+pub struct CreateRestaurantMenuSection {
+    pub name: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantMenuSection {
+    pub name: String,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantMenuSection {
+    pub restaurant_menu_section_id: i64,
+    pub restaurant_menu_id: i32,
+    pub name: String,
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantMenuSectionWithItems {
+    #[serde(flatten)]
+    pub section: RestaurantMenuSection,
+    pub items: Vec<RestaurantMenuItem>,
+}
+
+#[derive(Deserialize)]
+pub struct RestaurantMenuSectionOrder {
+    pub restaurant_menu_section_id: i32,
+    pub restaurant_menu_item_ids: Vec<i32>,
+}
+
+// The whole layout of a menu at once: items may move between sections.
+#[derive(Deserialize)]
+pub struct ReorderRestaurantMenu {
+    // Items shown before the first section.
+    #[serde(default)]
+    pub restaurant_menu_item_ids: Vec<i32>,
+    pub sections: Vec<RestaurantMenuSectionOrder>,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantMenuLayout {
+    pub restaurant_menu_id: i32,
+    pub items: Vec<RestaurantMenuItem>,
+    pub sections: Vec<RestaurantMenuSectionWithItems>,
+}
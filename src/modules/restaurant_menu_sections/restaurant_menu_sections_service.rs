@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    common::money::Money,
+    modules::restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+};
+
+use super::restaurant_menu_sections_dto::{
+    RestaurantMenuLayout, RestaurantMenuSection, RestaurantMenuSectionWithItems,
+};
+
+pub async fn find_restaurant_menu_sections(
+    db: &Pool<Postgres>,
+    restaurant_menu_ids: &[i32],
+) -> Result<Vec<RestaurantMenuSection>, sqlx::Error> {
+    sqlx::query_as!(
+        RestaurantMenuSection,
+        "SELECT restaurant_menu_section_id,restaurant_menu_id,name,position,created_at
+        FROM restaurant_menu_sections
+        WHERE restaurant_menu_id = ANY($1)
+        ORDER BY restaurant_menu_id ASC, position ASC, restaurant_menu_section_id ASC",
+        restaurant_menu_ids
+    )
+    .fetch_all(db)
+    .await
+}
+
+// Splits items already ordered by position into the ones without a section
+// and one group per section, sections keeping their own order.
+pub fn group_menu_items(
+    items: Vec<RestaurantMenuItem>,
+    sections: Vec<RestaurantMenuSection>,
+) -> (Vec<RestaurantMenuItem>, Vec<RestaurantMenuSectionWithItems>) {
+    let mut items_by_section: HashMap<Option<i32>, Vec<RestaurantMenuItem>> = HashMap::new();
+    for item in items {
+        items_by_section
+            .entry(item.restaurant_menu_section_id)
+            .or_default()
+            .push(item);
+    }
+
+    let sections = sections
+        .into_iter()
+        .map(|section| RestaurantMenuSectionWithItems {
+            items: items_by_section
+                .remove(&Some(section.restaurant_menu_section_id as i32))
+                .unwrap_or_default(),
+            section,
+        })
+        .collect();
+
+    (items_by_section.remove(&None).unwrap_or_default(), sections)
+}
+
+// Owner view of a menu: base prices, every item whatever the overrides.
+pub async fn find_restaurant_menu_layout(
+    db: &Pool<Postgres>,
+    restaurant_menu_id: i32,
+) -> Result<RestaurantMenuLayout, sqlx::Error> {
+    let items = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"SELECT restaurant_menu_item_id,name,menu_money(price_minor, restaurant_menu_id) AS "price!: Money",description,restaurant_menu_id,cover_image_uri,restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams,menu_item_availability(availability, sold_out_until, remaining_stock) AS "availability!",sold_out_until,daily_stock,remaining_stock,false AS "is_favorite!"
+        FROM restaurant_menu_items
+        WHERE restaurant_menu_id = $1 AND deleted_at IS NULL
+        ORDER BY position ASC, restaurant_menu_item_id ASC"#,
+        restaurant_menu_id
+    )
+    .fetch_all(db)
+    .await?;
+
+    let sections = find_restaurant_menu_sections(db, &[restaurant_menu_id]).await?;
+    let (items, sections) = group_menu_items(items, sections);
+
+    Ok(RestaurantMenuLayout {
+        restaurant_menu_id,
+        items,
+        sections,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::common::money::Money;
+
+    fn item(
+        restaurant_menu_item_id: i64,
+        restaurant_menu_section_id: Option<i32>,
+    ) -> RestaurantMenuItem {
+        RestaurantMenuItem {
+            restaurant_menu_item_id,
+            name: format!("Item {}", restaurant_menu_item_id),
+            price: Money {
+                amount_minor: 1000,
+                currency: String::from("EUR"),
+                exponent: 2,
+            },
+            description: String::new(),
+            restaurant_menu_id: 1,
+            cover_image_uri: String::new(),
+            restaurant_menu_section_id,
+            position: 0,
+            allergens: vec![],
+            diet_labels: vec![],
+            spicy_level: None,
+            calories: None,
+            protein_grams: None,
+            carbohydrate_grams: None,
+            fat_grams: None,
+            availability: String::from("available"),
+            sold_out_until: None,
+            daily_stock: None,
+            remaining_stock: None,
+            is_favorite: false,
+        }
+    }
+
+    fn section(restaurant_menu_section_id: i64) -> RestaurantMenuSection {
+        RestaurantMenuSection {
+            restaurant_menu_section_id,
+            restaurant_menu_id: 1,
+            name: format!("Section {}", restaurant_menu_section_id),
+            position: 0,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn item_ids(items: &[RestaurantMenuItem]) -> Vec<i64> {
+        items
+            .iter()
+            .map(|item| item.restaurant_menu_item_id)
+            .collect()
+    }
+
+    #[test]
+    fn items_are_grouped_under_their_section_in_order() {
+        let items = vec![
+            item(1, Some(20)),
+            item(2, None),
+            item(3, Some(10)),
+            item(4, Some(20)),
+            item(5, None),
+        ];
+
+        let (items, sections) =
+            group_menu_items(items, vec![section(20), section(10), section(30)]);
+
+        assert_eq!(item_ids(&items), vec![2, 5]);
+        let sections: Vec<(i64, Vec<i64>)> = sections
+            .iter()
+            .map(|section| {
+                (
+                    section.section.restaurant_menu_section_id,
+                    item_ids(&section.items),
+                )
+            })
+            .collect();
+        assert_eq!(
+            sections,
+            vec![(20, vec![1, 4]), (10, vec![3]), (30, vec![])]
+        );
+    }
+
+    #[test]
+    fn items_of_unknown_sections_are_left_out() {
+        let (items, sections) = group_menu_items(vec![item(1, Some(99)), item(2, None)], vec![]);
+
+        assert_eq!(item_ids(&items), vec![2]);
+        assert!(sections.is_empty());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod restaurant_menu_versions_controller;
+pub mod restaurant_menu_versions_dto;
+pub mod restaurant_menu_versions_service;
@@ -0,0 +1,650 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use crate::{
+    modules::{
+        restaurants::restaurants_service::{
+            check_restaurant_menu_access, find_menu_currency_exponent, refresh_menu_price_levels,
+        },
+        shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    Extension,
+};
+use sqlx::types::Json as JsonColumn;
+
+use super::{
+    restaurant_menu_versions_dto::{
+        RestaurantMenuVersion, RestaurantMenuVersionContent, RestaurantMenuVersionDiff,
+        RestaurantMenuVersionItem, RestaurantMenuVersionSummary, UpdateRestaurantMenuDraft,
+    },
+    restaurant_menu_versions_service::{
+        apply_menu_version_content, diff_menu_contents, find_live_menu_content, find_menu_currency,
+        find_menu_version,
+    },
+};
+
+// Newest first, the draft on top.
+pub async fn get_restaurant_menu_versions(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<Vec<RestaurantMenuVersionSummary>> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuVersionSummary,
+        r#"SELECT v.restaurant_menu_version_id,v.restaurant_menu_id,v.version_number,v.status,
+        v.content->>'name' AS "name!",jsonb_array_length(v.content->'items') AS "item_count!",
+        v.restored_from_version_id,v.created_by,v.published_by,
+        u.name || ' ' || u.last_name AS published_by_name,
+        v.published_at,v.created_at,v.updated_at
+        FROM restaurant_menu_versions v
+        LEFT JOIN users u ON u.user_id = v.published_by
+        WHERE v.restaurant_menu_id = $1
+        ORDER BY v.version_number DESC NULLS FIRST"#,
+        restaurant_menu_id
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match res {
+        Ok(versions) => AppResult::Result(StatusCode::OK, versions),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_menu_version(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_version_id)): Path<(i32, i32, i32)>,
+) -> AppResult<RestaurantMenuVersion> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    match find_menu_version(
+        &state.db,
+        restaurant_menu_id,
+        Some(restaurant_menu_version_id),
+    )
+    .await
+    {
+        Ok(Some(version)) => AppResult::Result(StatusCode::OK, version),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Version not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_menu_draft(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenuVersion> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    match find_menu_version(&state.db, restaurant_menu_id, None).await {
+        Ok(Some(draft)) => AppResult::Result(StatusCode::OK, draft),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Draft not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Starts from the menu as customers currently see it.
+pub async fn create_restaurant_menu_draft(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenuVersion> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res: Result<bool, sqlx::Error> = async {
+        let mut conn = state.db.acquire().await?;
+        let content = find_live_menu_content(&mut conn, restaurant_menu_id).await?;
+
+        let res = sqlx::query!(
+            "INSERT INTO restaurant_menu_versions (restaurant_menu_id,content,created_by)
+            VALUES ($1,$2,$3)
+            ON CONFLICT (restaurant_menu_id) WHERE status = 'draft' DO NOTHING",
+            restaurant_menu_id,
+            JsonColumn(content) as _,
+            current_user.user_id
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(res.rows_affected() > 0)
+    }
+    .await;
+
+    match res {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(
+                StatusCode::CONFLICT,
+                String::from("Menu already has a draft!"),
+            )
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    match find_menu_version(&state.db, restaurant_menu_id, None).await {
+        Ok(Some(draft)) => AppResult::Result(StatusCode::CREATED, draft),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Draft not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_restaurant_menu_draft(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+    Json(update_draft_dto): Json<UpdateRestaurantMenuDraft>,
+) -> AppResult<RestaurantMenuVersion> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let name = update_draft_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty"),
+        );
+    }
+
+    if update_draft_dto
+        .items
+        .iter()
+        .any(|item| !item.dietary_info.is_valid())
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "spicy_level must be between 0 and 3 and nutrition facts must not be negative",
+            ),
+        );
+    }
+
+    let exponent = match find_menu_currency_exponent(&state.db, restaurant_menu_id).await {
+        Ok(Some(exponent)) => exponent,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    // Removed items may be brought back, so trashed ones count too.
+    let known_ids = sqlx::query!(
+        r#"SELECT
+        ARRAY(SELECT restaurant_menu_item_id FROM restaurant_menu_items WHERE restaurant_menu_id = $1) AS "item_ids!",
+        ARRAY(SELECT restaurant_menu_section_id FROM restaurant_menu_sections WHERE restaurant_menu_id = $1) AS "section_ids!""#,
+        restaurant_menu_id
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    let (item_ids, section_ids): (HashSet<i32>, HashSet<i32>) = match known_ids {
+        Ok(known_ids) => (
+            known_ids.item_ids.into_iter().collect(),
+            known_ids.section_ids.into_iter().collect(),
+        ),
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let mut seen_item_ids = HashSet::new();
+    if update_draft_dto
+        .items
+        .iter()
+        .filter_map(|item| item.restaurant_menu_item_id)
+        .any(|item_id| !item_ids.contains(&item_id) || !seen_item_ids.insert(item_id))
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("restaurant_menu_item_id must be a distinct item of this menu"),
+        );
+    }
+
+    if update_draft_dto
+        .items
+        .iter()
+        .filter_map(|item| item.restaurant_menu_section_id)
+        .any(|section_id| !section_ids.contains(&section_id))
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("restaurant_menu_section_id must be a section of this menu"),
+        );
+    }
+
+    let mut positions: HashMap<Option<i32>, i32> = HashMap::new();
+    let mut items = Vec::with_capacity(update_draft_dto.items.len());
+    for item in &update_draft_dto.items {
+        let price_minor = match item.price.to_minor_units(exponent) {
+            Some(price_minor) if price_minor >= 0 => price_minor,
+            _ => {
+                return AppResult::Error(
+                    StatusCode::BAD_REQUEST,
+                    String::from(
+                        "price must not be negative nor have more decimals than its currency",
+                    ),
+                )
+            }
+        };
+
+        let position = positions
+            .entry(item.restaurant_menu_section_id)
+            .or_insert(0);
+        items.push(RestaurantMenuVersionItem {
+            restaurant_menu_item_id: item.restaurant_menu_item_id,
+            name: item.name.clone(),
+            description: item.description.clone(),
+            price_minor,
+            cover_image_uri: item.cover_image_uri.clone(),
+            restaurant_menu_section_id: item.restaurant_menu_section_id,
+            position: *position,
+            allergens: item.dietary_info.allergen_names(),
+            diet_labels: item.dietary_info.diet_label_names(),
+            spicy_level: item.dietary_info.spicy_level,
+            calories: item.dietary_info.calories,
+            protein_grams: item.dietary_info.protein_grams,
+            carbohydrate_grams: item.dietary_info.carbohydrate_grams,
+            fat_grams: item.dietary_info.fat_grams,
+        });
+        *position += 1;
+    }
+
+    let content = RestaurantMenuVersionContent {
+        name: name.to_string(),
+        items,
+    };
+
+    let res = sqlx::query!(
+        "UPDATE restaurant_menu_versions SET content = $1, updated_at = NOW()
+        WHERE restaurant_menu_id = $2 AND status = 'draft'",
+        JsonColumn(content) as _,
+        restaurant_menu_id
+    )
+    .execute(&state.db)
+    .await;
+
+    match res {
+        Ok(res) if res.rows_affected() == 0 => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Draft not found!"))
+        }
+        Ok(_) => {}
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    match find_menu_version(&state.db, restaurant_menu_id, None).await {
+        Ok(Some(draft)) => AppResult::Result(StatusCode::OK, draft),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Draft not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_menu_draft(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenuVersion> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let draft = match find_menu_version(&state.db, restaurant_menu_id, None).await {
+        Ok(Some(draft)) => draft,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Draft not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let res = sqlx::query!(
+        "DELETE FROM restaurant_menu_versions WHERE restaurant_menu_version_id = $1 AND status = 'draft'",
+        draft.summary.restaurant_menu_version_id
+    )
+    .execute(&state.db)
+    .await;
+
+    match res {
+        Ok(res) if res.rows_affected() == 0 => {
+            AppResult::Error(StatusCode::NOT_FOUND, String::from("Draft not found!"))
+        }
+        Ok(_) => AppResult::Result(StatusCode::OK, draft),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_menu_draft_diff(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenuVersionDiff> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res: Result<Option<RestaurantMenuVersionDiff>, sqlx::Error> = async {
+        let draft = sqlx::query_scalar!(
+            r#"SELECT content AS "content!: JsonColumn<RestaurantMenuVersionContent>"
+            FROM restaurant_menu_versions WHERE restaurant_menu_id = $1 AND status = 'draft'"#,
+            restaurant_menu_id
+        )
+        .fetch_optional(&state.db)
+        .await?;
+
+        let Some(JsonColumn(draft)) = draft else {
+            return Ok(None);
+        };
+
+        let mut conn = state.db.acquire().await?;
+        let published = find_live_menu_content(&mut conn, restaurant_menu_id).await?;
+        let currency = find_menu_currency(&state.db, restaurant_menu_id).await?;
+
+        Ok(Some(diff_menu_contents(published, draft, &currency)))
+    }
+    .await;
+
+    match res {
+        Ok(Some(diff)) => AppResult::Result(StatusCode::OK, diff),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Draft not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// The draft replaces the live menu in one transaction and becomes the
+// published version, the previous one is archived.
+pub async fn publish_restaurant_menu_draft(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantMenuVersion> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res: Result<Option<i32>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        // Publishes and rollbacks of the same menu run one after the other.
+        sqlx::query!(
+            "SELECT restaurant_menu_id FROM restaurant_menus WHERE restaurant_menu_id = $1 FOR UPDATE",
+            restaurant_menu_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let draft = sqlx::query!(
+            r#"SELECT restaurant_menu_version_id, content AS "content!: JsonColumn<RestaurantMenuVersionContent>"
+            FROM restaurant_menu_versions WHERE restaurant_menu_id = $1 AND status = 'draft'"#,
+            restaurant_menu_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(draft) = draft else {
+            return Ok(None);
+        };
+
+        let applied = apply_menu_version_content(&mut tx, restaurant_menu_id, &draft.content).await?;
+
+        sqlx::query!(
+            "UPDATE restaurant_menu_versions SET status = 'archived', updated_at = NOW()
+            WHERE restaurant_menu_id = $1 AND status = 'published'",
+            restaurant_menu_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE restaurant_menu_versions
+            SET status = 'published', content = $1, published_by = $2, published_at = NOW(), updated_at = NOW(),
+            version_number = (SELECT COALESCE(MAX(version_number)+1,1) FROM restaurant_menu_versions WHERE restaurant_menu_id = $3)
+            WHERE restaurant_menu_version_id = $4",
+            JsonColumn(applied) as _,
+            current_user.user_id,
+            restaurant_menu_id,
+            draft.restaurant_menu_version_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(draft.restaurant_menu_version_id))
+    }
+    .await;
+
+    let restaurant_menu_version_id = match res {
+        Ok(Some(restaurant_menu_version_id)) => restaurant_menu_version_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Draft not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+
+    match find_menu_version(
+        &state.db,
+        restaurant_menu_id,
+        Some(restaurant_menu_version_id),
+    )
+    .await
+    {
+        Ok(Some(version)) => AppResult::Result(StatusCode::OK, version),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Version not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Publishes an archived version again as a new version, the draft if any is
+// left untouched.
+pub async fn rollback_restaurant_menu_version(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_version_id)): Path<(i32, i32, i32)>,
+) -> AppResult<RestaurantMenuVersion> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let status = sqlx::query_scalar!(
+        "SELECT status FROM restaurant_menu_versions
+        WHERE restaurant_menu_version_id = $1 AND restaurant_menu_id = $2 AND status <> 'draft'",
+        restaurant_menu_version_id,
+        restaurant_menu_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match status {
+        Ok(Some(status)) if status == "published" => {
+            return AppResult::Error(
+                StatusCode::CONFLICT,
+                String::from("Version is already published!"),
+            )
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Version not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let res: Result<Option<i32>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        sqlx::query!(
+            "SELECT restaurant_menu_id FROM restaurant_menus WHERE restaurant_menu_id = $1 FOR UPDATE",
+            restaurant_menu_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let content = sqlx::query_scalar!(
+            r#"SELECT content AS "content!: JsonColumn<RestaurantMenuVersionContent>"
+            FROM restaurant_menu_versions
+            WHERE restaurant_menu_version_id = $1 AND status = 'archived'"#,
+            restaurant_menu_version_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(content) = content else {
+            return Ok(None);
+        };
+
+        let applied = apply_menu_version_content(&mut tx, restaurant_menu_id, &content).await?;
+
+        sqlx::query!(
+            "UPDATE restaurant_menu_versions SET status = 'archived', updated_at = NOW()
+            WHERE restaurant_menu_id = $1 AND status = 'published'",
+            restaurant_menu_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let restored_version_id = sqlx::query_scalar!(
+            "INSERT INTO restaurant_menu_versions (restaurant_menu_id,version_number,status,content,restored_from_version_id,created_by,published_by,published_at)
+            SELECT $1, COALESCE(MAX(version_number)+1,1), 'published', $2, $3, $4, $4, NOW()
+            FROM restaurant_menu_versions WHERE restaurant_menu_id = $1
+            RETURNING restaurant_menu_version_id",
+            restaurant_menu_id,
+            JsonColumn(applied) as _,
+            restaurant_menu_version_id,
+            current_user.user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(restored_version_id))
+    }
+    .await;
+
+    let restored_version_id = match res {
+        Ok(Some(restored_version_id)) => restored_version_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Version not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+
+    match find_menu_version(&state.db, restaurant_menu_id, Some(restored_version_id)).await {
+        Ok(Some(version)) => AppResult::Result(StatusCode::OK, version),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Version not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
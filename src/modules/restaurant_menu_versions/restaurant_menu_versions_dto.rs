@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::{
+    common::money::{Money, MoneyInput},
+    modules::restaurant_menu_items::restaurant_menu_items_dto::UpdateRestaurantMenuItemDietaryInfo,
+};
+
+// What a version holds: the menu name and its items. Availability, stock and
+// modifiers stay on the live items and are not versioned.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RestaurantMenuVersionContent {
+    pub name: String,
+    pub items: Vec<RestaurantMenuVersionItem>,
+}
+
+// Items added in a draft have no id until it is published.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RestaurantMenuVersionItem {
+    pub restaurant_menu_item_id: Option<i32>,
+    pub name: String,
+    pub description: String,
+    pub price_minor: i64,
+    pub cover_image_uri: String,
+    pub restaurant_menu_section_id: Option<i32>,
+    pub position: i32,
+    pub allergens: Vec<String>,
+    pub diet_labels: Vec<String>,
+    pub spicy_level: Option<i16>,
+    pub calories: Option<i32>,
+    pub protein_grams: Option<f64>,
+    pub carbohydrate_grams: Option<f64>,
+    pub fat_grams: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantMenuVersionItemWithPrice {
+    #[serde(flatten)]
+    pub item: RestaurantMenuVersionItem,
+    pub price: Money,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantMenuVersionSummary {
+    pub restaurant_menu_version_id: i32,
+    pub restaurant_menu_id: i32,
+    // Unset while the version is a draft.
+    pub version_number: Option<i32>,
+    // draft, published or archived.
+    pub status: String,
+    pub name: String,
+    pub item_count: i32,
+    // Set when the version was published again by a rollback.
+    pub restored_from_version_id: Option<i32>,
+    pub created_by: Option<i32>,
+    pub published_by: Option<i32>,
+    pub published_by_name: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantMenuVersion {
+    #[serde(flatten)]
+    pub summary: RestaurantMenuVersionSummary,
+    pub items: Vec<RestaurantMenuVersionItemWithPrice>,
+}
+
+// Replaces the whole draft. Items keep the order they are sent in, within
+// their section.
+#[derive(Deserialize)]
+pub struct UpdateRestaurantMenuDraft {
+    pub name: String,
+    pub items: Vec<RestaurantMenuDraftItem>,
+}
+
+// Leave out restaurant_menu_item_id to add an item, existing items left out
+// of the draft are removed on publish.
+#[derive(Deserialize)]
+pub struct RestaurantMenuDraftItem {
+    pub restaurant_menu_item_id: Option<i32>,
+    pub name: String,
+    pub description: String,
+    // In the currency of the menu's restaurant.
+    pub price: MoneyInput,
+    pub cover_image_uri: String,
+    pub restaurant_menu_section_id: Option<i32>,
+    #[serde(flatten)]
+    pub dietary_info: UpdateRestaurantMenuItemDietaryInfo,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantMenuItemChange {
+    pub restaurant_menu_item_id: i32,
+    pub fields: Vec<&'static str>,
+    pub published: RestaurantMenuVersionItemWithPrice,
+    pub draft: RestaurantMenuVersionItemWithPrice,
+}
+
+// Draft compared to what customers currently see.
+#[derive(Serialize)]
+pub struct RestaurantMenuVersionDiff {
+    pub published_name: String,
+    pub draft_name: String,
+    pub added: Vec<RestaurantMenuVersionItemWithPrice>,
+    pub removed: Vec<RestaurantMenuVersionItemWithPrice>,
+    pub changed: Vec<RestaurantMenuItemChange>,
+}
@@ -0,0 +1,445 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{types::Json, PgConnection, Pool, Postgres};
+
+use crate::common::money::Money;
+
+use super::restaurant_menu_versions_dto::{
+    RestaurantMenuItemChange, RestaurantMenuVersion, RestaurantMenuVersionContent,
+    RestaurantMenuVersionDiff, RestaurantMenuVersionItem, RestaurantMenuVersionItemWithPrice,
+    RestaurantMenuVersionSummary,
+};
+
+// A zero amount in the menu currency, snapshots only keep minor units.
+pub async fn find_menu_currency(
+    db: &Pool<Postgres>,
+    restaurant_menu_id: i32,
+) -> Result<Money, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT menu_money(0, $1) AS "money!: Money""#,
+        restaurant_menu_id
+    )
+    .fetch_one(db)
+    .await
+}
+
+pub fn with_price(
+    item: RestaurantMenuVersionItem,
+    currency: &Money,
+) -> RestaurantMenuVersionItemWithPrice {
+    RestaurantMenuVersionItemWithPrice {
+        price: Money {
+            amount_minor: item.price_minor,
+            ..currency.clone()
+        },
+        item,
+    }
+}
+
+// The draft when restaurant_menu_version_id is None.
+pub async fn find_menu_version(
+    db: &Pool<Postgres>,
+    restaurant_menu_id: i32,
+    restaurant_menu_version_id: Option<i32>,
+) -> Result<Option<RestaurantMenuVersion>, sqlx::Error> {
+    let summary = sqlx::query_as!(
+        RestaurantMenuVersionSummary,
+        r#"SELECT v.restaurant_menu_version_id,v.restaurant_menu_id,v.version_number,v.status,
+        v.content->>'name' AS "name!",jsonb_array_length(v.content->'items') AS "item_count!",
+        v.restored_from_version_id,v.created_by,v.published_by,
+        u.name || ' ' || u.last_name AS published_by_name,
+        v.published_at,v.created_at,v.updated_at
+        FROM restaurant_menu_versions v
+        LEFT JOIN users u ON u.user_id = v.published_by
+        WHERE v.restaurant_menu_id = $1
+        AND (v.restaurant_menu_version_id = $2 OR ($2::INTEGER IS NULL AND v.status = 'draft'))"#,
+        restaurant_menu_id,
+        restaurant_menu_version_id
+    )
+    .fetch_optional(db)
+    .await?;
+
+    let Some(summary) = summary else {
+        return Ok(None);
+    };
+
+    let content = sqlx::query_scalar!(
+        r#"SELECT content AS "content!: Json<RestaurantMenuVersionContent>"
+        FROM restaurant_menu_versions WHERE restaurant_menu_version_id = $1"#,
+        summary.restaurant_menu_version_id
+    )
+    .fetch_one(db)
+    .await?;
+
+    let currency = find_menu_currency(db, restaurant_menu_id).await?;
+
+    Ok(Some(RestaurantMenuVersion {
+        summary,
+        items: content
+            .0
+            .items
+            .into_iter()
+            .map(|item| with_price(item, &currency))
+            .collect(),
+    }))
+}
+
+// What customers see right now, whatever was published or edited directly.
+pub async fn find_live_menu_content(
+    conn: &mut PgConnection,
+    restaurant_menu_id: i32,
+) -> Result<RestaurantMenuVersionContent, sqlx::Error> {
+    let name = sqlx::query_scalar!(
+        "SELECT name FROM restaurant_menus WHERE restaurant_menu_id = $1",
+        restaurant_menu_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let items = sqlx::query_as!(
+        RestaurantMenuVersionItem,
+        r#"SELECT restaurant_menu_item_id AS "restaurant_menu_item_id?",name,description,price_minor,cover_image_uri,restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams
+        FROM restaurant_menu_items
+        WHERE restaurant_menu_id = $1 AND deleted_at IS NULL
+        ORDER BY position ASC, restaurant_menu_item_id ASC"#,
+        restaurant_menu_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(RestaurantMenuVersionContent { name, items })
+}
+
+// Makes the live menu match the content. Items that were removed since, even
+// purged from the trash, come back. Returns the content with the ids of the
+// items it had to create and without the sections deleted in the meantime.
+pub async fn apply_menu_version_content(
+    conn: &mut PgConnection,
+    restaurant_menu_id: i32,
+    content: &RestaurantMenuVersionContent,
+) -> Result<RestaurantMenuVersionContent, sqlx::Error> {
+    let section_ids: HashSet<i32> = sqlx::query_scalar!(
+        "SELECT restaurant_menu_section_id FROM restaurant_menu_sections WHERE restaurant_menu_id = $1",
+        restaurant_menu_id
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut applied = content.clone();
+    for item in applied.items.iter_mut() {
+        item.restaurant_menu_section_id = item
+            .restaurant_menu_section_id
+            .filter(|section_id| section_ids.contains(section_id));
+
+        let updated_item_id = match item.restaurant_menu_item_id {
+            Some(restaurant_menu_item_id) => {
+                sqlx::query_scalar!(
+                    "UPDATE restaurant_menu_items
+                    SET name = $1, description = $2, price_minor = $3, cover_image_uri = $4,
+                    restaurant_menu_section_id = $5, position = $6, allergens = $7, diet_labels = $8,
+                    spicy_level = $9, calories = $10, protein_grams = $11, carbohydrate_grams = $12,
+                    fat_grams = $13, deleted_at = NULL
+                    WHERE restaurant_menu_item_id = $14 AND restaurant_menu_id = $15
+                    RETURNING restaurant_menu_item_id",
+                    item.name,
+                    item.description,
+                    item.price_minor,
+                    item.cover_image_uri,
+                    item.restaurant_menu_section_id,
+                    item.position,
+                    &item.allergens,
+                    &item.diet_labels,
+                    item.spicy_level,
+                    item.calories,
+                    item.protein_grams,
+                    item.carbohydrate_grams,
+                    item.fat_grams,
+                    restaurant_menu_item_id,
+                    restaurant_menu_id
+                )
+                .fetch_optional(&mut *conn)
+                .await?
+            }
+            None => None,
+        };
+
+        let restaurant_menu_item_id = match updated_item_id {
+            Some(restaurant_menu_item_id) => restaurant_menu_item_id,
+            None => {
+                sqlx::query_scalar!(
+                    "INSERT INTO restaurant_menu_items (name,description,price_minor,cover_image_uri,restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams,restaurant_menu_id)
+                    VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14)
+                    RETURNING restaurant_menu_item_id",
+                    item.name,
+                    item.description,
+                    item.price_minor,
+                    item.cover_image_uri,
+                    item.restaurant_menu_section_id,
+                    item.position,
+                    &item.allergens,
+                    &item.diet_labels,
+                    item.spicy_level,
+                    item.calories,
+                    item.protein_grams,
+                    item.carbohydrate_grams,
+                    item.fat_grams,
+                    restaurant_menu_id
+                )
+                .fetch_one(&mut *conn)
+                .await?
+            }
+        };
+        item.restaurant_menu_item_id = Some(restaurant_menu_item_id);
+    }
+
+    let kept_item_ids: Vec<i32> = applied
+        .items
+        .iter()
+        .filter_map(|item| item.restaurant_menu_item_id)
+        .collect();
+
+    sqlx::query!(
+        "UPDATE restaurant_menu_items SET deleted_at = NOW()
+        WHERE restaurant_menu_id = $1 AND deleted_at IS NULL
+        AND restaurant_menu_item_id <> ALL($2)",
+        restaurant_menu_id,
+        &kept_item_ids
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE restaurant_menus SET name = $1 WHERE restaurant_menu_id = $2",
+        applied.name,
+        restaurant_menu_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(applied)
+}
+
+fn changed_fields(
+    published: &RestaurantMenuVersionItem,
+    draft: &RestaurantMenuVersionItem,
+) -> Vec<&'static str> {
+    [
+        ("name", published.name != draft.name),
+        ("description", published.description != draft.description),
+        ("price", published.price_minor != draft.price_minor),
+        (
+            "cover_image_uri",
+            published.cover_image_uri != draft.cover_image_uri,
+        ),
+        (
+            "restaurant_menu_section_id",
+            published.restaurant_menu_section_id != draft.restaurant_menu_section_id,
+        ),
+        ("position", published.position != draft.position),
+        ("allergens", published.allergens != draft.allergens),
+        ("diet_labels", published.diet_labels != draft.diet_labels),
+        ("spicy_level", published.spicy_level != draft.spicy_level),
+        ("calories", published.calories != draft.calories),
+        (
+            "protein_grams",
+            published.protein_grams != draft.protein_grams,
+        ),
+        (
+            "carbohydrate_grams",
+            published.carbohydrate_grams != draft.carbohydrate_grams,
+        ),
+        ("fat_grams", published.fat_grams != draft.fat_grams),
+    ]
+    .into_iter()
+    .filter(|(_, is_changed)| *is_changed)
+    .map(|(field, _)| field)
+    .collect()
+}
+
+// Items are matched on their id, draft items without one are added.
+pub fn diff_menu_contents(
+    published: RestaurantMenuVersionContent,
+    draft: RestaurantMenuVersionContent,
+    currency: &Money,
+) -> RestaurantMenuVersionDiff {
+    let mut published_items: HashMap<i32, RestaurantMenuVersionItem> = published
+        .items
+        .into_iter()
+        .filter_map(|item| Some((item.restaurant_menu_item_id?, item)))
+        .collect();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for draft_item in draft.items {
+        let published_item =
+            draft_item
+                .restaurant_menu_item_id
+                .and_then(|restaurant_menu_item_id| {
+                    let published_item = published_items.remove(&restaurant_menu_item_id)?;
+                    Some((restaurant_menu_item_id, published_item))
+                });
+
+        match published_item {
+            Some((restaurant_menu_item_id, published_item)) => {
+                let fields = changed_fields(&published_item, &draft_item);
+                if !fields.is_empty() {
+                    changed.push(RestaurantMenuItemChange {
+                        restaurant_menu_item_id,
+                        fields,
+                        published: with_price(published_item, currency),
+                        draft: with_price(draft_item, currency),
+                    });
+                }
+            }
+            None => added.push(with_price(draft_item, currency)),
+        }
+    }
+
+    let mut removed: Vec<RestaurantMenuVersionItem> = published_items.into_values().collect();
+    removed.sort_by_key(|item| (item.position, item.restaurant_menu_item_id));
+
+    RestaurantMenuVersionDiff {
+        published_name: published.name,
+        draft_name: draft.name,
+        added,
+        removed: removed
+            .into_iter()
+            .map(|item| with_price(item, currency))
+            .collect(),
+        changed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn euros() -> Money {
+        Money {
+            amount_minor: 0,
+            currency: String::from("EUR"),
+            exponent: 2,
+        }
+    }
+
+    fn item(
+        restaurant_menu_item_id: Option<i32>,
+        name: &str,
+        position: i32,
+    ) -> RestaurantMenuVersionItem {
+        RestaurantMenuVersionItem {
+            restaurant_menu_item_id,
+            name: String::from(name),
+            description: String::new(),
+            price_minor: 1000,
+            cover_image_uri: String::new(),
+            restaurant_menu_section_id: None,
+            position,
+            allergens: vec![],
+            diet_labels: vec![],
+            spicy_level: None,
+            calories: None,
+            protein_grams: None,
+            carbohydrate_grams: None,
+            fat_grams: None,
+        }
+    }
+
+    fn content(name: &str, items: Vec<RestaurantMenuVersionItem>) -> RestaurantMenuVersionContent {
+        RestaurantMenuVersionContent {
+            name: String::from(name),
+            items,
+        }
+    }
+
+    fn names(items: &[RestaurantMenuVersionItemWithPrice]) -> Vec<&str> {
+        items.iter().map(|item| item.item.name.as_str()).collect()
+    }
+
+    #[test]
+    fn items_are_matched_on_their_id() {
+        let published = content(
+            "Lunch",
+            vec![
+                item(Some(1), "Soup", 0),
+                item(Some(2), "Salad", 1),
+                item(Some(3), "Cake", 2),
+            ],
+        );
+        let mut pricier_salad = item(Some(2), "Salad", 0);
+        pricier_salad.price_minor = 1250;
+        let draft = content(
+            "Lunch menu",
+            vec![
+                pricier_salad,
+                item(Some(1), "Soup", 1),
+                item(None, "Tart", 2),
+            ],
+        );
+
+        let diff = diff_menu_contents(published, draft, &euros());
+
+        assert_eq!(diff.published_name, "Lunch");
+        assert_eq!(diff.draft_name, "Lunch menu");
+        assert_eq!(names(&diff.added), vec!["Tart"]);
+        assert_eq!(names(&diff.removed), vec!["Cake"]);
+        let changes: Vec<(i32, Vec<&str>)> = diff
+            .changed
+            .iter()
+            .map(|change| (change.restaurant_menu_item_id, change.fields.clone()))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![(2, vec!["price", "position"]), (1, vec!["position"])]
+        );
+        assert_eq!(diff.changed[0].published.price.amount_minor, 1000);
+        assert_eq!(diff.changed[0].draft.price.amount_minor, 1250);
+        assert_eq!(diff.changed[0].draft.price.currency, "EUR");
+    }
+
+    #[test]
+    fn identical_contents_have_no_changes() {
+        let items = vec![item(Some(1), "Soup", 0), item(Some(2), "Salad", 1)];
+
+        let diff = diff_menu_contents(
+            content("Lunch", items.clone()),
+            content("Lunch", items),
+            &euros(),
+        );
+
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn removed_items_are_listed_in_menu_order() {
+        let published = content(
+            "Lunch",
+            vec![
+                item(Some(7), "Cake", 2),
+                item(Some(4), "Soup", 0),
+                item(Some(9), "Salad", 1),
+            ],
+        );
+
+        let diff = diff_menu_contents(published, content("Lunch", vec![]), &euros());
+
+        assert_eq!(names(&diff.removed), vec!["Soup", "Salad", "Cake"]);
+    }
+
+    #[test]
+    fn dietary_changes_are_reported_by_field() {
+        let published = item(Some(1), "Soup", 0);
+        let mut draft = published.clone();
+        draft.allergens = vec![String::from("celery")];
+        draft.calories = Some(180);
+
+        assert_eq!(
+            changed_fields(&published, &draft),
+            vec!["allergens", "calories"]
+        );
+    }
+}
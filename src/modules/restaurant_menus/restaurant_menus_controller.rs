@@ -1,26 +1,72 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
+    common::{
+        etag::{etag_for, is_not_modified},
+        locale::RequestLocales,
+        money::Money,
+    },
     modules::{
-        files::files_controller::delete_file,
+        restaurant_menu_items::{
+            restaurant_menu_items_dto::RestaurantMenuItem,
+            restaurant_menu_items_service::copy_menu_items,
+        },
+        restaurant_menu_sections::{
+            restaurant_menu_sections_dto::RestaurantMenuSection,
+            restaurant_menu_sections_service::{find_restaurant_menu_sections, group_menu_items},
+        },
+        restaurants::restaurants_service::{
+            check_restaurant_access, check_restaurant_menu_access,
+            find_sibling_restaurant_currency_match, refresh_menu_price_levels, resolve_restaurant,
+            RestaurantLookup,
+        },
         shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
+        translations::translations_service::{translate_menu_items, translate_menus},
         users::users_dto::User,
     },
     AppState,
 };
 use axum::{
     extract::{Json, Path, Query, State},
-    http::StatusCode,
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE, ETAG},
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
     Extension,
 };
 
-use super::restaurant_menus_dto::{CreateRestaurantMenu, RestaurantMenu};
+use super::restaurant_menus_dto::{
+    CloneRestaurantMenu, CreateRestaurantMenu, MenuAvailabilityInput, RestaurantFullMenu,
+    RestaurantMenu, RestaurantMenuWithItems,
+};
 
 pub async fn get_restaurant_menus_pub(
     State(state): State<Arc<AppState>>,
     pagination_input: Query<PaginationInput>,
-    Path(restaurant_id): Path<i32>,
+    availability_input: Query<MenuAvailabilityInput>,
+    Path(restaurant_id): Path<String>,
+    current_user: Option<Extension<Arc<User>>>,
+    locales: RequestLocales,
 ) -> AppResult<PaginatedList<RestaurantMenu>> {
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
     let PaginationInput { page, page_size } = pagination_input.0;
     let offset = (page.saturating_sub(1)) * page_size;
     let res = sqlx::query_as!(
@@ -29,23 +75,51 @@ pub async fn get_restaurant_menus_pub(
         restaurant_menu_id, 
         name, 
         is_active,
-        restaurant_id       
+        restaurant_id,
+        restaurant_chain_id
         FROM restaurant_menus
-        where restaurant_id = $1 
+        where (
+            restaurant_id = $1
+            or restaurant_chain_id = (SELECT restaurant_chain_id FROM restaurants WHERE restaurant_id = $1)
+        )
         and is_active = true  
+        and deleted_at IS NULL
+        and EXISTS (SELECT 1 FROM restaurants WHERE restaurant_id = $1 AND deleted_at IS NULL)
+        and menu_available_at(
+            restaurant_menu_id,
+            COALESCE($4, NOW()) AT TIME ZONE (SELECT timezone FROM restaurants WHERE restaurant_id = $1)
+        )
         ORDER BY
 	    restaurant_menu_id asc
         LIMIT $2 OFFSET $3",
         restaurant_id,
         page_size,
-        offset
+        offset,
+        availability_input.at
     )
     .fetch_all(&state.db)
     .await;
+    let res = match res {
+        Ok(mut menus) => translate_menus(&state.db, &locales, restaurant_id, &mut menus)
+            .await
+            .map(|_| menus),
+        Err(err) => Err(err),
+    };
 
     let count: i64 = match sqlx::query_scalar!(
-        "SELECT COUNT (restaurant_id) FROM restaurant_menus where restaurant_id = $1 and is_active = true",
-        restaurant_id
+        "SELECT COUNT (restaurant_menu_id) FROM restaurant_menus
+        where (
+            restaurant_id = $1
+            or restaurant_chain_id = (SELECT restaurant_chain_id FROM restaurants WHERE restaurant_id = $1)
+        )
+        and is_active = true and deleted_at IS NULL
+        and EXISTS (SELECT 1 FROM restaurants WHERE restaurant_id = $1 AND deleted_at IS NULL)
+        and menu_available_at(
+            restaurant_menu_id,
+            COALESCE($2, NOW()) AT TIME ZONE (SELECT timezone FROM restaurants WHERE restaurant_id = $1)
+        )",
+        restaurant_id,
+        availability_input.at
     )
     .fetch_one(&state.db)
     .await
@@ -81,9 +155,11 @@ pub async fn get_restaurant_menus(
         restaurant_menu_id, 
         name, 
         is_active,
-        restaurant_id       
+        restaurant_id,
+        restaurant_chain_id
         FROM restaurant_menus
         where restaurant_id = $1        
+        and deleted_at IS NULL
         ORDER BY
 	    restaurant_menu_id asc
         LIMIT $2 OFFSET $3",
@@ -95,7 +171,7 @@ pub async fn get_restaurant_menus(
     .await;
 
     let count: i64 = match sqlx::query_scalar!(
-        "SELECT COUNT (restaurant_id) FROM restaurant_menus where restaurant_id = $1",
+        "SELECT COUNT (restaurant_id) FROM restaurant_menus where restaurant_id = $1 and deleted_at IS NULL",
         restaurant_id
     )
     .fetch_one(&state.db)
@@ -122,12 +198,17 @@ pub async fn get_restaurant_menus(
 
 pub async fn create_restaurant_menu(
     State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
     Path(restaurant_id): Path<i32>,
     Json(create_restaurant_menu_dto): Json<CreateRestaurantMenu>,
 ) -> AppResult<RestaurantMenu> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
     let res = sqlx::query_as!(
         RestaurantMenu,
-        "INSERT INTO restaurant_menus (name,is_active,restaurant_id) VALUES ($1,$2,$3) RETURNING restaurant_menu_id,name,is_active,restaurant_id",
+        "INSERT INTO restaurant_menus (name,is_active,restaurant_id) VALUES ($1,$2,$3) RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
         create_restaurant_menu_dto.name,
         create_restaurant_menu_dto.is_active,
         restaurant_id,
@@ -146,18 +227,30 @@ pub async fn create_restaurant_menu(
 
 pub async fn activate_restaurant_menu(
     State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
     Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
 ) -> AppResult<RestaurantMenu> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
     let res = sqlx::query_as!(
         RestaurantMenu,
-        "update restaurant_menus SET is_active = true where restaurant_menu_id = $1 RETURNING restaurant_menu_id,name,is_active,restaurant_id",
-        restaurant_menu_id
+        "update restaurant_menus SET is_active = true where restaurant_menu_id = $1 and restaurant_id = $2 and deleted_at IS NULL RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
+        restaurant_menu_id,
+        restaurant_id
     )
     .fetch_one(&state.db)
     .await;
 
     return match res {
-        Ok(restaurant) => AppResult::Result(StatusCode::OK, restaurant),
+        Ok(restaurant) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::OK, restaurant)
+        }
         Err(_) => AppResult::Error(
             StatusCode::INTERNAL_SERVER_ERROR,
             String::from("Something went wrong"),
@@ -167,18 +260,30 @@ pub async fn activate_restaurant_menu(
 
 pub async fn disactivate_restaurant_menu(
     State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
     Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
 ) -> AppResult<RestaurantMenu> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
     let res = sqlx::query_as!(
         RestaurantMenu,
-        "update restaurant_menus SET is_active = false where restaurant_menu_id = $1 RETURNING restaurant_menu_id,name,is_active,restaurant_id",
-        restaurant_menu_id
+        "update restaurant_menus SET is_active = false where restaurant_menu_id = $1 and restaurant_id = $2 and deleted_at IS NULL RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
+        restaurant_menu_id,
+        restaurant_id
     )
     .fetch_one(&state.db)
     .await;
 
     return match res {
-        Ok(restaurant) => AppResult::Result(StatusCode::OK, restaurant),
+        Ok(restaurant) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::OK, restaurant)
+        }
         Err(_) => AppResult::Error(
             StatusCode::INTERNAL_SERVER_ERROR,
             String::from("Something went wrong"),
@@ -191,30 +296,339 @@ pub async fn delete_restaurant_menu(
     Extension(current_user): Extension<Arc<User>>,
     Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
 ) -> AppResult<RestaurantMenu> {
-    let res = match current_user.role.as_str() {
-        "Admin" =>
-            sqlx::query_as!(
-                RestaurantMenu,
-                "DELETE FROM restaurant_menus Where restaurant_menu_id = $1 RETURNING restaurant_menu_id,name,is_active,restaurant_id",
-                restaurant_menu_id
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenu,
+        "UPDATE restaurant_menus SET deleted_at = NOW() Where restaurant_menu_id = $1 and restaurant_id = $2 and deleted_at IS NULL RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
+        restaurant_menu_id,
+        restaurant_id
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    return match res {
+        Ok(restaurant_menu) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::OK, restaurant_menu)
+        }
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    };
+}
+
+// Sections, items and their modifiers come along, schedules and versions do
+// not.
+pub async fn clone_restaurant_menu(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+    Json(clone_restaurant_menu_dto): Json<CloneRestaurantMenu>,
+) -> AppResult<RestaurantMenu> {
+    let name = clone_restaurant_menu_dto
+        .name
+        .as_deref()
+        .map(|name| name.trim().to_string());
+    if name.as_ref().is_some_and(|name| name.is_empty()) {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty"),
+        );
+    }
+
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let target_restaurant_id = clone_restaurant_menu_dto
+        .target_restaurant_id
+        .unwrap_or(restaurant_id);
+    match find_sibling_restaurant_currency_match(&state.db, restaurant_id, target_restaurant_id)
+        .await
+    {
+        Ok(Some(true)) => {}
+        Ok(Some(false)) => {
+            return AppResult::Error(
+                StatusCode::CONFLICT,
+                String::from("Target restaurant uses a different currency!"),
             )
-            .fetch_one(&state.db)
-            .await,
+        }
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let res: Result<Option<RestaurantMenu>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
 
-        _ => sqlx::query_as!(
+        let restaurant_menu = sqlx::query_as!(
             RestaurantMenu,
-            "DELETE FROM restaurant_menus Where restaurant_menu_id = $1 RETURNING restaurant_menu_id,name,is_active,restaurant_id",
+            "INSERT INTO restaurant_menus (name,is_active,restaurant_id)
+            SELECT COALESCE($1,name),false,$2 FROM restaurant_menus
+            WHERE restaurant_menu_id = $3 AND restaurant_id = $4 AND deleted_at IS NULL
+            RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
+            name,
+            target_restaurant_id,
             restaurant_menu_id,
+            restaurant_id
         )
-        .fetch_one(&state.db)
-        .await,
-    };
+        .fetch_optional(&mut *tx)
+        .await?;
 
-    return match res {
-        Ok(restaurant_menu) => AppResult::Result(StatusCode::OK, restaurant_menu),
+        let Some(restaurant_menu) = restaurant_menu else {
+            return Ok(None);
+        };
+
+        let section_ids = sqlx::query_scalar!(
+            "SELECT restaurant_menu_section_id FROM restaurant_menu_sections WHERE restaurant_menu_id = $1",
+            restaurant_menu_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut copied_section_ids = HashMap::new();
+        for section_id in section_ids {
+            let copied_section_id = sqlx::query_scalar!(
+                "INSERT INTO restaurant_menu_sections (restaurant_menu_id,name,position)
+                SELECT $1,name,position FROM restaurant_menu_sections WHERE restaurant_menu_section_id = $2
+                RETURNING restaurant_menu_section_id",
+                restaurant_menu.restaurant_menu_id as i32,
+                section_id
+            )
+            .fetch_one(&mut *tx)
+            .await?;
+            copied_section_ids.insert(section_id, copied_section_id);
+        }
+
+        let item_ids = sqlx::query_scalar!(
+            "SELECT restaurant_menu_item_id FROM restaurant_menu_items WHERE restaurant_menu_id = $1 AND deleted_at IS NULL",
+            restaurant_menu_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        copy_menu_items(
+            &mut tx,
+            &item_ids,
+            restaurant_menu.restaurant_menu_id as i32,
+            &copied_section_ids,
+            clone_restaurant_menu_dto.copy_images,
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(restaurant_menu))
+    }
+    .await;
+
+    match res {
+        Ok(Some(restaurant_menu)) => AppResult::Result(StatusCode::CREATED, restaurant_menu),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!")),
         Err(_) => AppResult::Error(
             StatusCode::INTERNAL_SERVER_ERROR,
             String::from("Something went wrong"),
         ),
+    }
+}
+
+// Whole public menu in one response: one query each for the menus, their
+// items and their sections, whatever the number of menus.
+pub async fn get_restaurant_full_menu(
+    State(state): State<Arc<AppState>>,
+    Path(restaurant_id): Path<String>,
+    availability_input: Query<MenuAvailabilityInput>,
+    headers: HeaderMap,
+    locales: RequestLocales,
+    current_user: Option<Extension<Arc<User>>>,
+) -> Response {
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::<()>::Error(
+                StatusCode::NOT_FOUND,
+                String::from("Restaurant not found!"),
+            )
+            .into_response()
+        }
+        Err(_) => {
+            return AppResult::<()>::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+            .into_response()
+        }
     };
+    let current_user_id = current_user.map(|Extension(user)| user.user_id);
+
+    let res: Result<RestaurantFullMenu, sqlx::Error> = async {
+        let mut menus = sqlx::query_as!(
+            RestaurantMenu,
+            "SELECT rm.restaurant_menu_id,rm.name,rm.is_active,rm.restaurant_id,rm.restaurant_chain_id
+            FROM restaurant_menus rm
+            JOIN restaurants r ON rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id
+            WHERE r.restaurant_id = $1 AND r.deleted_at IS NULL
+            AND rm.is_active = true AND rm.deleted_at IS NULL
+            AND menu_available_at(rm.restaurant_menu_id, COALESCE($2, NOW()) AT TIME ZONE r.timezone)
+            ORDER BY rm.restaurant_menu_id ASC",
+            restaurant_id,
+            availability_input.at
+        )
+        .fetch_all(&state.db)
+        .await?;
+
+        let restaurant_menu_ids: Vec<i32> = menus
+            .iter()
+            .map(|menu| menu.restaurant_menu_id as i32)
+            .collect();
+
+        let mut items = sqlx::query_as!(
+            RestaurantMenuItem,
+            r#"SELECT
+            rmi.restaurant_menu_item_id,
+            rmi.name,
+            menu_money(COALESCE(rmio.price_minor, rmi.price_minor), rmi.restaurant_menu_id) AS "price!: Money",
+            rmi.description,
+            rmi.cover_image_uri,
+            rmi.restaurant_menu_section_id,
+            rmi.position,
+            rmi.allergens,
+            rmi.diet_labels,
+            rmi.spicy_level,
+            rmi.calories,
+            rmi.protein_grams,
+            rmi.carbohydrate_grams,
+            rmi.fat_grams,
+            menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) AS "availability!",
+            rmi.sold_out_until,
+            rmi.daily_stock,
+            rmi.remaining_stock,
+            rmi.restaurant_menu_id,
+            EXISTS (
+                SELECT 1 FROM favorite_menu_items fmi
+                WHERE fmi.restaurant_menu_item_id = rmi.restaurant_menu_item_id
+                AND fmi.user_id = $3
+            ) AS "is_favorite!"
+            FROM restaurant_menu_items rmi
+            LEFT JOIN restaurant_menu_item_overrides rmio
+            ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id AND rmio.restaurant_id = $2
+            WHERE rmi.restaurant_menu_id = ANY($1)
+            AND rmi.deleted_at IS NULL
+            AND COALESCE(rmio.is_available, true)
+            AND menu_item_availability(rmi.availability, rmi.sold_out_until, rmi.remaining_stock) <> 'hidden'
+            ORDER BY rmi.restaurant_menu_id ASC, rmi.position ASC, rmi.restaurant_menu_item_id ASC"#,
+            &restaurant_menu_ids,
+            restaurant_id,
+            current_user_id
+        )
+        .fetch_all(&state.db)
+        .await?;
+
+        translate_menus(&state.db, &locales, restaurant_id, &mut menus).await?;
+        translate_menu_items(&state.db, &locales, restaurant_id, &mut items).await?;
+
+        let sections = find_restaurant_menu_sections(&state.db, &restaurant_menu_ids).await?;
+
+        let mut items_by_menu: HashMap<i64, Vec<RestaurantMenuItem>> = HashMap::new();
+        for item in items {
+            items_by_menu
+                .entry(item.restaurant_menu_id)
+                .or_default()
+                .push(item);
+        }
+
+        let mut sections_by_menu: HashMap<i32, Vec<RestaurantMenuSection>> = HashMap::new();
+        for section in sections {
+            sections_by_menu
+                .entry(section.restaurant_menu_id)
+                .or_default()
+                .push(section);
+        }
+
+        Ok(RestaurantFullMenu {
+            restaurant_id,
+            menus: menus
+                .into_iter()
+                .map(|menu| {
+                    let (items, sections) = group_menu_items(
+                        items_by_menu
+                            .remove(&menu.restaurant_menu_id)
+                            .unwrap_or_default(),
+                        sections_by_menu
+                            .remove(&(menu.restaurant_menu_id as i32))
+                            .unwrap_or_default(),
+                    );
+                    RestaurantMenuWithItems {
+                        menu,
+                        items,
+                        sections,
+                    }
+                })
+                .collect(),
+        })
+    }
+    .await;
+
+    let full_menu = match res {
+        Ok(full_menu) => full_menu,
+        Err(_) => {
+            return AppResult::<()>::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+            .into_response()
+        }
+    };
+
+    let body = match serde_json::to_vec(&full_menu) {
+        Ok(body) => body,
+        Err(_) => {
+            return AppResult::<()>::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+            .into_response()
+        }
+    };
+
+    // is_favorite depends on the caller, so only private caches may keep it.
+    let etag = etag_for(&body);
+    let cache_headers = [
+        (ETAG, etag.clone()),
+        (CACHE_CONTROL, String::from("private, no-cache")),
+    ];
+
+    if is_not_modified(&headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    (
+        StatusCode::OK,
+        cache_headers,
+        [(CONTENT_TYPE, String::from("application/json"))],
+        body,
+    )
+        .into_response()
 }
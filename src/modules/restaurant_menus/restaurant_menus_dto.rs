@@ -1,6 +1,12 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::modules::{
+    restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+    restaurant_menu_sections::restaurant_menu_sections_dto::RestaurantMenuSectionWithItems,
+};
+
 #[derive(Deserialize)]
 pub struct CreateRestaurantMenu {
     pub name: String,
@@ -12,5 +18,39 @@ pub struct RestaurantMenu {
     pub restaurant_menu_id: i64,
     pub name: String,
     pub is_active: bool,
-    pub restaurant_id: i64,
+    pub restaurant_id: Option<i32>,
+    pub restaurant_chain_id: Option<i32>,
+}
+
+// The copy starts inactive. Images are shared with the original unless
+// copy_images asks for files of its own.
+#[derive(Deserialize)]
+pub struct CloneRestaurantMenu {
+    // Defaults to the original name.
+    pub name: Option<String>,
+    // Another restaurant of the same owner, in the same currency.
+    pub target_restaurant_id: Option<i32>,
+    #[serde(default)]
+    pub copy_images: bool,
+}
+
+// Defaults to now, evaluated in the restaurant timezone.
+#[derive(Deserialize)]
+pub struct MenuAvailabilityInput {
+    pub at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantMenuWithItems {
+    #[serde(flatten)]
+    pub menu: RestaurantMenu,
+    // Items without a section, listed before the sections.
+    pub items: Vec<RestaurantMenuItem>,
+    pub sections: Vec<RestaurantMenuSectionWithItems>,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantFullMenu {
+    pub restaurant_id: i32,
+    pub menus: Vec<RestaurantMenuWithItems>,
 }
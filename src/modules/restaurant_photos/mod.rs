@@ -0,0 +1,2 @@
+pub mod restaurant_photos_controller;
+pub mod restaurant_photos_dto;
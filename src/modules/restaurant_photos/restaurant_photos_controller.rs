@@ -0,0 +1,459 @@
+use std::sync::Arc;
+
+use crate::{
+    modules::{
+        files::files_controller::delete_file,
+        restaurants::restaurants_service::{
+            check_restaurant_access, resolve_restaurant, RestaurantLookup,
+        },
+        shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    Extension,
+};
+use sqlx::{Pool, Postgres, Transaction};
+
+use super::restaurant_photos_dto::{
+    CreateRestaurantPhoto, ReorderRestaurantPhotos, RestaurantPhoto, UpdateRestaurantPhoto,
+};
+
+pub async fn find_restaurant_photos(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+) -> Result<Vec<RestaurantPhoto>, sqlx::Error> {
+    sqlx::query_as!(
+        RestaurantPhoto,
+        "SELECT restaurant_photo_id,restaurant_id,image_uri,caption,position,is_cover,created_at
+        FROM restaurant_photos
+        WHERE restaurant_id = $1
+        AND restaurant_id IN (SELECT restaurant_id FROM restaurants WHERE deleted_at IS NULL)
+        ORDER BY position ASC, restaurant_photo_id ASC",
+        restaurant_id
+    )
+    .fetch_all(db)
+    .await
+}
+
+// Makes the photo the restaurant cover and mirrors it into
+// `restaurants.cover_image_uri`. Returns the previous cover when it was a
+// standalone upload that nothing references anymore.
+async fn set_cover_photo(
+    tx: &mut Transaction<'_, Postgres>,
+    restaurant_id: i32,
+    restaurant_photo_id: i32,
+) -> Result<Option<String>, sqlx::Error> {
+    let previous_cover_image_uri = sqlx::query_scalar!(
+        "SELECT cover_image_uri FROM restaurants WHERE restaurant_id = $1 FOR UPDATE",
+        restaurant_id
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE restaurant_photos SET is_cover = false WHERE restaurant_id = $1 AND is_cover",
+        restaurant_id
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    let image_uri = sqlx::query_scalar!(
+        "UPDATE restaurant_photos SET is_cover = true WHERE restaurant_photo_id = $1 AND restaurant_id = $2 RETURNING image_uri",
+        restaurant_photo_id,
+        restaurant_id
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE restaurants SET cover_image_uri = $2 WHERE restaurant_id = $1",
+        restaurant_id,
+        image_uri
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    let is_in_gallery = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM restaurant_photos WHERE restaurant_id = $1 AND image_uri = $2
+        ) AS "is_in_gallery!""#,
+        restaurant_id,
+        previous_cover_image_uri
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if previous_cover_image_uri.is_empty() || is_in_gallery {
+        return Ok(None);
+    }
+
+    Ok(Some(previous_cover_image_uri))
+}
+
+// A reordering has to list every photo of the gallery exactly once.
+fn is_full_reordering(mut current_ids: Vec<i32>, requested_ids: &[i32]) -> bool {
+    let mut requested_ids = requested_ids.to_vec();
+    current_ids.sort_unstable();
+    requested_ids.sort_unstable();
+
+    current_ids == requested_ids
+}
+
+pub async fn get_restaurant_photos(
+    State(state): State<Arc<AppState>>,
+    Path(restaurant_id): Path<String>,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<Vec<RestaurantPhoto>> {
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    match find_restaurant_photos(&state.db, restaurant_id).await {
+        Ok(photos) => AppResult::Result(StatusCode::OK, photos),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_restaurant_photo(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(create_restaurant_photo_dto): Json<CreateRestaurantPhoto>,
+) -> AppResult<RestaurantPhoto> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let res: Result<(RestaurantPhoto, Option<String>), sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        // New photos go to the end of the gallery.
+        let photo = sqlx::query_as!(
+            RestaurantPhoto,
+            "INSERT INTO restaurant_photos (restaurant_id,image_uri,caption,position)
+            SELECT $1, $2, NULLIF($3, ''), COALESCE(MAX(position) + 1, 0) FROM restaurant_photos WHERE restaurant_id = $1
+            RETURNING restaurant_photo_id,restaurant_id,image_uri,caption,position,is_cover,created_at",
+            restaurant_id,
+            create_restaurant_photo_dto.image_uri,
+            create_restaurant_photo_dto.caption
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if !create_restaurant_photo_dto.is_cover {
+            tx.commit().await?;
+            return Ok((photo, None));
+        }
+
+        let orphaned_cover_image_uri =
+            set_cover_photo(&mut tx, restaurant_id, photo.restaurant_photo_id as i32).await?;
+
+        tx.commit().await?;
+
+        Ok((
+            RestaurantPhoto {
+                is_cover: true,
+                ..photo
+            },
+            orphaned_cover_image_uri,
+        ))
+    }
+    .await;
+
+    match res {
+        Ok((photo, orphaned_cover_image_uri)) => {
+            if let Some(cover_image_uri) = orphaned_cover_image_uri {
+                delete_file(cover_image_uri);
+            }
+
+            AppResult::Result(StatusCode::CREATED, photo)
+        }
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_restaurant_photo(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_photo_id)): Path<(i32, i32)>,
+    Json(update_restaurant_photo_dto): Json<UpdateRestaurantPhoto>,
+) -> AppResult<RestaurantPhoto> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    // An empty caption clears it.
+    let res = sqlx::query_as!(
+        RestaurantPhoto,
+        "UPDATE restaurant_photos SET caption = NULLIF(COALESCE($3, caption), '')
+        WHERE restaurant_photo_id = $1 AND restaurant_id = $2
+        RETURNING restaurant_photo_id,restaurant_id,image_uri,caption,position,is_cover,created_at",
+        restaurant_photo_id,
+        restaurant_id,
+        update_restaurant_photo_dto.caption
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(photo)) => AppResult::Result(StatusCode::OK, photo),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Photo not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn reorder_restaurant_photos(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(reorder_restaurant_photos_dto): Json<ReorderRestaurantPhotos>,
+) -> AppResult<Vec<RestaurantPhoto>> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let restaurant_photo_ids = reorder_restaurant_photos_dto.restaurant_photo_ids;
+
+    let res: Result<Option<Vec<RestaurantPhoto>>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let current_ids = sqlx::query_scalar!(
+            "SELECT restaurant_photo_id FROM restaurant_photos WHERE restaurant_id = $1 FOR UPDATE",
+            restaurant_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if !is_full_reordering(current_ids, &restaurant_photo_ids) {
+            return Ok(None);
+        }
+
+        sqlx::query!(
+            "UPDATE restaurant_photos SET position = ordered.position::INTEGER - 1
+            FROM UNNEST($2::INTEGER[]) WITH ORDINALITY AS ordered(restaurant_photo_id, position)
+            WHERE restaurant_photos.restaurant_photo_id = ordered.restaurant_photo_id
+            AND restaurant_photos.restaurant_id = $1",
+            restaurant_id,
+            &restaurant_photo_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(
+            find_restaurant_photos(&state.db, restaurant_id).await?,
+        ))
+    }
+    .await;
+
+    match res {
+        Ok(Some(photos)) => AppResult::Result(StatusCode::OK, photos),
+        Ok(None) => AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("restaurant_photo_ids must list every photo of the gallery exactly once"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn set_restaurant_cover_photo(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_photo_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantPhoto> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let res: Result<Option<(RestaurantPhoto, Option<String>)>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let photo = sqlx::query_as!(
+            RestaurantPhoto,
+            "SELECT restaurant_photo_id,restaurant_id,image_uri,caption,position,is_cover,created_at
+            FROM restaurant_photos WHERE restaurant_photo_id = $1 AND restaurant_id = $2",
+            restaurant_photo_id,
+            restaurant_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(photo) = photo else {
+            return Ok(None);
+        };
+
+        let orphaned_cover_image_uri =
+            set_cover_photo(&mut tx, restaurant_id, restaurant_photo_id).await?;
+
+        tx.commit().await?;
+
+        Ok(Some((
+            RestaurantPhoto {
+                is_cover: true,
+                ..photo
+            },
+            orphaned_cover_image_uri,
+        )))
+    }
+    .await;
+
+    match res {
+        Ok(Some((photo, orphaned_cover_image_uri))) => {
+            if let Some(cover_image_uri) = orphaned_cover_image_uri {
+                delete_file(cover_image_uri);
+            }
+
+            AppResult::Result(StatusCode::OK, photo)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Photo not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_photo(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_photo_id)): Path<(i32, i32)>,
+) -> AppResult<RestaurantPhoto> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let res: Result<Option<(RestaurantPhoto, bool)>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let cover_image_uri = sqlx::query_scalar!(
+            "SELECT cover_image_uri FROM restaurants WHERE restaurant_id = $1 FOR UPDATE",
+            restaurant_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let photo = sqlx::query_as!(
+            RestaurantPhoto,
+            "DELETE FROM restaurant_photos WHERE restaurant_photo_id = $1 AND restaurant_id = $2
+            RETURNING restaurant_photo_id,restaurant_id,image_uri,caption,position,is_cover,created_at",
+            restaurant_photo_id,
+            restaurant_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(photo) = photo else {
+            return Ok(None);
+        };
+
+        // The next photo in the gallery takes over as cover.
+        if photo.is_cover {
+            let next_cover_image_uri = sqlx::query_scalar!(
+                "UPDATE restaurant_photos SET is_cover = true
+                WHERE restaurant_photo_id = (
+                    SELECT restaurant_photo_id FROM restaurant_photos
+                    WHERE restaurant_id = $1
+                    ORDER BY position ASC, restaurant_photo_id ASC
+                    LIMIT 1
+                )
+                RETURNING image_uri",
+                restaurant_id
+            )
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "UPDATE restaurants SET cover_image_uri = $2 WHERE restaurant_id = $1",
+                restaurant_id,
+                next_cover_image_uri.unwrap_or_default()
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // The same upload can be in the gallery twice, or still be the
+        // standalone cover, so only remove the file once nothing uses it.
+        let is_still_used = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM restaurant_photos WHERE restaurant_id = $1 AND image_uri = $2
+            ) AS "is_still_used!""#,
+            restaurant_id,
+            photo.image_uri
+        )
+        .fetch_one(&mut *tx)
+        .await?
+            || (!photo.is_cover && cover_image_uri == photo.image_uri);
+
+        tx.commit().await?;
+
+        Ok(Some((photo, is_still_used)))
+    }
+    .await;
+
+    match res {
+        Ok(Some((photo, is_still_used))) => {
+            if !is_still_used {
+                delete_file(photo.image_uri.clone());
+            }
+
+            AppResult::Result(StatusCode::OK, photo)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Photo not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reordering_accepts_any_order_of_the_whole_gallery() {
+        assert!(is_full_reordering(vec![1, 2, 3], &[3, 1, 2]));
+        assert!(is_full_reordering(vec![1, 2, 3], &[1, 2, 3]));
+        assert!(is_full_reordering(vec![], &[]));
+    }
+
+    #[test]
+    fn reordering_rejects_missing_repeated_or_foreign_photos() {
+        assert!(!is_full_reordering(vec![1, 2, 3], &[1, 2]));
+        assert!(!is_full_reordering(vec![1, 2, 3], &[1, 2, 2, 3]));
+        assert!(!is_full_reordering(vec![1, 2, 3], &[1, 2, 2]));
+        assert!(!is_full_reordering(vec![1, 2, 3], &[1, 2, 4]));
+    }
+}
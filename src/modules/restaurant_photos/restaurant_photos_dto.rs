@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Deserialize)]
+pub struct CreateRestaurantPhoto {
+    pub image_uri: String,
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub is_cover: bool,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantPhoto {
+    pub caption: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ReorderRestaurantPhotos {
+    pub restaurant_photo_ids: Vec<i32>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantPhoto {
+    pub restaurant_photo_id: i64,
+    pub restaurant_id: i64,
+    pub image_uri: String,
+    pub caption: Option<String>,
+    pub position: i32,
+    pub is_cover: bool,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,3 @@
+pub mod restaurant_qr_codes_controller;
+pub mod restaurant_qr_codes_dto;
+pub mod restaurant_qr_codes_service;
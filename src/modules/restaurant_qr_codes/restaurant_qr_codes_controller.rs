@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use crate::{
+    common::qr::QrCode,
+    modules::{
+        restaurants::restaurants_service::can_manage_restaurant, shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+};
+
+use super::{
+    restaurant_qr_codes_dto::{
+        QrCodeFormatEnum, QrCodeInput, QrCodeSheetInput, MAX_QR_CODE_MARGIN, MAX_QR_CODE_SIZE,
+        MAX_SHEET_TABLES, MIN_QR_CODE_SIZE,
+    },
+    restaurant_qr_codes_service::{menu_link, qr_code_png, qr_code_sheet_html, qr_code_svg},
+};
+
+struct QrCodeTarget {
+    slug: String,
+    restaurant_name: String,
+    menu_name: Option<String>,
+}
+
+// Menus of the chain of the restaurant can be linked too, they are shown on
+// its public page.
+async fn find_qr_code_target(
+    state: &AppState,
+    restaurant_id: i32,
+    restaurant_menu_id: Option<i32>,
+    current_user: &User,
+) -> Result<QrCodeTarget, AppResult<()>> {
+    let internal_error = || {
+        AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )
+    };
+
+    match can_manage_restaurant(&state.db, restaurant_id, current_user).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(AppResult::Error(
+                StatusCode::NOT_FOUND,
+                String::from("Restaurant not found!"),
+            ))
+        }
+        Err(_) => return Err(internal_error()),
+    }
+
+    let restaurant = sqlx::query!(
+        "SELECT slug,name FROM restaurants WHERE restaurant_id = $1 AND deleted_at IS NULL",
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+    let restaurant = match restaurant {
+        Ok(Some(restaurant)) => restaurant,
+        Ok(None) => {
+            return Err(AppResult::Error(
+                StatusCode::NOT_FOUND,
+                String::from("Restaurant not found!"),
+            ))
+        }
+        Err(_) => return Err(internal_error()),
+    };
+
+    let menu_name = match restaurant_menu_id {
+        Some(restaurant_menu_id) => {
+            let menu_name = sqlx::query_scalar!(
+                "SELECT rm.name FROM restaurant_menus rm
+                JOIN restaurants r ON rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id
+                WHERE rm.restaurant_menu_id = $1 AND r.restaurant_id = $2 AND rm.deleted_at IS NULL",
+                restaurant_menu_id,
+                restaurant_id
+            )
+            .fetch_optional(&state.db)
+            .await;
+
+            match menu_name {
+                Ok(Some(menu_name)) => Some(menu_name),
+                Ok(None) => {
+                    return Err(AppResult::Error(
+                        StatusCode::NOT_FOUND,
+                        String::from("Menu not found!"),
+                    ))
+                }
+                Err(_) => return Err(internal_error()),
+            }
+        }
+        None => None,
+    };
+
+    Ok(QrCodeTarget {
+        slug: restaurant.slug,
+        restaurant_name: restaurant.name,
+        menu_name,
+    })
+}
+
+fn encode_link(link: &str) -> Result<QrCode, AppResult<()>> {
+    QrCode::encode(link.as_bytes()).ok_or_else(|| {
+        AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )
+    })
+}
+
+pub async fn get_restaurant_qr_code(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    qr_code_input: Query<QrCodeInput>,
+) -> Response {
+    if !(MIN_QR_CODE_SIZE..=MAX_QR_CODE_SIZE).contains(&qr_code_input.size)
+        || qr_code_input.margin > MAX_QR_CODE_MARGIN
+    {
+        return AppResult::<()>::Error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "size must be between {} and {} and margin at most {}",
+                MIN_QR_CODE_SIZE, MAX_QR_CODE_SIZE, MAX_QR_CODE_MARGIN
+            ),
+        )
+        .into_response();
+    }
+    if qr_code_input.table.is_some_and(|table| table < 1) {
+        return AppResult::<()>::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("table must be positive"),
+        )
+        .into_response();
+    }
+
+    let target = match find_qr_code_target(
+        &state,
+        restaurant_id,
+        qr_code_input.menu_id,
+        &current_user,
+    )
+    .await
+    {
+        Ok(target) => target,
+        Err(err) => return err.into_response(),
+    };
+
+    let link = menu_link(
+        &state.env.public_menu_url,
+        &target.slug,
+        qr_code_input.menu_id,
+        qr_code_input.table,
+    );
+    let qr = match encode_link(&link) {
+        Ok(qr) => qr,
+        Err(err) => return err.into_response(),
+    };
+
+    match qr_code_input.format {
+        QrCodeFormatEnum::Png => (
+            StatusCode::OK,
+            [(CONTENT_TYPE, "image/png")],
+            qr_code_png(&qr, qr_code_input.size, qr_code_input.margin),
+        )
+            .into_response(),
+        QrCodeFormatEnum::Svg => (
+            StatusCode::OK,
+            [(CONTENT_TYPE, "image/svg+xml")],
+            qr_code_svg(&qr, Some(qr_code_input.size), qr_code_input.margin),
+        )
+            .into_response(),
+    }
+}
+
+// A printable page, one card with the QR code and number of each table.
+pub async fn get_restaurant_qr_code_sheet(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    sheet_input: Query<QrCodeSheetInput>,
+) -> Response {
+    let QrCodeSheetInput {
+        menu_id,
+        from_table,
+        to_table,
+        margin,
+    } = sheet_input.0;
+
+    if from_table < 1 || to_table < from_table || to_table - from_table >= MAX_SHEET_TABLES {
+        return AppResult::<()>::Error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "tables must be positive, from_table at most to_table and at most {} tables",
+                MAX_SHEET_TABLES
+            ),
+        )
+        .into_response();
+    }
+    if margin > MAX_QR_CODE_MARGIN {
+        return AppResult::<()>::Error(
+            StatusCode::BAD_REQUEST,
+            format!("margin must be at most {}", MAX_QR_CODE_MARGIN),
+        )
+        .into_response();
+    }
+
+    let target = match find_qr_code_target(&state, restaurant_id, menu_id, &current_user).await {
+        Ok(target) => target,
+        Err(err) => return err.into_response(),
+    };
+
+    let mut tables = Vec::new();
+    for table in from_table..=to_table {
+        let link = menu_link(
+            &state.env.public_menu_url,
+            &target.slug,
+            menu_id,
+            Some(table),
+        );
+        match encode_link(&link) {
+            Ok(qr) => tables.push((table, qr_code_svg(&qr, None, margin))),
+            Err(err) => return err.into_response(),
+        }
+    }
+
+    (
+        StatusCode::OK,
+        [(CONTENT_TYPE, "text/html; charset=utf-8")],
+        qr_code_sheet_html(
+            &target.restaurant_name,
+            target.menu_name.as_deref(),
+            &tables,
+        ),
+    )
+        .into_response()
+}
@@ -0,0 +1,52 @@
+use serde::Deserialize;
+
+pub const MIN_QR_CODE_SIZE: u32 = 64;
+pub const MAX_QR_CODE_SIZE: u32 = 2048;
+// In modules. Scanners need a quiet zone of 4 around the code.
+pub const MAX_QR_CODE_MARGIN: u32 = 16;
+pub const MAX_SHEET_TABLES: i32 = 100;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QrCodeFormatEnum {
+    Png,
+    #[default]
+    Svg,
+}
+
+#[derive(Deserialize)]
+pub struct QrCodeInput {
+    #[serde(default)]
+    pub format: QrCodeFormatEnum,
+    // Links to this menu instead of the whole restaurant.
+    pub menu_id: Option<i32>,
+    pub table: Option<i32>,
+    // Width and height in pixels.
+    #[serde(default = "default_size")]
+    pub size: u32,
+    #[serde(default = "default_margin")]
+    pub margin: u32,
+}
+
+// One QR code per table, from_table to to_table included.
+#[derive(Deserialize)]
+pub struct QrCodeSheetInput {
+    pub menu_id: Option<i32>,
+    #[serde(default = "default_from_table")]
+    pub from_table: i32,
+    pub to_table: i32,
+    #[serde(default = "default_margin")]
+    pub margin: u32,
+}
+
+fn default_size() -> u32 {
+    512
+}
+
+fn default_margin() -> u32 {
+    4
+}
+
+fn default_from_table() -> i32 {
+    1
+}
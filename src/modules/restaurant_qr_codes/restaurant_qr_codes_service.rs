@@ -0,0 +1,121 @@
+use std::fmt::Write;
+
+use crate::common::{png::encode_grayscale_png, qr::QrCode};
+
+pub fn menu_link(
+    public_menu_url: &str,
+    slug: &str,
+    restaurant_menu_id: Option<i32>,
+    table: Option<i32>,
+) -> String {
+    let mut params = Vec::new();
+    if let Some(restaurant_menu_id) = restaurant_menu_id {
+        params.push(format!("menu={}", restaurant_menu_id));
+    }
+    if let Some(table) = table {
+        params.push(format!("table={}", table));
+    }
+
+    if params.is_empty() {
+        format!("{}/{}", public_menu_url, slug)
+    } else {
+        format!("{}/{}?{}", public_menu_url, slug, params.join("&"))
+    }
+}
+
+// Dark modules as a single path of unit squares, scaled by the viewBox.
+pub fn qr_code_svg(qr: &QrCode, size: Option<u32>, margin: u32) -> String {
+    let total = qr.size as u32 + margin * 2;
+    let mut path = String::new();
+    for y in 0..qr.size {
+        for x in 0..qr.size {
+            if qr.is_dark(x, y) {
+                let _ = write!(path, "M{},{}h1v1h-1z", x as u32 + margin, y as u32 + margin);
+            }
+        }
+    }
+
+    let dimensions = match size {
+        Some(size) => format!(" width=\"{}\" height=\"{}\"", size, size),
+        None => String::new(),
+    };
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {total} {total}\"{dimensions} shape-rendering=\"crispEdges\"><rect width=\"{total}\" height=\"{total}\" fill=\"#fff\"/><path d=\"{path}\" fill=\"#000\"/></svg>",
+    )
+}
+
+// Modules are whole pixels so the code stays sharp, the image is therefore
+// the largest multiple of the module count that fits in size, at least one
+// pixel per module.
+pub fn qr_code_png(qr: &QrCode, size: u32, margin: u32) -> Vec<u8> {
+    let total = qr.size as u32 + margin * 2;
+    let scale = (size / total).max(1);
+    let width = total * scale;
+
+    let mut pixels = vec![255u8; (width * width) as usize];
+    for y in 0..qr.size {
+        for x in 0..qr.size {
+            if !qr.is_dark(x, y) {
+                continue;
+            }
+            let left = (x as u32 + margin) * scale;
+            let top = (y as u32 + margin) * scale;
+            for row in top..top + scale {
+                let start = (row * width + left) as usize;
+                pixels[start..start + scale as usize].fill(0);
+            }
+        }
+    }
+
+    encode_grayscale_png(width, width, &pixels)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// A4 page with six cards a page, each cut along its dashed border.
+pub fn qr_code_sheet_html(
+    restaurant_name: &str,
+    menu_name: Option<&str>,
+    tables: &[(i32, String)],
+) -> String {
+    let title = match menu_name {
+        Some(menu_name) => format!("{} - {}", restaurant_name, menu_name),
+        None => restaurant_name.to_string(),
+    };
+    let title = escape_html(&title);
+
+    let mut html = format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+@page {{ size: A4; margin: 10mm; }}
+body {{ margin: 0; font-family: sans-serif; }}
+.sheet {{ display: flex; flex-wrap: wrap; }}
+.card {{ box-sizing: border-box; width: 50%; height: 92mm; padding: 6mm; border: 1px dashed #999; text-align: center; break-inside: avoid; }}
+.card svg {{ width: 60mm; height: 60mm; }}
+.name {{ font-size: 11pt; }}
+.table {{ font-size: 16pt; font-weight: bold; }}
+</style>
+</head>
+<body>
+<div class=\"sheet\">
+"
+    );
+    for (table, svg) in tables {
+        let _ = writeln!(
+            html,
+            "<div class=\"card\"><div class=\"name\">{}</div>{}<div class=\"table\">Table {}</div></div>",
+            title, svg, table
+        );
+    }
+    html.push_str("</div>\n</body>\n</html>\n");
+    html
+}
@@ -0,0 +1,2 @@
+pub mod restaurant_transfers_controller;
+pub mod restaurant_transfers_dto;
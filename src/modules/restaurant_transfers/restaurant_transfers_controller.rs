@@ -0,0 +1,543 @@
+use std::sync::Arc;
+
+use crate::{
+    modules::{
+        restaurants::restaurants_service::{can_manage_restaurant, refresh_restaurant_price_level},
+        shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+
+use super::restaurant_transfers_dto::{CreateRestaurantTransfer, RestaurantTransfer};
+
+const RESTAURANT_TRANSFER_EXPIRY_DAYS: i32 = 7;
+
+#[derive(Debug, PartialEq)]
+enum TransferAcceptance {
+    Allowed,
+    NotPending,
+    Expired,
+}
+
+// Only a pending transfer can be accepted, and only until it expires.
+fn check_transfer_acceptance(
+    status: &str,
+    expires_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> TransferAcceptance {
+    if status != "Pending" {
+        TransferAcceptance::NotPending
+    } else if expires_at <= now {
+        TransferAcceptance::Expired
+    } else {
+        TransferAcceptance::Allowed
+    }
+}
+
+// Pending transfers past their expiry are reported as expired even before
+// anything has marked them so.
+async fn find_restaurant_transfer(
+    db: &Pool<Postgres>,
+    restaurant_transfer_id: i32,
+) -> Result<RestaurantTransfer, sqlx::Error> {
+    sqlx::query_as!(
+        RestaurantTransfer,
+        r#"SELECT
+        rt.restaurant_transfer_id,
+        rt.restaurant_id,
+        r.name AS restaurant_name,
+        rt.from_user_id,
+        from_user.email AS from_user_email,
+        rt.to_user_id,
+        to_user.email AS to_user_email,
+        rt.initiated_by_user_id,
+        CASE WHEN rt.status = 'Pending' AND rt.expires_at <= NOW() THEN 'Expired' ELSE rt.status END AS "status!",
+        rt.created_at,
+        rt.expires_at,
+        rt.responded_at
+        FROM restaurant_transfers rt
+        JOIN restaurants r ON rt.restaurant_id = r.restaurant_id
+        JOIN users from_user ON rt.from_user_id = from_user.user_id
+        JOIN users to_user ON rt.to_user_id = to_user.user_id
+        WHERE rt.restaurant_transfer_id = $1"#,
+        restaurant_transfer_id
+    )
+    .fetch_one(db)
+    .await
+}
+
+async fn to_restaurant_transfer_result(
+    state: &AppState,
+    status_code: StatusCode,
+    restaurant_transfer_id: i32,
+) -> AppResult<RestaurantTransfer> {
+    match find_restaurant_transfer(&state.db, restaurant_transfer_id).await {
+        Ok(restaurant_transfer) => AppResult::Result(status_code, restaurant_transfer),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_transfers(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<RestaurantTransfer>> {
+    match can_manage_restaurant(&state.db, restaurant_id, &current_user).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantTransfer,
+        r#"SELECT
+        rt.restaurant_transfer_id,
+        rt.restaurant_id,
+        r.name AS restaurant_name,
+        rt.from_user_id,
+        from_user.email AS from_user_email,
+        rt.to_user_id,
+        to_user.email AS to_user_email,
+        rt.initiated_by_user_id,
+        CASE WHEN rt.status = 'Pending' AND rt.expires_at <= NOW() THEN 'Expired' ELSE rt.status END AS "status!",
+        rt.created_at,
+        rt.expires_at,
+        rt.responded_at
+        FROM restaurant_transfers rt
+        JOIN restaurants r ON rt.restaurant_id = r.restaurant_id
+        JOIN users from_user ON rt.from_user_id = from_user.user_id
+        JOIN users to_user ON rt.to_user_id = to_user.user_id
+        WHERE rt.restaurant_id = $1
+        ORDER BY rt.created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        restaurant_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (restaurant_transfer_id) FROM restaurant_transfers where restaurant_id = $1",
+        restaurant_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(restaurant_transfers) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: restaurant_transfers,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_my_restaurant_transfers(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<RestaurantTransfer>> {
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantTransfer,
+        r#"SELECT
+        rt.restaurant_transfer_id,
+        rt.restaurant_id,
+        r.name AS restaurant_name,
+        rt.from_user_id,
+        from_user.email AS from_user_email,
+        rt.to_user_id,
+        to_user.email AS to_user_email,
+        rt.initiated_by_user_id,
+        CASE WHEN rt.status = 'Pending' AND rt.expires_at <= NOW() THEN 'Expired' ELSE rt.status END AS "status!",
+        rt.created_at,
+        rt.expires_at,
+        rt.responded_at
+        FROM restaurant_transfers rt
+        JOIN restaurants r ON rt.restaurant_id = r.restaurant_id
+        JOIN users from_user ON rt.from_user_id = from_user.user_id
+        JOIN users to_user ON rt.to_user_id = to_user.user_id
+        WHERE rt.from_user_id = $1 OR rt.to_user_id = $1 OR rt.initiated_by_user_id = $1
+        ORDER BY rt.created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        current_user.user_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (restaurant_transfer_id) FROM restaurant_transfers
+        where from_user_id = $1 OR to_user_id = $1 OR initiated_by_user_id = $1",
+        current_user.user_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(restaurant_transfers) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: restaurant_transfers,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_restaurant_transfer(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(create_restaurant_transfer_dto): Json<CreateRestaurantTransfer>,
+) -> AppResult<RestaurantTransfer> {
+    match can_manage_restaurant(&state.db, restaurant_id, &current_user).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let recipient = sqlx::query!(
+        "SELECT users.user_id, users.user_id = restaurants.user_id AS is_current_owner
+        FROM users, restaurants
+        WHERE LOWER(users.email) = LOWER($1) AND users.role = 'RestaurantOwner' AND users.status = 'Accepted'
+        AND restaurants.restaurant_id = $2",
+        create_restaurant_transfer_dto.to_email.trim(),
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    let to_user_id = match recipient {
+        Ok(Some(recipient)) if recipient.is_current_owner == Some(true) => {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("The restaurant already belongs to this owner"),
+            )
+        }
+        Ok(Some(recipient)) => recipient.user_id,
+        Ok(None) => {
+            return AppResult::Error(
+                StatusCode::NOT_FOUND,
+                String::from("Restaurant owner not found!"),
+            )
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    let res: Result<i32, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        // An expired transfer must not keep blocking new ones.
+        sqlx::query!(
+            "UPDATE restaurant_transfers SET status = 'Expired'
+            WHERE restaurant_id = $1 AND status = 'Pending' AND expires_at <= NOW()",
+            restaurant_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let restaurant_transfer_id = sqlx::query_scalar!(
+            "INSERT INTO restaurant_transfers (restaurant_id,from_user_id,to_user_id,initiated_by_user_id,expires_at)
+            SELECT restaurant_id, user_id, $2, $3, NOW() + make_interval(days => $4)
+            FROM restaurants WHERE restaurant_id = $1
+            RETURNING restaurant_transfer_id",
+            restaurant_id,
+            to_user_id,
+            current_user.user_id,
+            RESTAURANT_TRANSFER_EXPIRY_DAYS
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(restaurant_transfer_id)
+    }
+    .await;
+
+    match res {
+        Ok(restaurant_transfer_id) => {
+            to_restaurant_transfer_result(&state, StatusCode::CREATED, restaurant_transfer_id).await
+        }
+        Err(sqlx::Error::Database(err)) if err.is_unique_violation() => AppResult::Error(
+            StatusCode::CONFLICT,
+            String::from("A transfer is already pending for this restaurant!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn accept_restaurant_transfer(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_transfer_id): Path<i32>,
+) -> AppResult<RestaurantTransfer> {
+    let res: Result<Result<i32, (StatusCode, &str)>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let transfer = sqlx::query!(
+            "SELECT restaurant_id, from_user_id, status, expires_at
+            FROM restaurant_transfers
+            WHERE restaurant_transfer_id = $1 AND to_user_id = $2
+            FOR UPDATE",
+            restaurant_transfer_id,
+            current_user.user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(transfer) = transfer else {
+            return Ok(Err((StatusCode::NOT_FOUND, "Transfer not found!")));
+        };
+
+        match check_transfer_acceptance(&transfer.status, transfer.expires_at, Utc::now()) {
+            TransferAcceptance::Allowed => {}
+            TransferAcceptance::NotPending => {
+                return Ok(Err((StatusCode::CONFLICT, "Transfer is no longer pending!")));
+            }
+            TransferAcceptance::Expired => {
+                sqlx::query!(
+                    "UPDATE restaurant_transfers SET status = 'Expired' WHERE restaurant_transfer_id = $1",
+                    restaurant_transfer_id
+                )
+                .execute(&mut *tx)
+                .await?;
+                tx.commit().await?;
+
+                return Ok(Err((StatusCode::CONFLICT, "Transfer has expired!")));
+            }
+        }
+
+        // The restaurant may have been trashed or handed over some other way
+        // since the transfer was created.
+        let is_still_owned = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM restaurants
+                WHERE restaurant_id = $1 AND user_id = $2 AND deleted_at IS NULL
+                FOR UPDATE
+            ) AS "is_still_owned!""#,
+            transfer.restaurant_id,
+            transfer.from_user_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if !is_still_owned {
+            sqlx::query!(
+                "UPDATE restaurant_transfers SET status = 'Cancelled', responded_at = NOW() WHERE restaurant_transfer_id = $1",
+                restaurant_transfer_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            return Ok(Err((StatusCode::CONFLICT, "Transfer is no longer valid!")));
+        }
+
+        sqlx::query!(
+            // The new owner cannot manage the previous owner's chain, so the
+            // branch leaves it.
+            "UPDATE restaurants SET user_id = $2, restaurant_chain_id = NULL WHERE restaurant_id = $1",
+            transfer.restaurant_id,
+            current_user.user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "INSERT INTO restaurant_ownership_changes (restaurant_id,previous_user_id,new_user_id,restaurant_transfer_id) VALUES ($1,$2,$3,$4)",
+            transfer.restaurant_id,
+            transfer.from_user_id,
+            current_user.user_id,
+            restaurant_transfer_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE restaurant_transfers SET status = 'Accepted', responded_at = NOW() WHERE restaurant_transfer_id = $1",
+            restaurant_transfer_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Ok(transfer.restaurant_id))
+    }
+    .await;
+
+    match res {
+        Ok(Ok(restaurant_id)) => {
+            // Leaving the chain may have dropped inherited menus.
+            refresh_restaurant_price_level(&state.db, restaurant_id).await;
+            to_restaurant_transfer_result(&state, StatusCode::OK, restaurant_transfer_id).await
+        }
+        Ok(Err((status_code, message))) => AppResult::Error(status_code, String::from(message)),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn decline_restaurant_transfer(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_transfer_id): Path<i32>,
+) -> AppResult<RestaurantTransfer> {
+    let res = sqlx::query_scalar!(
+        "UPDATE restaurant_transfers SET status = 'Declined', responded_at = NOW()
+        WHERE restaurant_transfer_id = $1 AND to_user_id = $2
+        AND status = 'Pending' AND expires_at > NOW()
+        RETURNING restaurant_transfer_id",
+        restaurant_transfer_id,
+        current_user.user_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_transfer_id)) => {
+            to_restaurant_transfer_result(&state, StatusCode::OK, restaurant_transfer_id).await
+        }
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Pending transfer not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn cancel_restaurant_transfer(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_transfer_id): Path<i32>,
+) -> AppResult<RestaurantTransfer> {
+    let res = sqlx::query_scalar!(
+        "UPDATE restaurant_transfers SET status = 'Cancelled', responded_at = NOW()
+        WHERE restaurant_transfer_id = $1
+        AND (from_user_id = $2 OR initiated_by_user_id = $2 OR $3)
+        AND status = 'Pending' AND expires_at > NOW()
+        RETURNING restaurant_transfer_id",
+        restaurant_transfer_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant_transfer_id)) => {
+            to_restaurant_transfer_result(&state, StatusCode::OK, restaurant_transfer_id).await
+        }
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Pending transfer not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn pending_transfers_can_be_accepted_until_they_expire() {
+        let now = Utc::now();
+
+        assert_eq!(
+            check_transfer_acceptance("Pending", now + Duration::minutes(1), now),
+            TransferAcceptance::Allowed
+        );
+        assert_eq!(
+            check_transfer_acceptance("Pending", now, now),
+            TransferAcceptance::Expired
+        );
+        assert_eq!(
+            check_transfer_acceptance("Pending", now - Duration::days(1), now),
+            TransferAcceptance::Expired
+        );
+    }
+
+    #[test]
+    fn settled_transfers_cannot_be_accepted() {
+        let now = Utc::now();
+
+        for status in ["Accepted", "Declined", "Cancelled", "Expired"] {
+            assert_eq!(
+                check_transfer_acceptance(status, now + Duration::days(1), now),
+                TransferAcceptance::NotPending,
+                "{}",
+                status
+            );
+        }
+    }
+}
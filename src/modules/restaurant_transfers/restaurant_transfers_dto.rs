@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Deserialize)]
+pub struct CreateRestaurantTransfer {
+    pub to_email: String,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantTransfer {
+    pub restaurant_transfer_id: i64,
+    pub restaurant_id: i64,
+    pub restaurant_name: String,
+    pub from_user_id: i64,
+    pub from_user_email: String,
+    pub to_user_id: i64,
+    pub to_user_email: String,
+    pub initiated_by_user_id: i64,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,3 @@
+pub mod restaurant_verifications_controller;
+pub mod restaurant_verifications_dto;
+pub mod restaurant_verifications_service;
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use crate::{
+    modules::{shared::shared_dto::AppResult, users::users_dto::User},
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    Extension,
+};
+use pwhash::bcrypt;
+
+use super::{
+    restaurant_verifications_dto::{ConfirmRestaurantVerification, RestaurantVerification},
+    restaurant_verifications_service::{
+        send_restaurant_verification_code, MAX_VERIFICATION_ATTEMPTS,
+        VERIFICATION_CODE_RESEND_SECONDS,
+    },
+};
+
+pub async fn get_restaurant_verification(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+) -> AppResult<RestaurantVerification> {
+    let res = sqlx::query_as!(
+        RestaurantVerification,
+        r#"SELECT restaurants.restaurant_id AS "restaurant_id!",restaurants.email,restaurants.is_verified,
+        restaurant_email_verifications.expires_at AS "expires_at?"
+        FROM restaurants
+        LEFT JOIN restaurant_email_verifications
+        ON restaurant_email_verifications.restaurant_id = restaurants.restaurant_id
+        AND restaurant_email_verifications.email = restaurants.email
+        AND restaurant_email_verifications.expires_at > NOW()
+        WHERE restaurants.restaurant_id = $1 AND (restaurants.user_id = $2 OR $3)
+        AND restaurants.deleted_at IS NULL"#,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(verification)) => AppResult::Result(StatusCode::OK, verification),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn send_restaurant_verification(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+) -> AppResult<RestaurantVerification> {
+    let restaurant = sqlx::query!(
+        r#"SELECT restaurants.email,restaurants.is_verified,
+        EXISTS (
+            SELECT 1 FROM restaurant_email_verifications
+            WHERE restaurant_email_verifications.restaurant_id = restaurants.restaurant_id
+            AND restaurant_email_verifications.created_at > NOW() - make_interval(secs => $4)
+        ) AS "is_throttled!"
+        FROM restaurants
+        WHERE restaurant_id = $1 AND (user_id = $2 OR $3) AND deleted_at IS NULL"#,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin",
+        VERIFICATION_CODE_RESEND_SECONDS as f64
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    let restaurant = match restaurant {
+        Ok(Some(restaurant)) => restaurant,
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+
+    if restaurant.is_verified {
+        return AppResult::Error(
+            StatusCode::CONFLICT,
+            String::from("Restaurant email is already verified!"),
+        );
+    }
+
+    if restaurant.is_throttled {
+        return AppResult::Error(
+            StatusCode::TOO_MANY_REQUESTS,
+            String::from("Please wait before requesting a new code!"),
+        );
+    }
+
+    match send_restaurant_verification_code(&state.db, restaurant_id, &restaurant.email).await {
+        Ok(expires_at) => AppResult::Result(
+            StatusCode::CREATED,
+            RestaurantVerification {
+                restaurant_id,
+                email: restaurant.email,
+                is_verified: false,
+                expires_at: Some(expires_at),
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn confirm_restaurant_verification(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(confirm_restaurant_verification_dto): Json<ConfirmRestaurantVerification>,
+) -> AppResult<RestaurantVerification> {
+    let code = confirm_restaurant_verification_dto.code.trim().to_string();
+
+    // Failed attempts are committed too, so the attempt limit holds even
+    // though the request itself is rejected.
+    let res: Result<Result<RestaurantVerification, (StatusCode, &str)>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let verification = sqlx::query!(
+            r#"SELECT restaurant_email_verifications.code_hash,restaurant_email_verifications.attempts,
+            restaurant_email_verifications.expires_at < NOW() AS "is_expired!",
+            restaurants.email
+            FROM restaurant_email_verifications
+            JOIN restaurants ON restaurants.restaurant_id = restaurant_email_verifications.restaurant_id
+            AND restaurants.email = restaurant_email_verifications.email
+            WHERE restaurant_email_verifications.restaurant_id = $1
+            AND (restaurants.user_id = $2 OR $3) AND restaurants.deleted_at IS NULL
+            FOR UPDATE OF restaurant_email_verifications"#,
+            restaurant_id,
+            current_user.user_id,
+            current_user.role == "Admin"
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(verification) = verification else {
+            return Ok(Err((StatusCode::NOT_FOUND, "Verification code not found!")));
+        };
+
+        if verification.is_expired {
+            return Ok(Err((
+                StatusCode::CONFLICT,
+                "Verification code has expired, request a new one!",
+            )));
+        }
+
+        if verification.attempts >= MAX_VERIFICATION_ATTEMPTS {
+            return Ok(Err((
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many attempts, request a new code!",
+            )));
+        }
+
+        if !bcrypt::verify(&code, &verification.code_hash) {
+            sqlx::query!(
+                "UPDATE restaurant_email_verifications SET attempts = attempts + 1 WHERE restaurant_id = $1",
+                restaurant_id
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+
+            return Ok(Err((StatusCode::BAD_REQUEST, "Invalid verification code!")));
+        }
+
+        sqlx::query!(
+            "UPDATE restaurants SET is_verified = true WHERE restaurant_id = $1",
+            restaurant_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "DELETE FROM restaurant_email_verifications WHERE restaurant_id = $1",
+            restaurant_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Ok(RestaurantVerification {
+            restaurant_id,
+            email: verification.email,
+            is_verified: true,
+            expires_at: None,
+        }))
+    }
+    .await;
+
+    match res {
+        Ok(Ok(verification)) => AppResult::Result(StatusCode::OK, verification),
+        Ok(Err((status_code, message))) => AppResult::Error(status_code, String::from(message)),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
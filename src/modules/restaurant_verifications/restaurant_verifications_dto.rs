@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+pub struct RestaurantVerification {
+    pub restaurant_id: i32,
+    pub email: String,
+    pub is_verified: bool,
+    // Only set while a code is waiting to be confirmed.
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmRestaurantVerification {
+    pub code: String,
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use pwhash::bcrypt;
+use rand::Rng;
+use sqlx::{Pool, Postgres};
+
+use crate::common::mailer::send_mail;
+
+pub const VERIFICATION_CODE_TTL_MINUTES: i32 = 30;
+pub const VERIFICATION_CODE_RESEND_SECONDS: i32 = 60;
+pub const MAX_VERIFICATION_ATTEMPTS: i32 = 5;
+
+// Replaces any pending code, so only the last mailed one can be confirmed.
+pub async fn send_restaurant_verification_code(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+    email: &str,
+) -> Result<DateTime<Utc>, sqlx::Error> {
+    let code = format!("{:06}", rand::thread_rng().gen_range(0..1_000_000));
+    let code_hash = bcrypt::hash(&code).unwrap();
+
+    let expires_at = sqlx::query_scalar!(
+        "INSERT INTO restaurant_email_verifications (restaurant_id,email,code_hash,expires_at)
+        VALUES ($1,$2,$3,NOW() + make_interval(mins => $4))
+        ON CONFLICT (restaurant_id) DO UPDATE
+        SET email = EXCLUDED.email, code_hash = EXCLUDED.code_hash, attempts = 0,
+        expires_at = EXCLUDED.expires_at, created_at = NOW()
+        RETURNING expires_at",
+        restaurant_id,
+        email,
+        code_hash,
+        VERIFICATION_CODE_TTL_MINUTES
+    )
+    .fetch_one(db)
+    .await?;
+
+    send_mail(
+        email,
+        "Verify your restaurant email",
+        &format!(
+            "Your verification code is {}. It expires in {} minutes.",
+            code, VERIFICATION_CODE_TTL_MINUTES
+        ),
+    )
+    .await;
+
+    Ok(expires_at)
+}
@@ -1,2 +1,3 @@
 pub mod restaurants_controller;
 pub mod restaurants_dto;
+pub mod restaurants_service;
@@ -1,49 +1,202 @@
 use std::sync::Arc;
 
 use crate::{
+    common::{
+        contact::{is_known_country, is_valid_email, normalize_email, normalize_phone},
+        geo::parse_point,
+        locale::{normalize_locale, RequestLocales},
+        slug::is_valid_slug,
+    },
     modules::{
-        restaurants::restaurants_dto::{CreateRestaurant, Restaurant},
+        restaurant_delivery_zones::restaurant_delivery_zones_service::find_delivery_zones_for_point,
+        restaurant_photos::restaurant_photos_controller::find_restaurant_photos,
+        restaurant_verifications::restaurant_verifications_service::send_restaurant_verification_code,
+        restaurants::restaurants_dto::{CreateRestaurant, Restaurant, RestaurantDetails},
         shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
-        users::users_dto::User, files::files_controller::delete_file,
+        translations::translations_service::{
+            translate_texts, TranslatableText, TranslationTarget,
+        },
+        users::users_dto::User,
     },
     AppState,
 };
 use axum::{
     extract::{Extension, Json, Path, Query, State},
     http::StatusCode,
-    };
+    response::{IntoResponse, Redirect, Response},
+};
+use sqlx::{Pool, Postgres};
+
+use super::{
+    restaurants_dto::{
+        MyRestaurantsFilters, PriceFilters, RestaurantDeliveryFilters, RestaurantSortEnum,
+        RestaurantSortInput, RestaurantUser, TopRestaurantsFilters, UpdateRestaurantCurrency,
+        UpdateRestaurantDescription, UpdateRestaurantSlug, UpdateRestaurantTimezone,
+    },
+    restaurants_service::{
+        can_manage_restaurant, generate_restaurant_slug, is_valid_currency, is_valid_timezone,
+        refresh_restaurant_price_level, resolve_restaurant, RestaurantLookup,
+        RESERVED_RESTAURANT_SLUGS,
+    },
+};
 
-use super::restaurants_dto::RestaurantUser;
+async fn translate_restaurant_users(
+    db: &Pool<Postgres>,
+    locales: &RequestLocales,
+    restaurants: &mut [RestaurantUser],
+) -> Result<(), sqlx::Error> {
+    let texts = restaurants
+        .iter_mut()
+        .map(|restaurant| TranslatableText {
+            id: restaurant.restaurant_id,
+            default_locale: &restaurant.default_locale,
+            name: &mut restaurant.name,
+            description: Some(&mut restaurant.description),
+        })
+        .collect();
+
+    translate_texts(db, TranslationTarget::Restaurant, locales, texts).await
+}
 
 pub async fn get_restaurants(
     State(state): State<Arc<AppState>>,
     pagination_input: Query<PaginationInput>,
+    sort_input: Query<RestaurantSortInput>,
+    delivery_filters: Query<RestaurantDeliveryFilters>,
+    price_filters: Query<PriceFilters>,
+    locales: RequestLocales,
+    current_user: Option<Extension<Arc<User>>>,
 ) -> AppResult<PaginatedList<RestaurantUser>> {
+    if !price_filters.is_valid() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "price_level must be between 1 and 3 and max_price must not be negative and needs a currency",
+            ),
+        );
+    }
+
+    if sort_input.sort == RestaurantSortEnum::Distance
+        && (sort_input.lat.is_none() || sort_input.lng.is_none())
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("lat and lng are required to sort by distance"),
+        );
+    }
+
+    // Zones are matched in Rust, the query only gets the resulting ids.
+    let delivering_restaurant_ids = match delivery_filters.delivers_to.as_deref() {
+        Some(delivers_to) => {
+            let Some(point) = parse_point(delivers_to) else {
+                return AppResult::Error(
+                    StatusCode::BAD_REQUEST,
+                    String::from("delivers_to must be lat,lng"),
+                );
+            };
+            match find_delivery_zones_for_point(&state.db, point, None).await {
+                Ok(zones) => Some(
+                    zones
+                        .iter()
+                        .map(|zone| zone.restaurant_id as i32)
+                        .collect::<Vec<i32>>(),
+                ),
+                Err(_) => {
+                    return AppResult::Error(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        String::from("Something went wrong"),
+                    )
+                }
+            }
+        }
+        None => None,
+    };
+
+    let current_user_id = current_user.map(|Extension(user)| user.user_id);
     let PaginationInput { page, page_size } = pagination_input.0;
     let offset = (page.saturating_sub(1)) * page_size;
     let res = sqlx::query_as!(
         RestaurantUser,
-        "SELECT 
+        r#"SELECT 
         restaurants.restaurant_id, 
         restaurants.name, 
+        restaurants.slug,
         restaurants.user_id,
         restaurants.location,       
         restaurants.cover_image_uri,       
         restaurants.phone,       
         restaurants.email,       
-        users.email AS user_email
+        users.email AS user_email,
+        restaurants.rating_average,
+        restaurants.rating_count,
+        restaurants.rating_histogram,
+        restaurants.city,
+        restaurants.category,
+        restaurants.latitude,
+        restaurants.longitude,
+        restaurants.score,
+        restaurants.restaurant_chain_id,
+        restaurants.average_price,
+        restaurants.price_level,
+        restaurants.is_verified,
+        restaurants.is_published,
+        restaurants.timezone,
+        restaurants.currency,
+        restaurants.description,
+        restaurants.default_locale,
+        restaurants.created_at,
+        haversine_km($4, $5, restaurants.latitude, restaurants.longitude) AS distance_km,
+        EXISTS (
+            SELECT 1 FROM favorite_restaurants
+            WHERE favorite_restaurants.restaurant_id = restaurants.restaurant_id
+            AND favorite_restaurants.user_id = $6
+        ) AS "is_favorite!"
         FROM restaurants
-        JOIN users ON restaurants.user_id = users.user_id        
-        LIMIT $1 OFFSET $2",
+        JOIN users ON restaurants.user_id = users.user_id
+        WHERE restaurants.deleted_at IS NULL AND restaurants.is_published = true
+        AND ($7::INTEGER[] IS NULL OR restaurants.restaurant_id = ANY($7))
+        AND ($8::SMALLINT IS NULL OR restaurants.price_level = $8)
+        AND ($9::FLOAT IS NULL OR (restaurants.currency = $10 AND restaurants.average_price <= $9))
+        ORDER BY
+        CASE WHEN $3 = 'score' THEN restaurants.score END DESC,
+        CASE WHEN $3 = 'rating' THEN restaurants.rating_average END DESC,
+        CASE WHEN $3 = 'rating' THEN restaurants.rating_count END DESC,
+        CASE WHEN $3 = 'newest' THEN restaurants.created_at END DESC,
+        CASE WHEN $3 = 'distance' THEN haversine_km($4, $5, restaurants.latitude, restaurants.longitude) END ASC NULLS LAST,
+        restaurants.restaurant_id ASC
+        LIMIT $1 OFFSET $2"#,
         page_size,
-        offset
+        offset,
+        sort_input.sort.as_str(),
+        sort_input.lat,
+        sort_input.lng,
+        current_user_id,
+        delivering_restaurant_ids.as_deref(),
+        price_filters.price_level,
+        price_filters.max_price,
+        price_filters.currency()
     )
     .fetch_all(&state.db)
     .await;
+    let res = match res {
+        Ok(mut restaurants) => translate_restaurant_users(&state.db, &locales, &mut restaurants)
+            .await
+            .map(|_| restaurants),
+        Err(err) => Err(err),
+    };
 
-    let count: i64 = match sqlx::query_scalar!("SELECT COUNT (restaurant_id) FROM restaurants",)
-        .fetch_one(&state.db)
-        .await
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (restaurant_id) FROM restaurants WHERE deleted_at IS NULL AND is_published = true
+        AND ($1::INTEGER[] IS NULL OR restaurant_id = ANY($1))
+        AND ($2::SMALLINT IS NULL OR price_level = $2)
+        AND ($3::FLOAT IS NULL OR (currency = $4 AND average_price <= $3))",
+        delivering_restaurant_ids.as_deref(),
+        price_filters.price_level,
+        price_filters.max_price,
+        price_filters.currency()
+    )
+    .fetch_one(&state.db)
+    .await
     {
         Ok(count) => count.unwrap_or(0),
         Err(_) => 0,
@@ -64,26 +217,130 @@ pub async fn get_restaurants(
     };
 }
 
+pub async fn get_top_restaurants(
+    State(state): State<Arc<AppState>>,
+    pagination_input: Query<PaginationInput>,
+    filters_input: Query<TopRestaurantsFilters>,
+    locales: RequestLocales,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<PaginatedList<RestaurantUser>> {
+    let current_user_id = current_user.map(|Extension(user)| user.user_id);
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        RestaurantUser,
+        r#"SELECT
+        restaurants.restaurant_id,
+        restaurants.name,
+        restaurants.slug,
+        restaurants.user_id,
+        restaurants.location,
+        restaurants.cover_image_uri,
+        restaurants.phone,
+        restaurants.email,
+        users.email AS user_email,
+        restaurants.rating_average,
+        restaurants.rating_count,
+        restaurants.rating_histogram,
+        restaurants.city,
+        restaurants.category,
+        restaurants.latitude,
+        restaurants.longitude,
+        restaurants.score,
+        restaurants.restaurant_chain_id,
+        restaurants.average_price,
+        restaurants.price_level,
+        restaurants.is_verified,
+        restaurants.is_published,
+        restaurants.timezone,
+        restaurants.currency,
+        restaurants.description,
+        restaurants.default_locale,
+        restaurants.created_at,
+        NULL::DOUBLE PRECISION AS distance_km,
+        EXISTS (
+            SELECT 1 FROM favorite_restaurants
+            WHERE favorite_restaurants.restaurant_id = restaurants.restaurant_id
+            AND favorite_restaurants.user_id = $5
+        ) AS "is_favorite!"
+        FROM restaurants
+        JOIN users ON restaurants.user_id = users.user_id
+        WHERE restaurants.deleted_at IS NULL AND restaurants.is_published = true
+        AND ($3::TEXT IS NULL OR LOWER(restaurants.city) = LOWER($3))
+        AND ($4::TEXT IS NULL OR LOWER(restaurants.category) = LOWER($4))
+        ORDER BY restaurants.score DESC, restaurants.restaurant_id ASC
+        LIMIT $1 OFFSET $2"#,
+        page_size,
+        offset,
+        filters_input.city,
+        filters_input.category,
+        current_user_id
+    )
+    .fetch_all(&state.db)
+    .await;
+    let res = match res {
+        Ok(mut restaurants) => translate_restaurant_users(&state.db, &locales, &mut restaurants)
+            .await
+            .map(|_| restaurants),
+        Err(err) => Err(err),
+    };
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (restaurant_id) FROM restaurants
+        WHERE deleted_at IS NULL AND is_published = true
+        AND ($1::TEXT IS NULL OR LOWER(city) = LOWER($1))
+        AND ($2::TEXT IS NULL OR LOWER(category) = LOWER($2))",
+        filters_input.city,
+        filters_input.category
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(restaurants) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: restaurants,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
 pub async fn get_my_restaurants(
     State(state): State<Arc<AppState>>,
     Extension(current_user): Extension<Arc<User>>,
     pagination_input: Query<PaginationInput>,
+    filters: Query<MyRestaurantsFilters>,
 ) -> AppResult<PaginatedList<Restaurant>> {
     let PaginationInput { page, page_size } = pagination_input.0;
     let offset = (page.saturating_sub(1)) * page_size;
     let res = sqlx::query_as!(
         Restaurant,
-        "SELECT restaurant_id,name,user_id,location,cover_image_uri,phone,email from restaurants where user_id = $1 LIMIT $2 OFFSET $3",
+        "SELECT restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at from restaurants
+        where user_id = $1 AND deleted_at IS NULL AND ($4::INTEGER IS NULL OR restaurant_chain_id = $4)
+        ORDER BY restaurant_chain_id NULLS LAST, restaurant_id
+        LIMIT $2 OFFSET $3",
         current_user.user_id,
         page_size,
-        offset
+        offset,
+        filters.restaurant_chain_id
     )
     .fetch_all(&state.db)
     .await;
 
     let count: i64 = match sqlx::query_scalar!(
-        "SELECT COUNT (restaurant_id) FROM restaurants where user_id = $1",
-        current_user.user_id
+        "SELECT COUNT (restaurant_id) FROM restaurants where user_id = $1 AND deleted_at IS NULL AND ($2::INTEGER IS NULL OR restaurant_chain_id = $2)",
+        current_user.user_id,
+        filters.restaurant_chain_id
     )
     .fetch_one(&state.db)
     .await
@@ -112,49 +369,541 @@ pub async fn create_restaurant(
     Extension(current_user): Extension<Arc<User>>,
     Json(create_restaurant_dto): Json<CreateRestaurant>,
 ) -> AppResult<Restaurant> {
+    let has_valid_coordinates = match (
+        create_restaurant_dto.latitude,
+        create_restaurant_dto.longitude,
+    ) {
+        (Some(latitude), Some(longitude)) => {
+            (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)
+        }
+        (None, None) => true,
+        _ => false,
+    };
+
+    if !has_valid_coordinates {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("latitude and longitude must be provided together and be valid"),
+        );
+    }
+
+    let email = normalize_email(&create_restaurant_dto.email);
+
+    if !is_valid_email(&email) {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("email must be a valid email address"),
+        );
+    }
+
+    let country = create_restaurant_dto
+        .country
+        .as_deref()
+        .or(state.env.default_phone_country.as_deref());
+
+    if country.is_some_and(|country| !is_known_country(country)) {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("country must be a supported ISO 3166 alpha-2 code"),
+        );
+    }
+
+    let Some(phone) = normalize_phone(&create_restaurant_dto.phone, country) else {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("phone must be a valid international number or a national number of the given country"),
+        );
+    };
+
+    if let Some(timezone) = &create_restaurant_dto.timezone {
+        match is_valid_timezone(&state.db, timezone).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return AppResult::Error(
+                    StatusCode::BAD_REQUEST,
+                    String::from("timezone must be an IANA time zone name such as Europe/Paris"),
+                )
+            }
+            Err(_) => {
+                return AppResult::Error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    String::from("Something went wrong"),
+                )
+            }
+        }
+    }
+
+    let currency = create_restaurant_dto
+        .currency
+        .as_deref()
+        .map(|currency| currency.trim().to_uppercase());
+
+    if let Some(currency) = &currency {
+        match is_valid_currency(&state.db, currency).await {
+            Ok(true) => {}
+            Ok(false) => {
+                return AppResult::Error(
+                    StatusCode::BAD_REQUEST,
+                    String::from("currency must be a supported ISO 4217 code"),
+                )
+            }
+            Err(_) => {
+                return AppResult::Error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    String::from("Something went wrong"),
+                )
+            }
+        }
+    }
+
+    let default_locale = match create_restaurant_dto.default_locale.as_deref() {
+        Some(default_locale) => match normalize_locale(default_locale) {
+            Some(default_locale) => Some(default_locale),
+            None => {
+                return AppResult::Error(
+                    StatusCode::BAD_REQUEST,
+                    String::from("default_locale must be a language tag such as fr or pt-BR"),
+                )
+            }
+        },
+        None => None,
+    };
+
+    // Two restaurants created at once may pick the same free slug, the one
+    // that loses the race picks again.
+    let mut attempts = 0;
+    let res = loop {
+        let slug = match generate_restaurant_slug(&state.db, &create_restaurant_dto.name).await {
+            Ok(slug) => slug,
+            Err(_) => {
+                return AppResult::Error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    String::from("Something went wrong"),
+                )
+            }
+        };
+
+        let res = sqlx::query_as!(
+            Restaurant,
+            "INSERT INTO restaurants (name,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,slug,timezone,currency,description,default_locale) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,COALESCE($12,'UTC'),COALESCE($13,'EUR'),COALESCE($14,''),COALESCE($15,'en')) RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
+            create_restaurant_dto.name,
+            current_user.user_id,
+            create_restaurant_dto.location,
+            create_restaurant_dto.cover_image_uri,
+            phone,
+            email,
+            create_restaurant_dto.city,
+            create_restaurant_dto.category,
+            create_restaurant_dto.latitude,
+            create_restaurant_dto.longitude,
+            slug,
+            create_restaurant_dto.timezone,
+            currency,
+            create_restaurant_dto.description,
+            default_locale
+        )
+        .fetch_one(&state.db)
+        .await;
+
+        match res {
+            Err(sqlx::Error::Database(err)) if err.is_unique_violation() && attempts < 3 => {
+                attempts += 1
+            }
+            res => break res,
+        }
+    };
+
+    // The owner can ask for a new code if this one never arrives.
+    if let Ok(restaurant) = &res {
+        if let Err(err) = send_restaurant_verification_code(
+            &state.db,
+            restaurant.restaurant_id as i32,
+            &restaurant.email,
+        )
+        .await
+        {
+            tracing::error!("Verification code sending failed: {:?}", err);
+        }
+    }
+
+    return match res {
+        Ok(restaurant) => AppResult::Result(StatusCode::CREATED, restaurant),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    };
+}
+
+pub async fn get_restaurant(
+    State(state): State<Arc<AppState>>,
+    Path(restaurant_id): Path<String>,
+    locales: RequestLocales,
+    current_user: Option<Extension<Arc<User>>>,
+) -> Response {
+    let restaurant_id = match resolve_restaurant(
+        &state.db,
+        &restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(Some(RestaurantLookup {
+            redirect_to: Some(slug),
+            ..
+        })) => return Redirect::permanent(&format!("/api/restaurants/{}", slug)).into_response(),
+        Ok(Some(RestaurantLookup { restaurant_id, .. })) => restaurant_id,
+        Ok(None) => {
+            return AppResult::<()>::Error(
+                StatusCode::NOT_FOUND,
+                String::from("Restaurant not found!"),
+            )
+            .into_response()
+        }
+        Err(_) => {
+            return AppResult::<()>::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+            .into_response()
+        }
+    };
+
     let res = sqlx::query_as!(
         Restaurant,
-        "INSERT INTO restaurants (name,user_id,location,cover_image_uri,phone,email) VALUES ($1,$2,$3,$4,$5,$6) RETURNING restaurant_id,name,user_id,location,cover_image_uri,phone,email",
-        create_restaurant_dto.name,
-        current_user.user_id,
-        create_restaurant_dto.location,
-        create_restaurant_dto.cover_image_uri,
-        create_restaurant_dto.phone,
-        create_restaurant_dto.email
+        "UPDATE restaurants SET view_count = view_count + 1
+        WHERE restaurant_id = $1 AND deleted_at IS NULL
+        RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    let res = match res {
+        Ok(Some(mut restaurant)) => {
+            let text = TranslatableText {
+                id: restaurant.restaurant_id,
+                default_locale: &restaurant.default_locale,
+                name: &mut restaurant.name,
+                description: Some(&mut restaurant.description),
+            };
+            match translate_texts(
+                &state.db,
+                TranslationTarget::Restaurant,
+                &locales,
+                vec![text],
+            )
+            .await
+            {
+                Ok(()) => find_restaurant_photos(&state.db, restaurant_id)
+                    .await
+                    .map(|photos| Some(RestaurantDetails { restaurant, photos })),
+                Err(err) => Err(err),
+            }
+        }
+        Ok(None) => Ok(None),
+        Err(err) => Err(err),
+    };
+
+    match res {
+        Ok(Some(restaurant)) => AppResult::Result(StatusCode::OK, restaurant),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+    .into_response()
+}
+
+pub async fn update_restaurant_slug(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(update_restaurant_slug_dto): Json<UpdateRestaurantSlug>,
+) -> AppResult<Restaurant> {
+    let slug = update_restaurant_slug_dto.slug.trim().to_lowercase();
+
+    if !is_valid_slug(&slug) || RESERVED_RESTAURANT_SLUGS.contains(&slug.as_str()) {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from(
+                "slug must be 3 to 80 lowercase letters, digits or single dashes and not only digits",
+            ),
+        );
+    }
+
+    // Slugs that used to belong to another restaurant stay reserved for its
+    // redirects.
+    let is_taken = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM restaurants WHERE slug = $1 AND restaurant_id <> $2)
+        OR EXISTS (SELECT 1 FROM restaurant_slug_redirects WHERE slug = $1 AND restaurant_id <> $2) AS "is_taken!""#,
+        slug,
+        restaurant_id
     )
     .fetch_one(&state.db)
     .await;
 
-    return match res {
-        Ok(restaurant) => AppResult::Result(StatusCode::CREATED, restaurant),
+    match is_taken {
+        Ok(false) => {}
+        Ok(true) => {
+            return AppResult::Error(StatusCode::CONFLICT, String::from("Slug already taken!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let res: Result<Option<Restaurant>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let current = sqlx::query!(
+            "SELECT slug FROM restaurants WHERE restaurant_id = $1 AND (user_id = $2 OR $3) AND deleted_at IS NULL FOR UPDATE",
+            restaurant_id,
+            current_user.user_id,
+            current_user.role == "Admin"
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(current) = current else {
+            return Ok(None);
+        };
+
+        if current.slug != slug {
+            // Moving back to an old slug turns it from a redirect into the
+            // canonical slug again.
+            sqlx::query!(
+                "DELETE FROM restaurant_slug_redirects WHERE slug = $1",
+                slug
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query!(
+                "INSERT INTO restaurant_slug_redirects (slug,restaurant_id) VALUES ($1,$2)",
+                current.slug,
+                restaurant_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let restaurant = sqlx::query_as!(
+            Restaurant,
+            "UPDATE restaurants SET slug = $2 WHERE restaurant_id = $1 RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
+            restaurant_id,
+            slug
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(restaurant))
+    }
+    .await;
+
+    match res {
+        Ok(Some(restaurant)) => AppResult::Result(StatusCode::OK, restaurant),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
         Err(_) => AppResult::Error(
             StatusCode::INTERNAL_SERVER_ERROR,
             String::from("Something went wrong"),
         ),
-    };
+    }
 }
 
-pub async fn get_restaurant(
+pub async fn update_restaurant_timezone(
     State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
     Path(restaurant_id): Path<i32>,
+    Json(update_restaurant_timezone_dto): Json<UpdateRestaurantTimezone>,
 ) -> AppResult<Restaurant> {
+    let timezone = update_restaurant_timezone_dto.timezone.trim();
+
+    match is_valid_timezone(&state.db, timezone).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("timezone must be an IANA time zone name such as Europe/Paris"),
+            )
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
     let res = sqlx::query_as!(
         Restaurant,
-        "SELECT restaurant_id,name,user_id,location,cover_image_uri,phone,email from restaurants where restaurant_id = $1",
+        "UPDATE restaurants SET timezone = $2 WHERE restaurant_id = $1 AND (user_id = $3 OR $4) AND deleted_at IS NULL RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
         restaurant_id,
+        timezone,
+        current_user.user_id,
+        current_user.role == "Admin"
     )
-    .fetch_one(&state.db)
+    .fetch_optional(&state.db)
     .await;
 
-    return match res {
-        Ok(restaurant) => AppResult::Result(StatusCode::OK, restaurant),
+    match res {
+        Ok(Some(restaurant)) => AppResult::Result(StatusCode::OK, restaurant),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
         Err(_) => AppResult::Error(
             StatusCode::INTERNAL_SERVER_ERROR,
             String::from("Something went wrong"),
         ),
+    }
+}
+
+pub async fn update_restaurant_description(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(update_restaurant_description_dto): Json<UpdateRestaurantDescription>,
+) -> AppResult<Restaurant> {
+    let default_locale = match update_restaurant_description_dto.default_locale.as_deref() {
+        Some(default_locale) => match normalize_locale(default_locale) {
+            Some(default_locale) => Some(default_locale),
+            None => {
+                return AppResult::Error(
+                    StatusCode::BAD_REQUEST,
+                    String::from("default_locale must be a language tag such as fr or pt-BR"),
+                )
+            }
+        },
+        None => None,
     };
+
+    let res = sqlx::query_as!(
+        Restaurant,
+        "UPDATE restaurants SET description = COALESCE($2, description), default_locale = COALESCE($3, default_locale) WHERE restaurant_id = $1 AND (user_id = $4 OR $5) AND deleted_at IS NULL RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
+        restaurant_id,
+        update_restaurant_description_dto.description,
+        default_locale,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant)) => AppResult::Result(StatusCode::OK, restaurant),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
 }
 
+// Prices are kept as they are, so the currency can only change when that
+// does not alter them: outside a chain, and for a currency with the same
+// number of decimals unless the restaurant has no menu items yet.
+pub async fn update_restaurant_currency(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(update_restaurant_currency_dto): Json<UpdateRestaurantCurrency>,
+) -> AppResult<Restaurant> {
+    let currency = update_restaurant_currency_dto
+        .currency
+        .trim()
+        .to_uppercase();
+
+    match is_valid_currency(&state.db, &currency).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("currency must be a supported ISO 4217 code"),
+            )
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let res = sqlx::query!(
+        r#"SELECT restaurants.restaurant_chain_id IS NOT NULL AS "is_in_chain!",
+        current_currency.exponent <> new_currency.exponent AND EXISTS (
+            SELECT 1 FROM restaurant_menu_items rmi
+            JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+            WHERE rm.restaurant_id = restaurants.restaurant_id
+        ) AS "changes_prices!"
+        FROM restaurants
+        JOIN currencies current_currency ON current_currency.code = restaurants.currency
+        JOIN currencies new_currency ON new_currency.code = $2
+        WHERE restaurants.restaurant_id = $1 AND (restaurants.user_id = $3 OR $4)
+        AND restaurants.deleted_at IS NULL"#,
+        restaurant_id,
+        currency,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant)) if restaurant.is_in_chain => {
+            return AppResult::Error(
+                StatusCode::CONFLICT,
+                String::from("Restaurants in a chain use the chain currency!"),
+            )
+        }
+        Ok(Some(restaurant)) if restaurant.changes_prices => {
+            return AppResult::Error(
+                StatusCode::CONFLICT,
+                String::from(
+                    "Currency has a different number of decimals than the existing prices!",
+                ),
+            )
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let res = sqlx::query_as!(
+        Restaurant,
+        "UPDATE restaurants SET currency = $2 WHERE restaurant_id = $1 AND deleted_at IS NULL RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
+        restaurant_id,
+        currency
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant)) => {
+            refresh_restaurant_price_level(&state.db, restaurant_id).await;
+            AppResult::Result(StatusCode::OK, restaurant)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Only moves the restaurant to the trash; the purge task removes it and its
+// files once the retention period is over.
 pub async fn delete_restaurant(
     State(state): State<Arc<AppState>>,
     Extension(current_user): Extension<Arc<User>>,
@@ -164,7 +913,7 @@ pub async fn delete_restaurant(
         "Admin" => 
             sqlx::query_as!(
                 Restaurant,
-                "DELETE FROM restaurants Where restaurant_id = $1 RETURNING restaurant_id,name,user_id,location,cover_image_uri,phone,email",
+                "UPDATE restaurants SET deleted_at = NOW() Where restaurant_id = $1 AND deleted_at IS NULL RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
                 restaurant_id
             )
             .fetch_one(&state.db)
@@ -173,7 +922,7 @@ pub async fn delete_restaurant(
 
         _ => sqlx::query_as!(
             Restaurant,
-            "DELETE FROM restaurants Where restaurant_id = $1 and user_id = $2 RETURNING restaurant_id,name,user_id,location,cover_image_uri,phone,email",
+            "UPDATE restaurants SET deleted_at = NOW() Where restaurant_id = $1 and user_id = $2 AND deleted_at IS NULL RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
             restaurant_id,
             current_user.user_id
         )
@@ -182,13 +931,85 @@ pub async fn delete_restaurant(
     };
 
     return match res {
-        Ok(restaurant) => {
-            delete_file(restaurant.cover_image_uri.clone());
-
-            AppResult::Result(StatusCode::OK, restaurant)},
+        Ok(restaurant) => AppResult::Result(StatusCode::OK, restaurant),
         Err(_) => AppResult::Error(
             StatusCode::INTERNAL_SERVER_ERROR,
             String::from("Something went wrong"),
         ),
     };
 }
+
+pub async fn publish_restaurant(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+) -> AppResult<Restaurant> {
+    let is_verified = sqlx::query_scalar!(
+        "SELECT is_verified FROM restaurants WHERE restaurant_id = $1 AND (user_id = $2 OR $3) AND deleted_at IS NULL",
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match is_verified {
+        Ok(Some(true)) => {}
+        Ok(Some(false)) => {
+            return AppResult::Error(
+                StatusCode::CONFLICT,
+                String::from("Verify the restaurant email before publishing!"),
+            )
+        }
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    set_restaurant_published(&state, restaurant_id, true).await
+}
+
+pub async fn unpublish_restaurant(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+) -> AppResult<Restaurant> {
+    match can_manage_restaurant(&state.db, restaurant_id, &current_user).await {
+        Ok(true) => set_restaurant_published(&state, restaurant_id, false).await,
+        Ok(false) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+async fn set_restaurant_published(
+    state: &AppState,
+    restaurant_id: i32,
+    is_published: bool,
+) -> AppResult<Restaurant> {
+    let res = sqlx::query_as!(
+        Restaurant,
+        "UPDATE restaurants SET is_published = $2 WHERE restaurant_id = $1 AND deleted_at IS NULL RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
+        restaurant_id,
+        is_published
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant)) => AppResult::Result(StatusCode::OK, restaurant),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
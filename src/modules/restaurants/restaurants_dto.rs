@@ -1,6 +1,9 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::modules::restaurant_photos::restaurant_photos_dto::RestaurantPhoto;
+
 #[derive(Deserialize)]
 pub struct CreateRestaurant {
     pub name: String,
@@ -8,29 +11,193 @@ pub struct CreateRestaurant {
     pub cover_image_uri: String,
     pub phone: String,
     pub email: String,
+    pub city: Option<String>,
+    pub category: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    // ISO 3166 alpha-2 code used to read a phone number without a + prefix.
+    pub country: Option<String>,
+    pub timezone: Option<String>,
+    // ISO 4217 code, EUR when omitted.
+    pub currency: Option<String>,
+    pub description: Option<String>,
+    // "en" when omitted.
+    pub default_locale: Option<String>,
 }
 
 #[derive(Serialize, FromRow)]
 pub struct Restaurant {
     pub restaurant_id: i64,
     pub name: String,
+    pub slug: String,
     pub user_id: i64,
     pub location: String,
     pub cover_image_uri: String,
     pub phone: String,
     pub email: String,
+    pub rating_average: f64,
+    pub rating_count: i32,
+    pub rating_histogram: Vec<i32>,
+    pub city: Option<String>,
+    pub category: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub score: f64,
+    pub restaurant_chain_id: Option<i32>,
+    pub average_price: Option<f64>,
+    // 1 to 3, rendered as € to €€€.
+    pub price_level: Option<i16>,
+    // Shown as a badge once the restaurant email has been confirmed.
+    pub is_verified: bool,
+    pub is_published: bool,
+    // IANA name, menu schedules are evaluated in it.
+    pub timezone: String,
+    // ISO 4217 code, menu prices are in its minor units.
+    pub currency: String,
+    pub description: String,
+    // Language of name and description, and of the menus when no
+    // translation matches the request.
+    pub default_locale: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+pub struct RestaurantDetails {
+    #[serde(flatten)]
+    pub restaurant: Restaurant,
+    pub photos: Vec<RestaurantPhoto>,
 }
 
 #[derive(Serialize, FromRow)]
 pub struct RestaurantUser {
     pub restaurant_id: i64,
     pub name: String,
+    pub slug: String,
     pub user_id: i64,
     pub location: String,
     pub phone: String,
     pub email: String,
     pub cover_image_uri: String,
     pub user_email: String,
+    pub rating_average: f64,
+    pub rating_count: i32,
+    pub rating_histogram: Vec<i32>,
+    pub city: Option<String>,
+    pub category: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub score: f64,
+    pub restaurant_chain_id: Option<i32>,
+    pub average_price: Option<f64>,
+    // 1 to 3, rendered as € to €€€.
+    pub price_level: Option<i16>,
+    // Shown as a badge once the restaurant email has been confirmed.
+    pub is_verified: bool,
+    pub is_published: bool,
+    // IANA name, menu schedules are evaluated in it.
+    pub timezone: String,
+    // ISO 4217 code, menu prices are in its minor units.
+    pub currency: String,
+    pub description: String,
+    // Language of name and description, and of the menus when no
+    // translation matches the request.
+    pub default_locale: String,
+    pub created_at: DateTime<Utc>,
+    pub distance_km: Option<f64>,
+    pub is_favorite: bool,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantSlug {
+    pub slug: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantTimezone {
+    pub timezone: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateRestaurantCurrency {
+    pub currency: String,
+}
+
+// Only the fields sent are changed.
+#[derive(Deserialize)]
+pub struct UpdateRestaurantDescription {
+    pub description: Option<String>,
+    pub default_locale: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RestaurantSortEnum {
+    #[default]
+    Score,
+    Rating,
+    Newest,
+    Distance,
+}
+
+impl RestaurantSortEnum {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RestaurantSortEnum::Score => "score",
+            RestaurantSortEnum::Rating => "rating",
+            RestaurantSortEnum::Newest => "newest",
+            RestaurantSortEnum::Distance => "distance",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RestaurantSortInput {
+    #[serde(default)]
+    pub sort: RestaurantSortEnum,
+    pub lat: Option<f64>,
+    pub lng: Option<f64>,
+}
+
+#[derive(Deserialize)]
+pub struct TopRestaurantsFilters {
+    pub city: Option<String>,
+    pub category: Option<String>,
+}
+
+// Restaurants are matched on their average price, meals on their own price.
+#[derive(Deserialize)]
+pub struct PriceFilters {
+    pub price_level: Option<i16>,
+    // In major units, only prices in this currency are compared with it.
+    pub max_price: Option<f64>,
+    pub currency: Option<String>,
+}
+
+impl PriceFilters {
+    pub fn is_valid(&self) -> bool {
+        self.price_level
+            .is_none_or(|price_level| (1..=3).contains(&price_level))
+            && self
+                .max_price
+                .is_none_or(|max_price| max_price >= 0.0 && self.currency.is_some())
+    }
+
+    pub fn currency(&self) -> Option<String> {
+        self.currency
+            .as_deref()
+            .map(|currency| currency.trim().to_uppercase())
+    }
+}
+
+// delivers_to is "lat,lng".
+#[derive(Deserialize)]
+pub struct RestaurantDeliveryFilters {
+    pub delivers_to: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MyRestaurantsFilters {
+    pub restaurant_chain_id: Option<i32>,
 }
 
 #[derive(Deserialize)]
@@ -42,3 +209,62 @@ pub struct RestaurantFilters {
 fn default_status() -> bool {
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_levels_run_from_one_to_three() {
+        for (price_level, is_valid) in [(0, false), (1, true), (2, true), (3, true), (4, false)] {
+            let price_filters = PriceFilters {
+                price_level: Some(price_level),
+                max_price: None,
+                currency: None,
+            };
+            assert_eq!(price_filters.is_valid(), is_valid, "{}", price_level);
+        }
+    }
+
+    #[test]
+    fn max_price_must_not_be_negative() {
+        for (max_price, is_valid) in [(-0.5, false), (0.0, true), (12.5, true)] {
+            let price_filters = PriceFilters {
+                price_level: None,
+                max_price: Some(max_price),
+                currency: Some(String::from("EUR")),
+            };
+            assert_eq!(price_filters.is_valid(), is_valid, "{}", max_price);
+        }
+    }
+
+    #[test]
+    fn max_price_needs_a_currency() {
+        let price_filters = PriceFilters {
+            price_level: None,
+            max_price: Some(12.5),
+            currency: None,
+        };
+        assert!(!price_filters.is_valid());
+    }
+
+    #[test]
+    fn currency_is_normalized() {
+        let price_filters = PriceFilters {
+            price_level: None,
+            max_price: None,
+            currency: Some(String::from(" jpy ")),
+        };
+        assert_eq!(price_filters.currency().as_deref(), Some("JPY"));
+    }
+
+    #[test]
+    fn missing_price_filters_are_valid() {
+        let price_filters = PriceFilters {
+            price_level: None,
+            max_price: None,
+            currency: None,
+        };
+        assert!(price_filters.is_valid());
+    }
+}
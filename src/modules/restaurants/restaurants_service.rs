@@ -0,0 +1,309 @@
+use std::collections::HashSet;
+
+use axum::http::StatusCode;
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    common::slug::{is_valid_slug, slugify, with_collision_suffix, MAX_SLUG_LENGTH},
+    modules::{shared::shared_dto::AppResult, users::users_dto::User},
+};
+
+// Static segments under /api/restaurants that a slug would be shadowed by.
+pub const RESERVED_RESTAURANT_SLUGS: [&str; 2] = ["me", "top"];
+// Up to "-9999999", far more restaurants than will ever share a name.
+const MAX_SLUG_SUFFIX_LENGTH: usize = 8;
+
+pub struct RestaurantLookup {
+    pub restaurant_id: i32,
+    // Set when the caller used a slug the restaurant has since moved away from.
+    pub redirect_to: Option<String>,
+}
+
+pub async fn generate_restaurant_slug(
+    db: &Pool<Postgres>,
+    name: &str,
+) -> Result<String, sqlx::Error> {
+    let base = match slugify(name) {
+        slug if is_valid_slug(&slug) => slug,
+        slug if slug.is_empty() => String::from("restaurant"),
+        slug => format!("restaurant-{}", slug),
+    };
+
+    // Suffixed candidates may cut the end of the base, so every slug sharing
+    // the part they all keep is loaded. Old slugs stay taken too, so shared
+    // links never start pointing to a different restaurant.
+    let mut prefix = base.clone();
+    prefix.truncate(MAX_SLUG_LENGTH - MAX_SLUG_SUFFIX_LENGTH);
+    let prefix = prefix.trim_end_matches('-');
+    let taken: HashSet<String> = sqlx::query_scalar!(
+        r#"SELECT slug AS "slug!" FROM restaurants WHERE slug LIKE $1 || '%'
+        UNION
+        SELECT slug FROM restaurant_slug_redirects WHERE slug LIKE $1 || '%'"#,
+        prefix
+    )
+    .fetch_all(db)
+    .await?
+    .into_iter()
+    .collect();
+
+    Ok(with_collision_suffix(&base, |slug| {
+        taken.contains(slug) || RESERVED_RESTAURANT_SLUGS.contains(&slug)
+    }))
+}
+
+// Restaurants the viewer is not allowed to see resolve to None.
+pub async fn resolve_restaurant(
+    db: &Pool<Postgres>,
+    id_or_slug: &str,
+    viewer: Option<&User>,
+) -> Result<Option<RestaurantLookup>, sqlx::Error> {
+    let lookup = match id_or_slug.parse::<i32>() {
+        Ok(restaurant_id) => Some(RestaurantLookup {
+            restaurant_id,
+            redirect_to: None,
+        }),
+        Err(_) => find_restaurant_by_slug(db, id_or_slug).await?,
+    };
+
+    let Some(lookup) = lookup else {
+        return Ok(None);
+    };
+    if !is_restaurant_visible(db, lookup.restaurant_id, viewer).await? {
+        return Ok(None);
+    }
+
+    Ok(Some(lookup))
+}
+
+async fn find_restaurant_by_slug(
+    db: &Pool<Postgres>,
+    slug: &str,
+) -> Result<Option<RestaurantLookup>, sqlx::Error> {
+    let res = sqlx::query!(
+        r#"SELECT restaurant_id AS "restaurant_id!", NULL::VARCHAR AS redirect_to FROM restaurants WHERE slug = $1 AND deleted_at IS NULL
+        UNION ALL
+        SELECT restaurants.restaurant_id, restaurants.slug AS redirect_to
+        FROM restaurant_slug_redirects
+        JOIN restaurants ON restaurant_slug_redirects.restaurant_id = restaurants.restaurant_id
+        WHERE restaurant_slug_redirects.slug = $1 AND restaurants.deleted_at IS NULL
+        LIMIT 1"#,
+        slug
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(res.map(|row| RestaurantLookup {
+        restaurant_id: row.restaurant_id,
+        redirect_to: row.redirect_to,
+    }))
+}
+
+// Unpublished restaurants are only visible to the people managing them.
+pub async fn is_restaurant_visible(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+    viewer: Option<&User>,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM restaurants
+            WHERE restaurant_id = $1 AND deleted_at IS NULL
+            AND (is_published = true OR user_id = $2 OR $3)
+        ) AS "is_visible!""#,
+        restaurant_id,
+        viewer.map(|user| user.user_id),
+        viewer.is_some_and(|user| user.role == "Admin")
+    )
+    .fetch_one(db)
+    .await
+}
+
+pub async fn is_valid_timezone(db: &Pool<Postgres>, timezone: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM pg_timezone_names WHERE name = $1) AS "is_valid!""#,
+        timezone
+    )
+    .fetch_one(db)
+    .await
+}
+
+pub async fn is_valid_currency(db: &Pool<Postgres>, currency: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM currencies WHERE code = $1) AS "is_valid!""#,
+        currency
+    )
+    .fetch_one(db)
+    .await
+}
+
+// Minor unit digits of the currency a menu is priced in, None when the menu
+// does not exist.
+pub async fn find_menu_currency_exponent(
+    db: &Pool<Postgres>,
+    restaurant_menu_id: i32,
+) -> Result<Option<i16>, sqlx::Error> {
+    sqlx::query_scalar!("SELECT (menu_money(0, $1)).exponent", restaurant_menu_id)
+        .fetch_one(db)
+        .await
+}
+
+pub async fn find_restaurant_currency_exponent(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+) -> Result<Option<i16>, sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT currencies.exponent FROM restaurants
+        JOIN currencies ON currencies.code = restaurants.currency
+        WHERE restaurants.restaurant_id = $1 AND restaurants.deleted_at IS NULL",
+        restaurant_id
+    )
+    .fetch_optional(db)
+    .await
+}
+
+// Menus and items are only copied between restaurants of the same owner, with
+// their prices as is. None when the target is not one of the owner's
+// restaurants, otherwise whether both use the same currency.
+pub async fn find_sibling_restaurant_currency_match(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+    sibling_restaurant_id: i32,
+) -> Result<Option<bool>, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"SELECT sibling.currency = restaurants.currency AS "is_same_currency!"
+        FROM restaurants
+        JOIN restaurants sibling ON sibling.user_id = restaurants.user_id
+        WHERE restaurants.restaurant_id = $1 AND sibling.restaurant_id = $2
+        AND restaurants.deleted_at IS NULL AND sibling.deleted_at IS NULL"#,
+        restaurant_id,
+        sibling_restaurant_id
+    )
+    .fetch_optional(db)
+    .await
+}
+
+// Admins manage every restaurant, owners only their own. Trashed restaurants
+// can only be restored.
+pub async fn can_manage_restaurant(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+    user: &User,
+) -> Result<bool, sqlx::Error> {
+    let res = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM restaurants
+            WHERE restaurant_id = $1 AND (user_id = $2 OR $3) AND deleted_at IS NULL
+        ) AS "can_manage!""#,
+        restaurant_id,
+        user.user_id,
+        user.role == "Admin"
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(res)
+}
+
+// can_manage_restaurant for handlers, restaurants the user can't manage read as missing.
+pub async fn check_restaurant_access<T>(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+    current_user: &User,
+) -> Result<(), AppResult<T>> {
+    match can_manage_restaurant(db, restaurant_id, current_user).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Restaurant not found!"),
+        )),
+        Err(_) => Err(AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )),
+    }
+}
+
+// Same for a menu, which has to be served by the restaurant in the path.
+pub async fn check_restaurant_menu_access<T>(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+    restaurant_menu_id: i32,
+    current_user: &User,
+) -> Result<(), AppResult<T>> {
+    let res = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM restaurant_menus rm
+            JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+            WHERE rm.restaurant_menu_id = $1 AND rm.restaurant_id = $2
+            AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+            AND (r.user_id = $3 OR $4)
+        ) AS "can_manage!""#,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_one(db)
+    .await;
+
+    match res {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Menu not found!"),
+        )),
+        Err(_) => Err(AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )),
+    }
+}
+
+// Recomputes average_price (in major units) and price_level from the active items a
+// restaurant serves, inherited chain items and branch overrides included.
+// Restaurants are picked by id and/or by a menu they serve.
+async fn refresh_price_levels(
+    db: &Pool<Postgres>,
+    restaurant_id: Option<i32>,
+    restaurant_menu_id: Option<i32>,
+) {
+    let res = sqlx::query!(
+        "UPDATE restaurants r
+        SET (average_price, price_level) = (
+            SELECT AVG(prices.price), price_level_for(AVG(prices.price), r.currency)
+            FROM (
+                SELECT COALESCE(rmio.price_minor, rmi.price_minor) / 10 ^ currencies.exponent AS price
+                FROM restaurant_menus rm
+                JOIN currencies ON currencies.code = r.currency
+                JOIN restaurant_menu_items rmi ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+                LEFT JOIN restaurant_menu_item_overrides rmio
+                ON rmio.restaurant_menu_item_id = rmi.restaurant_menu_item_id AND rmio.restaurant_id = r.restaurant_id
+                WHERE (rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id)
+                AND rm.is_active = true AND rm.deleted_at IS NULL AND rmi.deleted_at IS NULL
+                AND COALESCE(rmio.is_available, true)
+            ) prices
+        )
+        WHERE r.restaurant_id = $1
+        OR EXISTS (
+            SELECT 1 FROM restaurant_menus rm
+            WHERE rm.restaurant_menu_id = $2
+            AND (rm.restaurant_id = r.restaurant_id OR rm.restaurant_chain_id = r.restaurant_chain_id)
+        )",
+        restaurant_id,
+        restaurant_menu_id
+    )
+    .execute(db)
+    .await;
+
+    if let Err(err) = res {
+        tracing::error!("Price level refresh failed: {:?}", err);
+    }
+}
+
+pub async fn refresh_restaurant_price_level(db: &Pool<Postgres>, restaurant_id: i32) {
+    refresh_price_levels(db, Some(restaurant_id), None).await
+}
+
+pub async fn refresh_menu_price_levels(db: &Pool<Postgres>, restaurant_menu_id: i32) {
+    refresh_price_levels(db, None, Some(restaurant_menu_id)).await
+}
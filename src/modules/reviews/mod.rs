@@ -0,0 +1,2 @@
+pub mod reviews_controller;
+pub mod reviews_dto;
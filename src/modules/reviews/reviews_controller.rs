@@ -0,0 +1,844 @@
+use std::sync::Arc;
+
+use crate::{
+    common::word_filter::contains_banned_word,
+    modules::{
+        files::{
+            files_controller::{delete_file, upload_file_name},
+            files_service::find_unreferenced_file_uris,
+        },
+        restaurants::restaurants_service::is_restaurant_visible,
+        shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+use sqlx::{Postgres, Transaction};
+
+use super::reviews_dto::{
+    CreateReview, CreateReviewReport, Review, ReviewModeration, ReviewReply, ReviewReport,
+    ReviewUser, UpdateReview,
+};
+
+fn is_valid_rating(rating: i32) -> bool {
+    (1..=5).contains(&rating)
+}
+
+// Photos are deleted along with the review, so they must be plain uploads.
+fn are_valid_photo_uris(photo_uris: &[String]) -> bool {
+    photo_uris
+        .iter()
+        .all(|photo_uri| upload_file_name(photo_uri).is_some())
+}
+
+// Keeps the rating aggregates on `restaurants` in sync with a single review
+// being added (delta = 1) or removed (delta = -1), so listings never have to
+// scan the reviews table. Hidden reviews must never be counted.
+async fn apply_rating_delta(
+    tx: &mut Transaction<'_, Postgres>,
+    restaurant_id: i64,
+    rating: i32,
+    delta: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        "UPDATE restaurants SET
+        rating_count = rating_count + $3,
+        rating_sum = rating_sum + $2 * $3,
+        rating_histogram[$2] = rating_histogram[$2] + $3
+        WHERE restaurant_id = $1",
+        restaurant_id as i32,
+        rating,
+        delta
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+// The single review updates that take the aggregates from a review rated
+// `previous` to one rated `current`, None meaning it is not counted.
+fn rating_deltas(previous: Option<i32>, current: Option<i32>) -> Vec<(i32, i32)> {
+    if previous == current {
+        return Vec::new();
+    }
+
+    previous
+        .map(|rating| (rating, -1))
+        .into_iter()
+        .chain(current.map(|rating| (rating, 1)))
+        .collect()
+}
+
+// Hidden reviews stay in the table but drop out of the aggregates.
+fn counted_rating(review: &Review) -> Option<i32> {
+    (review.status != "Hidden").then_some(review.rating)
+}
+
+async fn apply_rating_change(
+    tx: &mut Transaction<'_, Postgres>,
+    restaurant_id: i64,
+    previous: Option<i32>,
+    current: Option<i32>,
+) -> Result<(), sqlx::Error> {
+    for (rating, delta) in rating_deltas(previous, current) {
+        apply_rating_delta(tx, restaurant_id, rating, delta).await?;
+    }
+
+    Ok(())
+}
+
+pub async fn get_restaurant_reviews(
+    State(state): State<Arc<AppState>>,
+    pagination_input: Query<PaginationInput>,
+    Path(restaurant_id): Path<i32>,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<PaginatedList<ReviewUser>> {
+    match is_restaurant_visible(
+        &state.db,
+        restaurant_id,
+        current_user.as_ref().map(|Extension(user)| user.as_ref()),
+    )
+    .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        ReviewUser,
+        "SELECT
+        reviews.review_id,
+        reviews.restaurant_id,
+        reviews.user_id,
+        reviews.rating,
+        reviews.content,
+        reviews.photo_uris,
+        reviews.status,
+        reviews.owner_reply,
+        reviews.owner_replied_at,
+        reviews.created_at,
+        reviews.updated_at,
+        users.name AS user_name,
+        users.last_name AS user_last_name
+        FROM reviews
+        JOIN users ON reviews.user_id = users.user_id
+        WHERE reviews.restaurant_id = $1
+        AND reviews.status <> 'Hidden'
+        ORDER BY reviews.created_at DESC
+        LIMIT $2 OFFSET $3",
+        restaurant_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (review_id) FROM reviews where restaurant_id = $1 AND status <> 'Hidden'",
+        restaurant_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(reviews) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: reviews,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_review(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+    Json(create_review_dto): Json<CreateReview>,
+) -> AppResult<Review> {
+    if !is_valid_rating(create_review_dto.rating) {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("rating must be between 1 and 5"),
+        );
+    }
+    if !are_valid_photo_uris(&create_review_dto.photo_uris) {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("photo_uris must be uploaded files"),
+        );
+    }
+
+    match is_restaurant_visible(&state.db, restaurant_id, Some(&current_user)).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let existing = sqlx::query_scalar!(
+        "SELECT review_id FROM reviews WHERE restaurant_id = $1 AND user_id = $2",
+        restaurant_id,
+        current_user.user_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match existing {
+        Ok(Some(_)) => {
+            return AppResult::Error(
+                StatusCode::CONFLICT,
+                String::from("You already reviewed this restaurant!"),
+            )
+        }
+        Ok(None) => {}
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let status =
+        match contains_banned_word(&create_review_dto.content, &state.env.review_banned_words) {
+            true => "Flagged",
+            false => "Visible",
+        };
+
+    let res: Result<Review, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let review = sqlx::query_as!(
+            Review,
+            "INSERT INTO reviews (restaurant_id,user_id,rating,content,photo_uris,status) VALUES ($1,$2,$3,$4,$5,$6) RETURNING review_id,restaurant_id,user_id,rating,content,photo_uris,status,owner_reply,owner_replied_at,created_at,updated_at",
+            restaurant_id,
+            current_user.user_id,
+            create_review_dto.rating,
+            create_review_dto.content,
+            &create_review_dto.photo_uris,
+            status
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        apply_rating_change(&mut tx, review.restaurant_id, None, Some(review.rating)).await?;
+
+        tx.commit().await?;
+        Ok(review)
+    }
+    .await;
+
+    match res {
+        Ok(review) => AppResult::Result(StatusCode::CREATED, review),
+        // Another request of the same user got in first.
+        Err(sqlx::Error::Database(err)) if err.is_unique_violation() => AppResult::Error(
+            StatusCode::CONFLICT,
+            String::from("You already reviewed this restaurant!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_review(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, review_id)): Path<(i32, i32)>,
+    Json(update_review_dto): Json<UpdateReview>,
+) -> AppResult<Review> {
+    if let Some(rating) = update_review_dto.rating {
+        if !is_valid_rating(rating) {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("rating must be between 1 and 5"),
+            );
+        }
+    }
+    if update_review_dto
+        .photo_uris
+        .as_deref()
+        .is_some_and(|photo_uris| !are_valid_photo_uris(photo_uris))
+    {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("photo_uris must be uploaded files"),
+        );
+    }
+
+    let res: Result<Option<(Review, Vec<String>)>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let previous = sqlx::query_as!(
+            Review,
+            "SELECT review_id,restaurant_id,user_id,rating,content,photo_uris,status,owner_reply,owner_replied_at,created_at,updated_at FROM reviews WHERE review_id = $1 AND restaurant_id = $2 AND user_id = $3 FOR UPDATE",
+            review_id,
+            restaurant_id,
+            current_user.user_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(previous) = previous else {
+            return Ok(None);
+        };
+
+        let is_flagged = update_review_dto.content.as_ref().is_some_and(|content| {
+            contains_banned_word(content, &state.env.review_banned_words)
+        });
+        let status = match previous.status.as_str() {
+            "Hidden" => "Hidden",
+            _ if is_flagged => "Flagged",
+            status => status,
+        };
+
+        let review = sqlx::query_as!(
+            Review,
+            "UPDATE reviews SET
+            rating = COALESCE($2, rating),
+            content = COALESCE($3, content),
+            photo_uris = COALESCE($4, photo_uris),
+            status = $5,
+            updated_at = NOW()
+            WHERE review_id = $1
+            RETURNING review_id,restaurant_id,user_id,rating,content,photo_uris,status,owner_reply,owner_replied_at,created_at,updated_at",
+            review_id,
+            update_review_dto.rating,
+            update_review_dto.content,
+            update_review_dto.photo_uris.as_deref(),
+            status
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        apply_rating_change(
+            &mut tx,
+            review.restaurant_id,
+            counted_rating(&previous),
+            counted_rating(&review),
+        )
+        .await?;
+
+        let removed_photos: Vec<String> = previous
+            .photo_uris
+            .into_iter()
+            .filter(|uri| !review.photo_uris.contains(uri))
+            .collect();
+        let removed_photos = find_unreferenced_file_uris(&mut tx, &removed_photos).await?;
+
+        tx.commit().await?;
+        Ok(Some((review, removed_photos)))
+    }
+    .await;
+
+    match res {
+        Ok(Some((review, removed_photos))) => {
+            for photo_uri in removed_photos {
+                delete_file(photo_uri);
+            }
+
+            AppResult::Result(StatusCode::OK, review)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Review not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_review(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, review_id)): Path<(i32, i32)>,
+) -> AppResult<Review> {
+    let res: Result<Option<(Review, Vec<String>)>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let review = match current_user.role.as_str() {
+            "Admin" => {
+                sqlx::query_as!(
+                    Review,
+                    "DELETE FROM reviews WHERE review_id = $1 AND restaurant_id = $2 RETURNING review_id,restaurant_id,user_id,rating,content,photo_uris,status,owner_reply,owner_replied_at,created_at,updated_at",
+                    review_id,
+                    restaurant_id
+                )
+                .fetch_optional(&mut *tx)
+                .await?
+            }
+            _ => {
+                sqlx::query_as!(
+                    Review,
+                    "DELETE FROM reviews WHERE review_id = $1 AND restaurant_id = $2 AND user_id = $3 RETURNING review_id,restaurant_id,user_id,rating,content,photo_uris,status,owner_reply,owner_replied_at,created_at,updated_at",
+                    review_id,
+                    restaurant_id,
+                    current_user.user_id
+                )
+                .fetch_optional(&mut *tx)
+                .await?
+            }
+        };
+
+        let Some(review) = review else {
+            return Ok(None);
+        };
+
+        apply_rating_change(&mut tx, review.restaurant_id, counted_rating(&review), None).await?;
+
+        let removed_photos = find_unreferenced_file_uris(&mut tx, &review.photo_uris).await?;
+
+        tx.commit().await?;
+        Ok(Some((review, removed_photos)))
+    }
+    .await;
+
+    match res {
+        Ok(Some((review, removed_photos))) => {
+            for photo_uri in removed_photos {
+                delete_file(photo_uri);
+            }
+
+            AppResult::Result(StatusCode::OK, review)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Review not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn report_review(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, review_id)): Path<(i32, i32)>,
+    Json(create_review_report_dto): Json<CreateReviewReport>,
+) -> AppResult<ReviewReport> {
+    let review = sqlx::query_scalar!(
+        "SELECT user_id FROM reviews WHERE review_id = $1 AND restaurant_id = $2 AND status <> 'Hidden'",
+        review_id,
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match review {
+        Ok(Some(author_id)) if author_id == current_user.user_id => {
+            return AppResult::Error(
+                StatusCode::BAD_REQUEST,
+                String::from("You can not report your own review!"),
+            )
+        }
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Review not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let res = sqlx::query_as!(
+        ReviewReport,
+        "INSERT INTO review_reports (review_id,user_id,reason,details) VALUES ($1,$2,$3,$4)
+        ON CONFLICT (review_id, user_id) DO NOTHING
+        RETURNING review_report_id,review_id,user_id,reason,details,created_at",
+        review_id,
+        current_user.user_id,
+        create_review_report_dto.reason.as_str(),
+        create_review_report_dto.details
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(review_report)) => AppResult::Result(StatusCode::CREATED, review_report),
+        Ok(None) => AppResult::Error(
+            StatusCode::CONFLICT,
+            String::from("You already reported this review!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Returns the existing owner reply of a review, or the error to send back when
+// the review does not exist or the current user does not own the restaurant.
+async fn find_review_reply(
+    state: &AppState,
+    current_user: &User,
+    restaurant_id: i32,
+    review_id: i32,
+) -> Result<Option<String>, AppResult<Review>> {
+    let res = sqlx::query!(
+        "SELECT reviews.owner_reply, restaurants.user_id
+        FROM reviews
+        JOIN restaurants ON reviews.restaurant_id = restaurants.restaurant_id
+        WHERE reviews.review_id = $1 AND reviews.restaurant_id = $2",
+        review_id,
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(row)) if current_user.role == "Admin" || row.user_id == current_user.user_id => {
+            Ok(row.owner_reply)
+        }
+        Ok(Some(_)) => Err(AppResult::Error(
+            StatusCode::FORBIDDEN,
+            String::from("Forbidden resources!"),
+        )),
+        Ok(None) => Err(AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Review not found!"),
+        )),
+        Err(_) => Err(AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )),
+    }
+}
+
+async fn set_review_reply(
+    state: &AppState,
+    review_id: i32,
+    content: Option<String>,
+) -> AppResult<Review> {
+    let res = sqlx::query_as!(
+        Review,
+        "UPDATE reviews SET
+        owner_reply = $2,
+        owner_replied_at = CASE WHEN $2::TEXT IS NULL THEN NULL ELSE NOW() END
+        WHERE review_id = $1
+        RETURNING review_id,restaurant_id,user_id,rating,content,photo_uris,status,owner_reply,owner_replied_at,created_at,updated_at",
+        review_id,
+        content
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(review) => AppResult::Result(StatusCode::OK, review),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_review_reply(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, review_id)): Path<(i32, i32)>,
+    Json(review_reply_dto): Json<ReviewReply>,
+) -> AppResult<Review> {
+    match find_review_reply(&state, &current_user, restaurant_id, review_id).await {
+        Ok(None) => match set_review_reply(&state, review_id, Some(review_reply_dto.content)).await
+        {
+            AppResult::Result(_, review) => AppResult::Result(StatusCode::CREATED, review),
+            error => error,
+        },
+        Ok(Some(_)) => AppResult::Error(
+            StatusCode::CONFLICT,
+            String::from("This review already has a reply!"),
+        ),
+        Err(error) => error,
+    }
+}
+
+pub async fn update_review_reply(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, review_id)): Path<(i32, i32)>,
+    Json(review_reply_dto): Json<ReviewReply>,
+) -> AppResult<Review> {
+    match find_review_reply(&state, &current_user, restaurant_id, review_id).await {
+        Ok(Some(_)) => set_review_reply(&state, review_id, Some(review_reply_dto.content)).await,
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Reply not found!")),
+        Err(error) => error,
+    }
+}
+
+pub async fn delete_review_reply(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, review_id)): Path<(i32, i32)>,
+) -> AppResult<Review> {
+    match find_review_reply(&state, &current_user, restaurant_id, review_id).await {
+        Ok(Some(_)) => set_review_reply(&state, review_id, None).await,
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Reply not found!")),
+        Err(error) => error,
+    }
+}
+
+pub async fn get_reviews_moderation_queue(
+    State(state): State<Arc<AppState>>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<ReviewModeration>> {
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    // Only reports filed after the last moderation decision keep a review in
+    // the queue; auto-flagged reviews stay there until a moderator acts.
+    let res = sqlx::query_as!(
+        ReviewModeration,
+        r#"SELECT
+        reviews.review_id,
+        reviews.restaurant_id,
+        reviews.user_id,
+        reviews.rating,
+        reviews.content,
+        reviews.photo_uris,
+        reviews.status,
+        reviews.created_at,
+        COUNT(review_reports.review_report_id) AS "report_count!",
+        COALESCE(
+            ARRAY_AGG(DISTINCT review_reports.reason) FILTER (WHERE review_reports.reason IS NOT NULL),
+            '{}'
+        ) AS "report_reasons!",
+        MAX(review_reports.created_at) AS last_reported_at
+        FROM reviews
+        LEFT JOIN review_reports ON review_reports.review_id = reviews.review_id
+        AND review_reports.created_at > COALESCE(reviews.moderated_at, '-infinity')
+        WHERE reviews.status <> 'Hidden'
+        AND (reviews.status = 'Flagged' OR review_reports.review_report_id IS NOT NULL)
+        GROUP BY reviews.review_id
+        ORDER BY COUNT(review_reports.review_report_id) DESC, reviews.created_at ASC
+        LIMIT $1 OFFSET $2"#,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (review_id) FROM reviews
+        WHERE status <> 'Hidden'
+        AND (
+            status = 'Flagged'
+            OR EXISTS (
+                SELECT 1 FROM review_reports
+                WHERE review_reports.review_id = reviews.review_id
+                AND review_reports.created_at > COALESCE(reviews.moderated_at, '-infinity')
+            )
+        )"
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(reviews) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: reviews,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+async fn moderate_review(state: &AppState, review_id: i32, hide: bool) -> AppResult<Review> {
+    let res: Result<Option<Review>, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+
+        let previous_status = sqlx::query_scalar!(
+            "SELECT status FROM reviews WHERE review_id = $1 FOR UPDATE",
+            review_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(previous_status) = previous_status else {
+            return Ok(None);
+        };
+
+        let review = sqlx::query_as!(
+            Review,
+            "UPDATE reviews SET status = $2, moderated_at = NOW() WHERE review_id = $1
+            RETURNING review_id,restaurant_id,user_id,rating,content,photo_uris,status,owner_reply,owner_replied_at,created_at,updated_at",
+            review_id,
+            if hide { "Hidden" } else { "Visible" }
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let previous_rating = (previous_status != "Hidden").then_some(review.rating);
+        apply_rating_change(
+            &mut tx,
+            review.restaurant_id,
+            previous_rating,
+            counted_rating(&review),
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(review))
+    }
+    .await;
+
+    match res {
+        Ok(Some(review)) => AppResult::Result(StatusCode::OK, review),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Review not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn hide_review(
+    State(state): State<Arc<AppState>>,
+    Path(review_id): Path<i32>,
+) -> AppResult<Review> {
+    moderate_review(&state, review_id, true).await
+}
+
+pub async fn restore_review(
+    State(state): State<Arc<AppState>>,
+    Path(review_id): Path<i32>,
+) -> AppResult<Review> {
+    moderate_review(&state, review_id, false).await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    // Count, sum and histogram as stored on `restaurants`.
+    fn aggregates(ratings: &[i32]) -> (i32, i32, [i32; 5]) {
+        let mut histogram = [0; 5];
+        for &rating in ratings {
+            histogram[rating as usize - 1] += 1;
+        }
+        (ratings.len() as i32, ratings.iter().sum(), histogram)
+    }
+
+    #[test]
+    fn rating_deltas_cover_create_update_and_delete() {
+        assert_eq!(rating_deltas(None, Some(4)), vec![(4, 1)]);
+        assert_eq!(rating_deltas(Some(4), None), vec![(4, -1)]);
+        assert_eq!(rating_deltas(Some(2), Some(5)), vec![(2, -1), (5, 1)]);
+        assert!(rating_deltas(Some(3), Some(3)).is_empty());
+        assert!(rating_deltas(None, None).is_empty());
+    }
+
+    #[test]
+    fn incremental_aggregates_match_a_full_recount() {
+        let mut incremental = aggregates(&[]);
+        let mut ratings = [None; 4];
+        let changes = [
+            (0, Some(5)),
+            (1, Some(3)),
+            (2, Some(1)),
+            (1, Some(4)),
+            (0, None),
+            (3, Some(4)),
+            (2, Some(1)),
+        ];
+
+        for (review, rating) in changes {
+            for (rating, delta) in rating_deltas(ratings[review], rating) {
+                incremental.0 += delta;
+                incremental.1 += rating * delta;
+                incremental.2[rating as usize - 1] += delta;
+            }
+            ratings[review] = rating;
+
+            let current: Vec<i32> = ratings.iter().flatten().copied().collect();
+            assert_eq!(incremental, aggregates(&current), "{:?}", current);
+        }
+    }
+
+    #[test]
+    fn hidden_reviews_leave_the_aggregates() {
+        let review = |status: &str| Review {
+            review_id: 1,
+            restaurant_id: 1,
+            user_id: 1,
+            rating: 2,
+            content: String::new(),
+            photo_uris: Vec::new(),
+            status: String::from(status),
+            owner_reply: None,
+            owner_replied_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        assert_eq!(counted_rating(&review("Visible")), Some(2));
+        assert_eq!(counted_rating(&review("Flagged")), Some(2));
+        assert_eq!(counted_rating(&review("Hidden")), None);
+        // Hiding, restoring and editing a hidden review.
+        assert_eq!(
+            rating_deltas(Some(2), counted_rating(&review("Hidden"))),
+            vec![(2, -1)]
+        );
+        assert_eq!(
+            rating_deltas(None, counted_rating(&review("Visible"))),
+            vec![(2, 1)]
+        );
+        assert!(rating_deltas(None, counted_rating(&review("Hidden"))).is_empty());
+    }
+
+    #[test]
+    fn ratings_and_photos_are_validated() {
+        assert!(is_valid_rating(1) && is_valid_rating(5));
+        assert!(!is_valid_rating(0) && !is_valid_rating(6));
+        assert!(are_valid_photo_uris(&[String::from(
+            "api/files/uploads/1_2.jpg"
+        )]));
+        assert!(!are_valid_photo_uris(&[String::from(
+            "https://example.com/a.jpg"
+        )]));
+        assert!(!are_valid_photo_uris(&[String::from(
+            "api/files/uploads/../secret"
+        )]));
+    }
+}
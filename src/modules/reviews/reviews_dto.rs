@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Deserialize)]
+pub struct CreateReview {
+    pub rating: i32,
+    pub content: String,
+    #[serde(default)]
+    pub photo_uris: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateReview {
+    pub rating: Option<i32>,
+    pub content: Option<String>,
+    pub photo_uris: Option<Vec<String>>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct Review {
+    pub review_id: i64,
+    pub restaurant_id: i64,
+    pub user_id: i64,
+    pub rating: i32,
+    pub content: String,
+    pub photo_uris: Vec<String>,
+    pub status: String,
+    pub owner_reply: Option<String>,
+    pub owner_replied_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct ReviewUser {
+    pub review_id: i64,
+    pub restaurant_id: i64,
+    pub user_id: i64,
+    pub rating: i32,
+    pub content: String,
+    pub photo_uris: Vec<String>,
+    pub status: String,
+    pub owner_reply: Option<String>,
+    pub owner_replied_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub user_name: String,
+    pub user_last_name: String,
+}
+
+#[derive(Deserialize)]
+pub struct ReviewReply {
+    pub content: String,
+}
+
+#[derive(Deserialize)]
+pub enum ReviewReportReasonEnum {
+    Spam,
+    Offensive,
+    OffTopic,
+    Fake,
+    Other,
+}
+
+impl ReviewReportReasonEnum {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewReportReasonEnum::Spam => "Spam",
+            ReviewReportReasonEnum::Offensive => "Offensive",
+            ReviewReportReasonEnum::OffTopic => "OffTopic",
+            ReviewReportReasonEnum::Fake => "Fake",
+            ReviewReportReasonEnum::Other => "Other",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CreateReviewReport {
+    pub reason: ReviewReportReasonEnum,
+    #[serde(default)]
+    pub details: String,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct ReviewReport {
+    pub review_report_id: i64,
+    pub review_id: i64,
+    pub user_id: i64,
+    pub reason: String,
+    pub details: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct ReviewModeration {
+    pub review_id: i64,
+    pub restaurant_id: i64,
+    pub user_id: i64,
+    pub rating: i32,
+    pub content: String,
+    pub photo_uris: Vec<String>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub report_count: i64,
+    pub report_reasons: Vec<String>,
+    pub last_reported_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,3 @@
+pub mod translations_controller;
+pub mod translations_dto;
+pub mod translations_service;
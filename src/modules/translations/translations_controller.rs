@@ -0,0 +1,451 @@
+use std::sync::Arc;
+
+use crate::{
+    common::locale::normalize_locale,
+    modules::{
+        restaurants::restaurants_service::{check_restaurant_access, check_restaurant_menu_access},
+        shared::shared_dto::AppResult,
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    Extension,
+};
+
+use super::{
+    translations_dto::{
+        RestaurantMenuTranslation, Translation, UpsertRestaurantMenuTranslation, UpsertTranslation,
+    },
+    translations_service::{find_restaurant_default_locale, is_same_language},
+};
+
+async fn check_restaurant_menu_item_access<T>(
+    state: &AppState,
+    restaurant_id: i32,
+    restaurant_menu_id: i32,
+    restaurant_menu_item_id: i32,
+    current_user: &User,
+) -> Result<String, AppResult<T>> {
+    let res = sqlx::query_scalar!(
+        "SELECT r.default_locale FROM restaurant_menu_items rmi
+        JOIN restaurant_menus rm ON rm.restaurant_menu_id = rmi.restaurant_menu_id
+        JOIN restaurants r ON r.restaurant_id = rm.restaurant_id
+        WHERE rmi.restaurant_menu_item_id = $1 AND rm.restaurant_menu_id = $2 AND rm.restaurant_id = $3
+        AND rmi.deleted_at IS NULL AND rm.deleted_at IS NULL AND r.deleted_at IS NULL
+        AND (r.user_id = $4 OR $5)",
+        restaurant_menu_item_id,
+        restaurant_menu_id,
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(default_locale)) => Ok(default_locale),
+        Ok(None) => Err(AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Menu item not found!"),
+        )),
+        Err(_) => Err(AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        )),
+    }
+}
+
+// Texts in the default locale are edited on the restaurant, menu or item.
+fn validate_locale<T>(locale: &str, default_locale: &str) -> Result<String, AppResult<T>> {
+    match normalize_locale(locale) {
+        // Requests for the default language never reach its translations.
+        Some(locale) if is_same_language(&locale, default_locale) => Err(AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("locale must not be the language of the restaurant"),
+        )),
+        Some(locale) => Ok(locale),
+        None => Err(AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("locale must be a language tag such as fr or pt-BR"),
+        )),
+    }
+}
+
+pub async fn get_restaurant_translations(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+) -> AppResult<Vec<Translation>> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        Translation,
+        "SELECT locale,name,description,updated_at FROM restaurant_translations
+        WHERE restaurant_id = $1 ORDER BY locale ASC",
+        restaurant_id
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match res {
+        Ok(translations) => AppResult::Result(StatusCode::OK, translations),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn upsert_restaurant_translation(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, locale)): Path<(i32, String)>,
+    Json(upsert_translation_dto): Json<UpsertTranslation>,
+) -> AppResult<Translation> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+    let default_locale = match find_restaurant_default_locale(&state.db, restaurant_id).await {
+        Ok(default_locale) => default_locale,
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+    let locale = match validate_locale(&locale, &default_locale) {
+        Ok(locale) => locale,
+        Err(err) => return err,
+    };
+
+    let name = upsert_translation_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        Translation,
+        "INSERT INTO restaurant_translations (restaurant_id,locale,name,description)
+        VALUES ($1,$2,$3,$4)
+        ON CONFLICT (restaurant_id, locale)
+        DO UPDATE SET name = EXCLUDED.name, description = EXCLUDED.description, updated_at = NOW()
+        RETURNING locale,name,description,updated_at",
+        restaurant_id,
+        locale,
+        name,
+        upsert_translation_dto.description
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(translation) => AppResult::Result(StatusCode::OK, translation),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_translation(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, locale)): Path<(i32, String)>,
+) -> AppResult<Translation> {
+    if let Err(err) = check_restaurant_access(&state.db, restaurant_id, &current_user).await {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        Translation,
+        "DELETE FROM restaurant_translations WHERE restaurant_id = $1 AND locale = $2
+        RETURNING locale,name,description,updated_at",
+        restaurant_id,
+        normalize_locale(&locale)
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(translation)) => AppResult::Result(StatusCode::OK, translation),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Translation not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_menu_translations(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id)): Path<(i32, i32)>,
+) -> AppResult<Vec<RestaurantMenuTranslation>> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuTranslation,
+        "SELECT locale,name,updated_at FROM restaurant_menu_translations
+        WHERE restaurant_menu_id = $1 ORDER BY locale ASC",
+        restaurant_menu_id
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match res {
+        Ok(translations) => AppResult::Result(StatusCode::OK, translations),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn upsert_restaurant_menu_translation(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, locale)): Path<(i32, i32, String)>,
+    Json(upsert_translation_dto): Json<UpsertRestaurantMenuTranslation>,
+) -> AppResult<RestaurantMenuTranslation> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+    let default_locale = match find_restaurant_default_locale(&state.db, restaurant_id).await {
+        Ok(default_locale) => default_locale,
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    };
+    let locale = match validate_locale(&locale, &default_locale) {
+        Ok(locale) => locale,
+        Err(err) => return err,
+    };
+
+    let name = upsert_translation_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuTranslation,
+        "INSERT INTO restaurant_menu_translations (restaurant_menu_id,locale,name)
+        VALUES ($1,$2,$3)
+        ON CONFLICT (restaurant_menu_id, locale)
+        DO UPDATE SET name = EXCLUDED.name, updated_at = NOW()
+        RETURNING locale,name,updated_at",
+        restaurant_menu_id,
+        locale,
+        name
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(translation) => AppResult::Result(StatusCode::OK, translation),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_menu_translation(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, locale)): Path<(i32, i32, String)>,
+) -> AppResult<RestaurantMenuTranslation> {
+    if let Err(err) =
+        check_restaurant_menu_access(&state.db, restaurant_id, restaurant_menu_id, &current_user)
+            .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuTranslation,
+        "DELETE FROM restaurant_menu_translations WHERE restaurant_menu_id = $1 AND locale = $2
+        RETURNING locale,name,updated_at",
+        restaurant_menu_id,
+        normalize_locale(&locale)
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(translation)) => AppResult::Result(StatusCode::OK, translation),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Translation not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_restaurant_menu_item_translations(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id)): Path<(i32, i32, i32)>,
+) -> AppResult<Vec<Translation>> {
+    if let Err(err) = check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        Translation,
+        "SELECT locale,name,description,updated_at FROM restaurant_menu_item_translations
+        WHERE restaurant_menu_item_id = $1 ORDER BY locale ASC",
+        restaurant_menu_item_id
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match res {
+        Ok(translations) => AppResult::Result(StatusCode::OK, translations),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn upsert_restaurant_menu_item_translation(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id, locale)): Path<(
+        i32,
+        i32,
+        i32,
+        String,
+    )>,
+    Json(upsert_translation_dto): Json<UpsertTranslation>,
+) -> AppResult<Translation> {
+    let default_locale = match check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        Ok(default_locale) => default_locale,
+        Err(err) => return err,
+    };
+    let locale = match validate_locale(&locale, &default_locale) {
+        Ok(locale) => locale,
+        Err(err) => return err,
+    };
+
+    let name = upsert_translation_dto.name.trim();
+    if name.is_empty() {
+        return AppResult::Error(
+            StatusCode::BAD_REQUEST,
+            String::from("name must not be empty"),
+        );
+    }
+
+    let res = sqlx::query_as!(
+        Translation,
+        "INSERT INTO restaurant_menu_item_translations (restaurant_menu_item_id,locale,name,description)
+        VALUES ($1,$2,$3,$4)
+        ON CONFLICT (restaurant_menu_item_id, locale)
+        DO UPDATE SET name = EXCLUDED.name, description = EXCLUDED.description, updated_at = NOW()
+        RETURNING locale,name,description,updated_at",
+        restaurant_menu_item_id,
+        locale,
+        name,
+        upsert_translation_dto.description
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(translation) => AppResult::Result(StatusCode::OK, translation),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_restaurant_menu_item_translation(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((restaurant_id, restaurant_menu_id, restaurant_menu_item_id, locale)): Path<(
+        i32,
+        i32,
+        i32,
+        String,
+    )>,
+) -> AppResult<Translation> {
+    if let Err(err) = check_restaurant_menu_item_access(
+        &state,
+        restaurant_id,
+        restaurant_menu_id,
+        restaurant_menu_item_id,
+        &current_user,
+    )
+    .await
+    {
+        return err;
+    }
+
+    let res = sqlx::query_as!(
+        Translation,
+        "DELETE FROM restaurant_menu_item_translations WHERE restaurant_menu_item_id = $1 AND locale = $2
+        RETURNING locale,name,description,updated_at",
+        restaurant_menu_item_id,
+        normalize_locale(&locale)
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(translation)) => AppResult::Result(StatusCode::OK, translation),
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("Translation not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
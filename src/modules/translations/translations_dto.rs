@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+// A translated restaurant or menu item, description falls back to the
+// default locale when unset.
+#[derive(Serialize, FromRow)]
+pub struct Translation {
+    pub locale: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct UpsertTranslation {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct RestaurantMenuTranslation {
+    pub locale: String,
+    pub name: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct UpsertRestaurantMenuTranslation {
+    pub name: String,
+}
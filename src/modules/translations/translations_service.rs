@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    common::locale::RequestLocales,
+    modules::{
+        restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+        restaurant_menus::restaurant_menus_dto::RestaurantMenu,
+    },
+};
+
+#[derive(Clone, Copy)]
+pub enum TranslationTarget {
+    Restaurant,
+    RestaurantMenu,
+    RestaurantMenuItem,
+}
+
+// The fields of a response that get replaced by their translation.
+pub struct TranslatableText<'a> {
+    pub id: i64,
+    // The locale name and description are written in.
+    pub default_locale: &'a str,
+    pub name: &'a mut String,
+    pub description: Option<&'a mut String>,
+}
+
+pub fn is_same_language(locale: &str, other: &str) -> bool {
+    locale == other
+        || other.starts_with(&format!("{}-", locale))
+        || locale.starts_with(&format!("{}-", other))
+}
+
+// A client asking for "en, fr" of an English restaurant gets the English
+// text even when there is a French translation.
+pub fn preferred_locales<'a>(locales: &'a [String], default_locale: &str) -> &'a [String] {
+    let end = locales
+        .iter()
+        .position(|locale| is_same_language(locale, default_locale))
+        .unwrap_or(locales.len());
+
+    &locales[..end]
+}
+
+// Menus and items are shown in the locale of the restaurant they are viewed
+// from, chain menus included.
+pub async fn find_restaurant_default_locale(
+    db: &Pool<Postgres>,
+    restaurant_id: i32,
+) -> Result<String, sqlx::Error> {
+    let default_locale = sqlx::query_scalar!(
+        "SELECT default_locale FROM restaurants WHERE restaurant_id = $1",
+        restaurant_id
+    )
+    .fetch_optional(db)
+    .await?;
+
+    Ok(default_locale.unwrap_or_else(|| String::from("en")))
+}
+
+pub async fn translate_texts(
+    db: &Pool<Postgres>,
+    target: TranslationTarget,
+    locales: &RequestLocales,
+    texts: Vec<TranslatableText<'_>>,
+) -> Result<(), sqlx::Error> {
+    if locales.0.is_empty() || texts.is_empty() {
+        return Ok(());
+    }
+
+    let ids: Vec<i32> = texts.iter().map(|text| text.id as i32).collect();
+    let rows: Vec<(i32, String, String, Option<String>)> = match target {
+        TranslationTarget::Restaurant => sqlx::query!(
+            "SELECT restaurant_id,locale,name,description FROM restaurant_translations
+            WHERE restaurant_id = ANY($1) AND locale = ANY($2)",
+            &ids,
+            &locales.0
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| (row.restaurant_id, row.locale, row.name, row.description))
+        .collect(),
+        TranslationTarget::RestaurantMenu => sqlx::query!(
+            "SELECT restaurant_menu_id,locale,name FROM restaurant_menu_translations
+            WHERE restaurant_menu_id = ANY($1) AND locale = ANY($2)",
+            &ids,
+            &locales.0
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| (row.restaurant_menu_id, row.locale, row.name, None))
+        .collect(),
+        TranslationTarget::RestaurantMenuItem => sqlx::query!(
+            "SELECT restaurant_menu_item_id,locale,name,description FROM restaurant_menu_item_translations
+            WHERE restaurant_menu_item_id = ANY($1) AND locale = ANY($2)",
+            &ids,
+            &locales.0
+        )
+        .fetch_all(db)
+        .await?
+        .into_iter()
+        .map(|row| {
+            (
+                row.restaurant_menu_item_id,
+                row.locale,
+                row.name,
+                row.description,
+            )
+        })
+        .collect(),
+    };
+
+    let mut translations: HashMap<(i32, String), (String, Option<String>)> = HashMap::new();
+    for (id, locale, name, description) in rows {
+        translations.insert((id, locale), (name, description));
+    }
+
+    for text in texts {
+        let translation = preferred_locales(&locales.0, text.default_locale)
+            .iter()
+            .find_map(|locale| translations.get(&(text.id as i32, locale.clone())));
+
+        if let Some((name, description)) = translation {
+            *text.name = name.clone();
+            if let (Some(text_description), Some(description)) = (text.description, description) {
+                *text_description = description.clone();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn translate_menus(
+    db: &Pool<Postgres>,
+    locales: &RequestLocales,
+    restaurant_id: i32,
+    menus: &mut [RestaurantMenu],
+) -> Result<(), sqlx::Error> {
+    if locales.0.is_empty() {
+        return Ok(());
+    }
+
+    let default_locale = find_restaurant_default_locale(db, restaurant_id).await?;
+    let texts = menus
+        .iter_mut()
+        .map(|menu| TranslatableText {
+            id: menu.restaurant_menu_id,
+            default_locale: &default_locale,
+            name: &mut menu.name,
+            description: None,
+        })
+        .collect();
+
+    translate_texts(db, TranslationTarget::RestaurantMenu, locales, texts).await
+}
+
+pub async fn translate_menu_items(
+    db: &Pool<Postgres>,
+    locales: &RequestLocales,
+    restaurant_id: i32,
+    items: &mut [RestaurantMenuItem],
+) -> Result<(), sqlx::Error> {
+    if locales.0.is_empty() {
+        return Ok(());
+    }
+
+    let default_locale = find_restaurant_default_locale(db, restaurant_id).await?;
+    let texts = items
+        .iter_mut()
+        .map(|item| TranslatableText {
+            id: item.restaurant_menu_item_id,
+            default_locale: &default_locale,
+            name: &mut item.name,
+            description: Some(&mut item.description),
+        })
+        .collect();
+
+    translate_texts(db, TranslationTarget::RestaurantMenuItem, locales, texts).await
+}
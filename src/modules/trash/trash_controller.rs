@@ -0,0 +1,271 @@
+use std::sync::Arc;
+
+use crate::{
+    common::money::Money,
+    modules::{
+        restaurant_menu_items::restaurant_menu_items_dto::RestaurantMenuItem,
+        restaurant_menus::restaurant_menus_dto::RestaurantMenu,
+        restaurants::{
+            restaurants_dto::Restaurant,
+            restaurants_service::{refresh_menu_price_levels, refresh_restaurant_price_level},
+        },
+        shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+
+use super::{trash_dto::TrashItem, trash_service::find_trashed_parent};
+
+pub async fn get_trash(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<TrashItem>> {
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let is_admin = current_user.role == "Admin";
+    let res = sqlx::query_as!(
+        TrashItem,
+        r#"SELECT
+        trash.item_type AS "item_type!",
+        trash.item_id AS "item_id!",
+        trash.name AS "name!",
+        trash.restaurant_id AS "restaurant_id?",
+        trash.restaurant_name AS "restaurant_name?",
+        trash.restaurant_chain_id AS "restaurant_chain_id?",
+        trash.restaurant_chain_name AS "restaurant_chain_name?",
+        trash.deleted_at AS "deleted_at!",
+        trash.deleted_at + make_interval(days => $5) AS "purge_at!"
+        FROM (
+            SELECT 'restaurant' AS item_type, r.restaurant_id AS item_id, r.name, r.restaurant_id, r.name AS restaurant_name,
+            NULL::INTEGER AS restaurant_chain_id, NULL::TEXT AS restaurant_chain_name, r.deleted_at
+            FROM restaurants r
+            WHERE r.deleted_at IS NOT NULL AND (r.user_id = $1 OR $2)
+            UNION ALL
+            SELECT 'menu', rm.restaurant_menu_id, rm.name, r.restaurant_id, r.name, rc.restaurant_chain_id, rc.name, rm.deleted_at
+            FROM restaurant_menus rm
+            LEFT JOIN restaurants r ON rm.restaurant_id = r.restaurant_id
+            LEFT JOIN restaurant_chains rc ON rm.restaurant_chain_id = rc.restaurant_chain_id
+            WHERE rm.deleted_at IS NOT NULL AND (r.user_id = $1 OR rc.user_id = $1 OR $2)
+            UNION ALL
+            SELECT 'item', rmi.restaurant_menu_item_id, rmi.name, r.restaurant_id, r.name, rc.restaurant_chain_id, rc.name, rmi.deleted_at
+            FROM restaurant_menu_items rmi
+            JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+            LEFT JOIN restaurants r ON rm.restaurant_id = r.restaurant_id
+            LEFT JOIN restaurant_chains rc ON rm.restaurant_chain_id = rc.restaurant_chain_id
+            WHERE rmi.deleted_at IS NOT NULL AND (r.user_id = $1 OR rc.user_id = $1 OR $2)
+        ) trash
+        ORDER BY trash.deleted_at DESC
+        LIMIT $3 OFFSET $4"#,
+        current_user.user_id,
+        is_admin,
+        page_size,
+        offset,
+        state.env.trash_retention_days
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT
+        (SELECT COUNT(*) FROM restaurants r
+            WHERE r.deleted_at IS NOT NULL AND (r.user_id = $1 OR $2))
+        + (SELECT COUNT(*) FROM restaurant_menus rm
+            LEFT JOIN restaurants r ON rm.restaurant_id = r.restaurant_id
+            LEFT JOIN restaurant_chains rc ON rm.restaurant_chain_id = rc.restaurant_chain_id
+            WHERE rm.deleted_at IS NOT NULL AND (r.user_id = $1 OR rc.user_id = $1 OR $2))
+        + (SELECT COUNT(*) FROM restaurant_menu_items rmi
+            JOIN restaurant_menus rm ON rmi.restaurant_menu_id = rm.restaurant_menu_id
+            LEFT JOIN restaurants r ON rm.restaurant_id = r.restaurant_id
+            LEFT JOIN restaurant_chains rc ON rm.restaurant_chain_id = rc.restaurant_chain_id
+            WHERE rmi.deleted_at IS NOT NULL AND (r.user_id = $1 OR rc.user_id = $1 OR $2))",
+        current_user.user_id,
+        is_admin
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(trash_items) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: trash_items,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn restore_restaurant(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_id): Path<i32>,
+) -> AppResult<Restaurant> {
+    let res = sqlx::query_as!(
+        Restaurant,
+        "UPDATE restaurants SET deleted_at = NULL
+        WHERE restaurant_id = $1 AND deleted_at IS NOT NULL AND (user_id = $2 OR $3)
+        RETURNING restaurant_id,name,slug,user_id,location,cover_image_uri,phone,email,city,category,latitude,longitude,rating_average,rating_count,rating_histogram,score,restaurant_chain_id,average_price,price_level,is_verified,is_published,timezone,currency,description,default_locale,created_at",
+        restaurant_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(restaurant)) => {
+            refresh_restaurant_price_level(&state.db, restaurant_id).await;
+            AppResult::Result(StatusCode::OK, restaurant)
+        }
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn restore_restaurant_menu(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_menu_id): Path<i32>,
+) -> AppResult<RestaurantMenu> {
+    // Chain menus have no restaurant, they are owned through their chain.
+    let parent = sqlx::query!(
+        "SELECT restaurants.deleted_at IS NOT NULL AS is_restaurant_deleted
+        FROM restaurant_menus
+        LEFT JOIN restaurants ON restaurant_menus.restaurant_id = restaurants.restaurant_id
+        LEFT JOIN restaurant_chains ON restaurant_menus.restaurant_chain_id = restaurant_chains.restaurant_chain_id
+        WHERE restaurant_menus.restaurant_menu_id = $1
+        AND restaurant_menus.deleted_at IS NOT NULL
+        AND (restaurants.user_id = $2 OR restaurant_chains.user_id = $2 OR $3)",
+        restaurant_menu_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match parent {
+        Ok(Some(parent)) => {
+            if let Some(parent) =
+                find_trashed_parent(false, parent.is_restaurant_deleted == Some(true))
+            {
+                return AppResult::Error(
+                    StatusCode::CONFLICT,
+                    format!("Restore the {} first!", parent),
+                );
+            }
+        }
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenu,
+        "UPDATE restaurant_menus SET deleted_at = NULL WHERE restaurant_menu_id = $1 RETURNING restaurant_menu_id,name,is_active,restaurant_id,restaurant_chain_id",
+        restaurant_menu_id
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(restaurant_menu) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_id).await;
+            AppResult::Result(StatusCode::OK, restaurant_menu)
+        }
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn restore_restaurant_menu_item(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(restaurant_menu_item_id): Path<i32>,
+) -> AppResult<RestaurantMenuItem> {
+    let parent = sqlx::query!(
+        "SELECT
+        restaurant_menus.deleted_at IS NOT NULL AS is_menu_deleted,
+        restaurants.deleted_at IS NOT NULL AS is_restaurant_deleted
+        FROM restaurant_menu_items
+        JOIN restaurant_menus ON restaurant_menu_items.restaurant_menu_id = restaurant_menus.restaurant_menu_id
+        LEFT JOIN restaurants ON restaurant_menus.restaurant_id = restaurants.restaurant_id
+        LEFT JOIN restaurant_chains ON restaurant_menus.restaurant_chain_id = restaurant_chains.restaurant_chain_id
+        WHERE restaurant_menu_items.restaurant_menu_item_id = $1
+        AND restaurant_menu_items.deleted_at IS NOT NULL
+        AND (restaurants.user_id = $2 OR restaurant_chains.user_id = $2 OR $3)",
+        restaurant_menu_item_id,
+        current_user.user_id,
+        current_user.role == "Admin"
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match parent {
+        Ok(Some(parent)) => {
+            if let Some(parent) = find_trashed_parent(
+                parent.is_menu_deleted == Some(true),
+                parent.is_restaurant_deleted == Some(true),
+            ) {
+                return AppResult::Error(
+                    StatusCode::CONFLICT,
+                    format!("Restore the {} first!", parent),
+                );
+            }
+        }
+        Ok(None) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Menu item not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let res = sqlx::query_as!(
+        RestaurantMenuItem,
+        r#"UPDATE restaurant_menu_items SET deleted_at = NULL WHERE restaurant_menu_item_id = $1 RETURNING restaurant_menu_item_id,name,description,restaurant_menu_id,cover_image_uri,menu_money(price_minor, restaurant_menu_id) AS "price!: Money",restaurant_menu_section_id,position,allergens,diet_labels,spicy_level,calories,protein_grams,carbohydrate_grams,fat_grams,menu_item_availability(availability, sold_out_until, remaining_stock) AS "availability!",sold_out_until,daily_stock,remaining_stock,false AS "is_favorite!""#,
+        restaurant_menu_item_id
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(restaurant_menu_item) => {
+            refresh_menu_price_levels(&state.db, restaurant_menu_item.restaurant_menu_id as i32)
+                .await;
+            AppResult::Result(StatusCode::OK, restaurant_menu_item)
+        }
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+#[derive(Serialize, FromRow)]
+pub struct TrashItem {
+    pub item_type: String,
+    pub item_id: i64,
+    pub name: String,
+    // Menus and items of a chain have a chain instead of a restaurant.
+    pub restaurant_id: Option<i32>,
+    pub restaurant_name: Option<String>,
+    pub restaurant_chain_id: Option<i32>,
+    pub restaurant_chain_name: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    pub purge_at: DateTime<Utc>,
+}
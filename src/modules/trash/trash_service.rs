@@ -0,0 +1,126 @@
+use std::{sync::Arc, time::Duration};
+
+use sqlx::{Pool, Postgres};
+
+use crate::{
+    modules::files::{files_controller::delete_file, files_service::find_unreferenced_file_uris},
+    AppState,
+};
+
+// Hard deletes whatever has been in the trash longer than the retention
+// period. Files are only removed here, after the rows are gone for good.
+pub async fn purge_trash(db: &Pool<Postgres>, retention_days: i32) -> Result<usize, sqlx::Error> {
+    let mut tx = db.begin().await?;
+
+    // Everything the cascades below will take with them, including menus and
+    // items that were never trashed themselves.
+    let file_uris = sqlx::query_scalar!(
+        r#"WITH purged_restaurants AS (
+            SELECT restaurant_id, cover_image_uri FROM restaurants
+            WHERE deleted_at < NOW() - make_interval(days => $1)
+        ),
+        purged_menus AS (
+            SELECT restaurant_menu_id FROM restaurant_menus
+            WHERE deleted_at < NOW() - make_interval(days => $1)
+            OR restaurant_id IN (SELECT restaurant_id FROM purged_restaurants)
+        )
+        SELECT DISTINCT uri AS "uri!" FROM (
+            SELECT cover_image_uri AS uri FROM purged_restaurants
+            UNION ALL
+            SELECT image_uri FROM restaurant_photos
+            WHERE restaurant_id IN (SELECT restaurant_id FROM purged_restaurants)
+            UNION ALL
+            SELECT UNNEST(photo_uris) FROM reviews
+            WHERE restaurant_id IN (SELECT restaurant_id FROM purged_restaurants)
+            UNION ALL
+            SELECT cover_image_uri FROM restaurant_menu_items
+            WHERE deleted_at < NOW() - make_interval(days => $1)
+            OR restaurant_menu_id IN (SELECT restaurant_menu_id FROM purged_menus)
+            UNION ALL
+            SELECT jsonb_array_elements(content->'items')->>'cover_image_uri' FROM restaurant_menu_versions
+            WHERE restaurant_menu_id IN (SELECT restaurant_menu_id FROM purged_menus)
+        ) files
+        WHERE uri IS NOT NULL AND uri <> ''"#,
+        retention_days
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM restaurant_menu_items WHERE deleted_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM restaurant_menus WHERE deleted_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        "DELETE FROM restaurants WHERE deleted_at < NOW() - make_interval(days => $1)",
+        retention_days
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let file_uris = find_unreferenced_file_uris(&mut tx, &file_uris).await?;
+
+    tx.commit().await?;
+
+    let count = file_uris.len();
+    for file_uri in file_uris {
+        delete_file(file_uri);
+    }
+
+    Ok(count)
+}
+
+// Trashed rows only come back under a live parent, so restoring starts from
+// the outermost trashed one.
+pub fn find_trashed_parent(
+    is_menu_deleted: bool,
+    is_restaurant_deleted: bool,
+) -> Option<&'static str> {
+    if is_restaurant_deleted {
+        Some("restaurant")
+    } else if is_menu_deleted {
+        Some("menu")
+    } else {
+        None
+    }
+}
+
+pub async fn run_trash_purge_task(state: Arc<AppState>) {
+    let mut interval =
+        tokio::time::interval(Duration::from_secs(state.env.trash_purge_interval_seconds));
+
+    loop {
+        interval.tick().await;
+
+        match purge_trash(&state.db, state.env.trash_retention_days).await {
+            Ok(count) => tracing::info!("Trash purged, {} files removed", count),
+            Err(err) => tracing::error!("Trash purge failed: {:?}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_waits_for_the_outermost_trashed_parent() {
+        assert_eq!(find_trashed_parent(true, true), Some("restaurant"));
+        assert_eq!(find_trashed_parent(false, true), Some("restaurant"));
+        assert_eq!(find_trashed_parent(true, false), Some("menu"));
+    }
+
+    #[test]
+    fn restore_goes_ahead_under_live_parents() {
+        assert_eq!(find_trashed_parent(false, false), None);
+    }
+}
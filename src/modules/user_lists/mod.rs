@@ -0,0 +1,2 @@
+pub mod user_lists_controller;
+pub mod user_lists_dto;
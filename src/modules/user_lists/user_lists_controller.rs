@@ -0,0 +1,534 @@
+use std::sync::Arc;
+
+use crate::{
+    modules::{
+        restaurants::{
+            restaurants_dto::RestaurantUser, restaurants_service::is_restaurant_visible,
+        },
+        shared::shared_dto::{AppResult, PaginatedList, PaginationInput},
+        users::users_dto::User,
+    },
+    AppState,
+};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    Extension,
+};
+use rand::{distributions::Alphanumeric, Rng};
+
+use super::user_lists_dto::{CreateUserList, UpdateUserList, UserList, UserListDetails};
+
+fn generate_share_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(24)
+        .map(char::from)
+        .collect()
+}
+
+async fn get_user_list_restaurants(
+    state: &AppState,
+    user_list_id: i64,
+    current_user_id: Option<i32>,
+) -> Result<Vec<RestaurantUser>, sqlx::Error> {
+    sqlx::query_as!(
+        RestaurantUser,
+        r#"SELECT
+        restaurants.restaurant_id,
+        restaurants.name,
+        restaurants.slug,
+        restaurants.user_id,
+        restaurants.location,
+        restaurants.cover_image_uri,
+        restaurants.phone,
+        restaurants.email,
+        users.email AS user_email,
+        restaurants.rating_average,
+        restaurants.rating_count,
+        restaurants.rating_histogram,
+        restaurants.city,
+        restaurants.category,
+        restaurants.latitude,
+        restaurants.longitude,
+        restaurants.score,
+        restaurants.restaurant_chain_id,
+        restaurants.average_price,
+        restaurants.price_level,
+        restaurants.is_verified,
+        restaurants.is_published,
+        restaurants.timezone,
+        restaurants.currency,
+        restaurants.description,
+        restaurants.default_locale,
+        restaurants.created_at,
+        NULL::DOUBLE PRECISION AS distance_km,
+        EXISTS (
+            SELECT 1 FROM favorite_restaurants
+            WHERE favorite_restaurants.restaurant_id = restaurants.restaurant_id
+            AND favorite_restaurants.user_id = $2
+        ) AS "is_favorite!"
+        FROM user_list_restaurants
+        JOIN restaurants ON user_list_restaurants.restaurant_id = restaurants.restaurant_id
+        JOIN users ON restaurants.user_id = users.user_id
+        WHERE user_list_restaurants.user_list_id = $1 AND restaurants.deleted_at IS NULL
+        AND (restaurants.is_published OR restaurants.user_id = $2)
+        ORDER BY user_list_restaurants.created_at ASC"#,
+        user_list_id as i32,
+        current_user_id
+    )
+    .fetch_all(&state.db)
+    .await
+}
+
+async fn to_user_list_details(
+    state: &AppState,
+    user_list: UserList,
+    current_user_id: Option<i32>,
+) -> AppResult<UserListDetails> {
+    match get_user_list_restaurants(state, user_list.user_list_id, current_user_id).await {
+        Ok(restaurants) => AppResult::Result(
+            StatusCode::OK,
+            UserListDetails {
+                user_list,
+                restaurants,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_my_user_lists(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    pagination_input: Query<PaginationInput>,
+) -> AppResult<PaginatedList<UserList>> {
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        UserList,
+        r#"SELECT
+        user_lists.user_list_id,
+        user_lists.user_id,
+        user_lists.name,
+        user_lists.is_public,
+        user_lists.share_token AS "share_token?",
+        user_lists.created_at,
+        (SELECT COUNT(*) FROM user_list_restaurants
+            JOIN restaurants ON user_list_restaurants.restaurant_id = restaurants.restaurant_id
+            WHERE user_list_restaurants.user_list_id = user_lists.user_list_id AND restaurants.deleted_at IS NULL) AS "restaurant_count!"
+        FROM user_lists
+        WHERE user_lists.user_id = $1
+        ORDER BY user_lists.created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        current_user.user_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (user_list_id) FROM user_lists where user_id = $1",
+        current_user.user_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(user_lists) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: user_lists,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn get_public_user_lists(
+    State(state): State<Arc<AppState>>,
+    pagination_input: Query<PaginationInput>,
+    Path(user_id): Path<i32>,
+) -> AppResult<PaginatedList<UserList>> {
+    let PaginationInput { page, page_size } = pagination_input.0;
+    let offset = (page.saturating_sub(1)) * page_size;
+    let res = sqlx::query_as!(
+        UserList,
+        r#"SELECT
+        user_lists.user_list_id,
+        user_lists.user_id,
+        user_lists.name,
+        user_lists.is_public,
+        NULL::TEXT AS share_token,
+        user_lists.created_at,
+        (SELECT COUNT(*) FROM user_list_restaurants
+            JOIN restaurants ON user_list_restaurants.restaurant_id = restaurants.restaurant_id
+            WHERE user_list_restaurants.user_list_id = user_lists.user_list_id AND restaurants.deleted_at IS NULL) AS "restaurant_count!"
+        FROM user_lists
+        WHERE user_lists.user_id = $1 AND user_lists.is_public = true
+        ORDER BY user_lists.created_at DESC
+        LIMIT $2 OFFSET $3"#,
+        user_id,
+        page_size,
+        offset
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    let count: i64 = match sqlx::query_scalar!(
+        "SELECT COUNT (user_list_id) FROM user_lists where user_id = $1 AND is_public = true",
+        user_id
+    )
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(count) => count.unwrap_or(0),
+        Err(_) => 0,
+    };
+
+    match res {
+        Ok(user_lists) => AppResult::Result(
+            StatusCode::OK,
+            PaginatedList {
+                count,
+                items: user_lists,
+            },
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+async fn find_user_list(
+    state: &AppState,
+    user_list_id: i32,
+    current_user_id: Option<i32>,
+) -> AppResult<UserListDetails> {
+    let res = sqlx::query_as!(
+        UserList,
+        r#"SELECT
+        user_lists.user_list_id,
+        user_lists.user_id,
+        user_lists.name,
+        user_lists.is_public,
+        CASE WHEN user_lists.user_id = $2 THEN user_lists.share_token END AS share_token,
+        user_lists.created_at,
+        (SELECT COUNT(*) FROM user_list_restaurants
+            JOIN restaurants ON user_list_restaurants.restaurant_id = restaurants.restaurant_id
+            WHERE user_list_restaurants.user_list_id = user_lists.user_list_id AND restaurants.deleted_at IS NULL) AS "restaurant_count!"
+        FROM user_lists
+        WHERE user_lists.user_list_id = $1 AND (user_lists.is_public OR user_lists.user_id = $2)"#,
+        user_list_id,
+        current_user_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(user_list)) => to_user_list_details(state, user_list, current_user_id).await,
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("List not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+// Public lists can be opened by anyone, private ones only by their owner.
+pub async fn get_user_list(
+    State(state): State<Arc<AppState>>,
+    Path(user_list_id): Path<i32>,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<UserListDetails> {
+    let current_user_id = current_user.map(|Extension(user)| user.user_id);
+    find_user_list(&state, user_list_id, current_user_id).await
+}
+
+// Anyone holding the share link can open the list, whatever its visibility;
+// owners revoke access by regenerating or removing the token.
+pub async fn get_shared_user_list(
+    State(state): State<Arc<AppState>>,
+    Path(share_token): Path<String>,
+    current_user: Option<Extension<Arc<User>>>,
+) -> AppResult<UserListDetails> {
+    let current_user_id = current_user.map(|Extension(user)| user.user_id);
+    let res = sqlx::query_as!(
+        UserList,
+        r#"SELECT
+        user_lists.user_list_id,
+        user_lists.user_id,
+        user_lists.name,
+        user_lists.is_public,
+        NULL::TEXT AS share_token,
+        user_lists.created_at,
+        (SELECT COUNT(*) FROM user_list_restaurants
+            JOIN restaurants ON user_list_restaurants.restaurant_id = restaurants.restaurant_id
+            WHERE user_list_restaurants.user_list_id = user_lists.user_list_id AND restaurants.deleted_at IS NULL) AS "restaurant_count!"
+        FROM user_lists
+        WHERE user_lists.share_token = $1"#,
+        share_token
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(user_list)) => to_user_list_details(&state, user_list, current_user_id).await,
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("List not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn create_user_list(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Json(create_user_list_dto): Json<CreateUserList>,
+) -> AppResult<UserList> {
+    let res = sqlx::query_as!(
+        UserList,
+        r#"INSERT INTO user_lists (user_id,name,is_public) VALUES ($1,$2,$3)
+        RETURNING user_list_id,user_id,name,is_public,share_token AS "share_token?",created_at,0::BIGINT AS "restaurant_count!""#,
+        current_user.user_id,
+        create_user_list_dto.name,
+        create_user_list_dto.is_public
+    )
+    .fetch_one(&state.db)
+    .await;
+
+    match res {
+        Ok(user_list) => AppResult::Result(StatusCode::CREATED, user_list),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn update_user_list(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(user_list_id): Path<i32>,
+    Json(update_user_list_dto): Json<UpdateUserList>,
+) -> AppResult<UserList> {
+    let res = sqlx::query_as!(
+        UserList,
+        r#"UPDATE user_lists SET
+        name = COALESCE($3, name),
+        is_public = COALESCE($4, is_public)
+        WHERE user_list_id = $1 AND user_id = $2
+        RETURNING user_list_id,user_id,name,is_public,share_token AS "share_token?",created_at,
+        (SELECT COUNT(*) FROM user_list_restaurants
+            JOIN restaurants ON user_list_restaurants.restaurant_id = restaurants.restaurant_id
+            WHERE user_list_restaurants.user_list_id = $1 AND restaurants.deleted_at IS NULL) AS "restaurant_count!""#,
+        user_list_id,
+        current_user.user_id,
+        update_user_list_dto.name,
+        update_user_list_dto.is_public
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(user_list)) => AppResult::Result(StatusCode::OK, user_list),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("List not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn regenerate_user_list_share_token(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(user_list_id): Path<i32>,
+) -> AppResult<UserList> {
+    let res = sqlx::query_as!(
+        UserList,
+        r#"UPDATE user_lists SET share_token = $3
+        WHERE user_list_id = $1 AND user_id = $2
+        RETURNING user_list_id,user_id,name,is_public,share_token AS "share_token?",created_at,
+        (SELECT COUNT(*) FROM user_list_restaurants
+            JOIN restaurants ON user_list_restaurants.restaurant_id = restaurants.restaurant_id
+            WHERE user_list_restaurants.user_list_id = $1 AND restaurants.deleted_at IS NULL) AS "restaurant_count!""#,
+        user_list_id,
+        current_user.user_id,
+        generate_share_token()
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(user_list)) => AppResult::Result(StatusCode::OK, user_list),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("List not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn revoke_user_list_share_token(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(user_list_id): Path<i32>,
+) -> AppResult<UserList> {
+    let res = sqlx::query_as!(
+        UserList,
+        r#"UPDATE user_lists SET share_token = NULL
+        WHERE user_list_id = $1 AND user_id = $2
+        RETURNING user_list_id,user_id,name,is_public,share_token AS "share_token?",created_at,
+        (SELECT COUNT(*) FROM user_list_restaurants WHERE user_list_restaurants.user_list_id = $1) AS "restaurant_count!""#,
+        user_list_id,
+        current_user.user_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(user_list)) => AppResult::Result(StatusCode::OK, user_list),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("List not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn delete_user_list(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path(user_list_id): Path<i32>,
+) -> AppResult<UserList> {
+    let res = sqlx::query_as!(
+        UserList,
+        r#"DELETE FROM user_lists WHERE user_list_id = $1 AND user_id = $2
+        RETURNING user_list_id,user_id,name,is_public,share_token AS "share_token?",created_at,0::BIGINT AS "restaurant_count!""#,
+        user_list_id,
+        current_user.user_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(user_list)) => AppResult::Result(StatusCode::OK, user_list),
+        Ok(None) => AppResult::Error(StatusCode::NOT_FOUND, String::from("List not found!")),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn add_user_list_restaurant(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((user_list_id, restaurant_id)): Path<(i32, i32)>,
+) -> AppResult<UserListDetails> {
+    match is_restaurant_visible(&state.db, restaurant_id, Some(&current_user)).await {
+        Ok(true) => {}
+        Ok(false) => {
+            return AppResult::Error(StatusCode::NOT_FOUND, String::from("Restaurant not found!"))
+        }
+        Err(_) => {
+            return AppResult::Error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                String::from("Something went wrong"),
+            )
+        }
+    }
+
+    let res = sqlx::query!(
+        "INSERT INTO user_list_restaurants (user_list_id,restaurant_id)
+        SELECT user_lists.user_list_id, restaurants.restaurant_id
+        FROM user_lists, restaurants
+        WHERE user_lists.user_list_id = $1 AND user_lists.user_id = $2
+        AND restaurants.restaurant_id = $3 AND restaurants.deleted_at IS NULL
+        ON CONFLICT (user_list_id, restaurant_id) DO UPDATE SET created_at = user_list_restaurants.created_at
+        RETURNING restaurant_id",
+        user_list_id,
+        current_user.user_id,
+        restaurant_id
+    )
+    .fetch_optional(&state.db)
+    .await;
+
+    match res {
+        Ok(Some(_)) => find_user_list(&state, user_list_id, Some(current_user.user_id)).await,
+        Ok(None) => AppResult::Error(
+            StatusCode::NOT_FOUND,
+            String::from("List or restaurant not found!"),
+        ),
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+pub async fn remove_user_list_restaurant(
+    State(state): State<Arc<AppState>>,
+    Extension(current_user): Extension<Arc<User>>,
+    Path((user_list_id, restaurant_id)): Path<(i32, i32)>,
+) -> AppResult<UserListDetails> {
+    let res = sqlx::query!(
+        "DELETE FROM user_list_restaurants
+        USING user_lists
+        WHERE user_list_restaurants.user_list_id = user_lists.user_list_id
+        AND user_lists.user_list_id = $1 AND user_lists.user_id = $2
+        AND user_list_restaurants.restaurant_id = $3",
+        user_list_id,
+        current_user.user_id,
+        restaurant_id
+    )
+    .execute(&state.db)
+    .await;
+
+    match res {
+        Ok(_) => find_user_list(&state, user_list_id, Some(current_user.user_id)).await,
+        Err(_) => AppResult::Error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            String::from("Something went wrong"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn generate_share_token_is_url_safe() {
+        let token = generate_share_token();
+        assert_eq!(token.len(), 24);
+        assert!(
+            token.chars().all(|c| c.is_ascii_alphanumeric()),
+            "{}",
+            token
+        );
+    }
+
+    #[test]
+    fn generate_share_token_does_not_repeat() {
+        let tokens: HashSet<String> = (0..1000).map(|_| generate_share_token()).collect();
+        assert_eq!(tokens.len(), 1000);
+    }
+}
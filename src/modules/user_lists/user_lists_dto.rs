@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::modules::restaurants::restaurants_dto::RestaurantUser;
+
+#[derive(Deserialize)]
+pub struct CreateUserList {
+    pub name: String,
+    #[serde(default)]
+    pub is_public: bool,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateUserList {
+    pub name: Option<String>,
+    pub is_public: Option<bool>,
+}
+
+#[derive(Serialize, FromRow)]
+pub struct UserList {
+    pub user_list_id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub is_public: bool,
+    pub share_token: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub restaurant_count: i64,
+}
+
+#[derive(Serialize)]
+pub struct UserListDetails {
+    #[serde(flatten)]
+    pub user_list: UserList,
+    pub restaurants: Vec<RestaurantUser>,
+}